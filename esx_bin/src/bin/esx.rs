@@ -0,0 +1,29 @@
+// Usage: esx [--format text|ron|json] <command> [args]
+// Run `esx help` for the list of commands, or `esx help <command>` for their arguments
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use esx_bin::{commands::Command, output::Format};
+
+/// Inspect and analyse Bethesda plugin files
+#[derive(Debug, Parser)]
+#[command(name = "esx", version)]
+struct Cli {
+  /// How results are written to stdout
+  #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+  format: Format,
+  #[command(subcommand)]
+  command: Command,
+}
+
+fn main() -> ExitCode {
+  let cli = Cli::parse();
+  match cli.command.run(cli.format) {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(e) => {
+      eprintln!("Error: {:?}", e);
+      ExitCode::FAILURE
+    }
+  }
+}
@@ -0,0 +1,64 @@
+//! The subcommands of the `esx` binary. Each writes its results to stdout in the chosen
+//! [`Format`], and processing diagnostics to stderr.
+
+use std::path::Path;
+
+use clap::Subcommand;
+use esx_lib::{options::ProcessOptions, ESx};
+
+use crate::{error::Result, load, output::Format};
+
+pub mod corpus;
+pub mod dump;
+pub mod field_analysis;
+pub mod fingerprint;
+pub mod query;
+pub mod quests;
+pub mod record_stats;
+pub mod stat_report;
+pub mod top_groups;
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+  /// Write a plugin to a RON file, or a JSON file with `--format json`
+  Dump(dump::DumpArgs),
+  /// List the top groups of a plugin and their sizes
+  TopGroups(top_groups::TopGroupsArgs),
+  /// Count the records of each type and form version, and the fields in them
+  RecordStats(record_stats::RecordStatsArgs),
+  /// Summarise the fields of each record type and form version
+  StatReport(stat_report::StatReportArgs),
+  /// Combine the stat reports of a directory of plugins or a load order
+  Corpus(corpus::CorpusArgs),
+  /// Dump one type of field from the records of a type or matching a query
+  FieldAnalysis(field_analysis::FieldAnalysisArgs),
+  /// Fingerprint the fields of each record type and form version
+  Fingerprint(fingerprint::FingerprintArgs),
+  /// List the records matching a query
+  Query(query::QueryArgs),
+  /// Export the quests of a plugin, as Markdown for text
+  Quests(quests::QuestsArgs),
+}
+
+impl Command {
+  pub fn run(&self, format: Format) -> Result<()> {
+    match self {
+      Command::Dump(args) => dump::run(args, format),
+      Command::TopGroups(args) => top_groups::run(args, format),
+      Command::RecordStats(args) => record_stats::run(args, format),
+      Command::StatReport(args) => stat_report::run(args, format),
+      Command::Corpus(args) => corpus::run(args, format),
+      Command::FieldAnalysis(args) => field_analysis::run(args, format),
+      Command::Fingerprint(args) => fingerprint::run(args, format),
+      Command::Query(args) => query::run(args, format),
+      Command::Quests(args) => quests::run(args, format),
+    }
+  }
+}
+
+/// Loads and processes a plugin, and prints a summary of the diagnostics
+fn load_processed<P: AsRef<Path>>(path: P, options: &ProcessOptions) -> Result<ESx> {
+  let mut esx = load::from_file(path)?;
+  crate::diagnostics::print_summary(&esx.process_with_options(options));
+  Ok(esx)
+}
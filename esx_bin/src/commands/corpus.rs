@@ -0,0 +1,95 @@
+//! Stat reports combined across a corpus of plugins, by game, record type and form version, with
+//! the plugins each layout and field was found in. Plugins that fail to load are listed rather
+//! than stopping the scan.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::{
+  error::Result,
+  load,
+  output::Format,
+  statistics::{corpus::CorpusReport, StatReport},
+};
+
+#[derive(Debug, Args)]
+pub struct CorpusArgs {
+  /// A directory to search for plugins, including its subdirectories, or a load order file
+  /// such as `plugins.txt`
+  path: PathBuf,
+  /// The directory holding the plugins of a load order, by default the load order's directory
+  #[arg(long)]
+  data: Option<PathBuf>,
+}
+
+pub fn run(args: &CorpusArgs, format: Format) -> Result<()> {
+  let (base, paths) = match args.path.is_dir() {
+    true => (args.path.clone(), load::plugin_paths(&args.path)?),
+    false => {
+      let data = match &args.data {
+        Some(data) => data.clone(),
+        None => args.path.parent().map(PathBuf::from).unwrap_or_default(),
+      };
+      let paths = load::load_order(&args.path, &data)?;
+      (data, paths)
+    }
+  };
+
+  let mut corpus = CorpusReport::new();
+  for (i, path) in paths.iter().enumerate() {
+    let name = path
+      .strip_prefix(&base)
+      .unwrap_or(path)
+      .display()
+      .to_string();
+    eprintln!("[{}/{}] {}", i + 1, paths.len(), name);
+    match load::from_file(path) {
+      Ok(esx) => corpus.add_report(&name, &StatReport::new(esx)),
+      Err(e) => {
+        eprintln!("Error loading {}: {:?}", name, e);
+        corpus.add_failure(&name, format!("{:?}", e));
+      }
+    }
+  }
+
+  format.print(&corpus, |corpus| {
+    println!(
+      "Plugins: {} ({} failed)",
+      corpus.plugins().len(),
+      corpus.failures().len()
+    );
+    for (plugin, error) in corpus.failures() {
+      println!("Failed: {}: {}", plugin, error);
+    }
+    for layout in corpus.layouts() {
+      let game = match layout.game() {
+        Some(game) => game.to_string(),
+        None => "Unknown".to_string(),
+      };
+      let records = layout.layout();
+      println!(
+        "{} {} v{}: {} records from {} plugins",
+        game,
+        layout.signature(),
+        layout.form_version(),
+        records.total_count(),
+        layout.plugins().len()
+      );
+      for (field, lengths) in records.field_lengths() {
+        let plugins = layout.field_plugins().get(field).map_or(0, |p| p.len());
+        println!(
+          "\tField: {} ( |{}|{}|{}| ) Length: ( |{}|{:.1}|{}| ) in {} plugins",
+          field,
+          records.field_min(field),
+          records.field_mean(field),
+          records.field_max(field),
+          lengths.min(),
+          lengths.mean(),
+          lengths.max(),
+          plugins
+        );
+      }
+    }
+  })
+}
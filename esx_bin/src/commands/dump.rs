@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use esx_lib::options::ProcessOptions;
+
+use crate::{error::Result, output::Format, save};
+
+#[derive(Debug, Args)]
+pub struct DumpArgs {
+  /// The plugin to dump
+  file: PathBuf,
+  /// The file to write
+  output: PathBuf,
+}
+
+pub fn run(args: &DumpArgs, format: Format) -> Result<()> {
+  let esx = super::load_processed(&args.file, &ProcessOptions::new())?;
+  match format {
+    Format::Json => save::to_json_file(&esx, &args.output),
+    Format::Text | Format::Ron => save::to_ron_file(&esx, &args.output),
+  }
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use esx_lib::{options::ProcessOptions, query::Query, types::FormID, Field};
+use ron::ser::PrettyConfig;
+
+use crate::{error::Result, output::Format};
+
+/// Either `--record` or `--query` selects the records to search
+#[derive(Debug, Args)]
+pub struct FieldAnalysisArgs {
+  /// The plugin to search
+  #[arg(long)]
+  file: PathBuf,
+  /// The signature of the records to search
+  #[arg(long, required_unless_present = "query", conflicts_with = "query")]
+  record: Option<String>,
+  /// A query selecting the records to search, such as `NPC_ where flags.Essential`
+  #[arg(long)]
+  query: Option<String>,
+  /// The signature of the fields to dump
+  #[arg(long)]
+  field: String,
+  /// Only search records of this form version
+  #[arg(long)]
+  form_version: Option<u16>,
+}
+
+pub fn run(args: &FieldAnalysisArgs, format: Format) -> Result<()> {
+  // A record signature on its own is a query for every record of that type
+  let query: Query = match (&args.query, &args.record) {
+    (Some(query), _) | (None, Some(query)) => query.parse()?,
+    (None, None) => unreachable!("clap requires --record or --query"),
+  };
+  let options = ProcessOptions {
+    signatures: query.get_signatures().map(<[_]>::to_vec),
+    ..Default::default()
+  };
+  let esx = super::load_processed(&args.file, &options)?;
+  let mut records = esx.query(&query);
+  if let Some(form_version) = args.form_version {
+    records.retain(|x| *x.get_form_version() == form_version);
+  }
+
+  let mut fields: Vec<(&FormID, Vec<&Field>)> = vec![];
+  for rec in records {
+    let rec_fields: Vec<&Field> = rec
+      .get_data()
+      .get_fields()
+      .into_iter()
+      .filter(|x| x.get_signature().as_string() == args.field)
+      .collect();
+    if !rec_fields.is_empty() {
+      fields.push((rec.get_form_id(), rec_fields));
+    }
+  }
+
+  match format.serialize(&fields)? {
+    Some(output) => println!("{}", output),
+    None => {
+      let fields = ron::ser::to_string_pretty(&fields, PrettyConfig::default())?;
+      println!("Fields: {}", fields);
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,50 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use clap::Args;
+use esx_lib::{options::ProcessOptions, types::Signature, Field};
+
+use crate::{
+  error::Result,
+  output::Format,
+  statistics::fingerprinting::{FieldFingerprint, FieldFingerprinting},
+};
+
+#[derive(Debug, Args)]
+pub struct FingerprintArgs {
+  /// The plugin to fingerprint
+  file: PathBuf,
+}
+
+pub fn run(args: &FingerprintArgs, format: Format) -> Result<()> {
+  let esx = super::load_processed(&args.file, &ProcessOptions::new())?;
+
+  let mut fields_by_signature_path: BTreeMap<(Signature, u16, Signature), Vec<&Field>> =
+    BTreeMap::new();
+  for record in esx.get_all_records() {
+    let rec_sig = *record.get_signature();
+    let rec_ver = *record.get_form_version();
+    for field in record.get_data().get_fields() {
+      let field_path = (rec_sig, rec_ver, *field.get_signature());
+      fields_by_signature_path
+        .entry(field_path)
+        .or_default()
+        .push(field);
+    }
+  }
+
+  let mut fingerprints: BTreeMap<String, FieldFingerprint> = BTreeMap::new();
+  for (path, fields) in fields_by_signature_path {
+    let path_str = path.0.as_string() + ":" + &path.1.to_string() + " -> " + &path.2.as_string();
+    fingerprints.insert(path_str, fields.fingerprint());
+  }
+
+  // Fingerprints have no plainer form than RON, so text is RON
+  let format = match format {
+    Format::Text => Format::Ron,
+    format => format,
+  };
+  if let Some(output) = format.serialize(&fingerprints)? {
+    println!("{}", output);
+  }
+  Ok(())
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use esx_lib::{
+  options::ProcessOptions,
+  query::Query,
+  types::{FormID, Signature},
+};
+use serde::Serialize;
+
+use crate::{error::Result, output::Format};
+
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+  /// The plugin to search
+  file: PathBuf,
+  /// The query, such as `WEAP where EDID ~ "Iron.*" and DATA.damage > 10`
+  query: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Match {
+  signature: Signature,
+  form_id: FormID,
+  editor_id: Option<String>,
+}
+
+pub fn run(args: &QueryArgs, format: Format) -> Result<()> {
+  let query: Query = args.query.parse()?;
+  let options = ProcessOptions {
+    signatures: query.get_signatures().map(<[_]>::to_vec),
+    ..Default::default()
+  };
+  let esx = super::load_processed(&args.file, &options)?;
+
+  let matches: Vec<Match> = esx
+    .query(&query)
+    .into_iter()
+    .map(|record| Match {
+      signature: *record.get_signature(),
+      form_id: *record.get_form_id(),
+      editor_id: record
+        .read_field(Signature::new(b"EDID"))
+        .map(|f| f.get_zstring()),
+    })
+    .collect();
+  format.print(&matches, |matches| {
+    for m in matches {
+      println!(
+        "{} {} {}",
+        m.signature,
+        m.form_id,
+        m.editor_id.as_deref().unwrap_or_default()
+      );
+    }
+    println!("{} matching records", matches.len());
+  })
+}
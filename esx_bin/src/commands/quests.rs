@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use esx_lib::{
+  options::ProcessOptions,
+  quest::{AliasKind, Quest},
+};
+
+use crate::{error::Result, output::Format};
+
+#[derive(Debug, Args)]
+pub struct QuestsArgs {
+  /// The plugin to export
+  file: PathBuf,
+}
+
+pub fn run(args: &QuestsArgs, format: Format) -> Result<()> {
+  let esx = super::load_processed(&args.file, &ProcessOptions::new())?;
+  let quests = esx.get_quests()?;
+  format.print(&quests, |quests| {
+    for quest in quests {
+      print!("{}", to_markdown(quest));
+    }
+  })
+}
+
+fn to_markdown(quest: &Quest) -> String {
+  let mut out = format!(
+    "# {} ({})\n\n",
+    quest.editor_id.as_deref().unwrap_or("Unnamed quest"),
+    quest.form_id
+  );
+  if let Some(name) = &quest.name {
+    out += &format!("**{}**\n\n", name);
+  }
+
+  if !quest.stages.is_empty() {
+    out += "## Stages\n\n";
+    for stage in &quest.stages {
+      out += &format!("- **{}** (flags {:#04x})\n", stage.index, stage.flags);
+      for entry in &stage.log_entries {
+        out += &format!("  - {}", entry.text.as_deref().unwrap_or("*no log entry*"));
+        if entry.condition_count > 0 {
+          out += &format!(" — {} conditions", entry.condition_count);
+        }
+        if let Some(next) = entry.next_quest {
+          out += &format!(" — starts {}", next);
+        }
+        out += "\n";
+      }
+    }
+    out += "\n";
+  }
+
+  if !quest.objectives.is_empty() {
+    out += "## Objectives\n\n";
+    for objective in &quest.objectives {
+      out += &format!(
+        "- **{}** {}",
+        objective.index,
+        objective.text.as_deref().unwrap_or("")
+      );
+      if !objective.target_aliases.is_empty() {
+        out += &format!(" — targets aliases {:?}", objective.target_aliases);
+      }
+      out += "\n";
+    }
+    out += "\n";
+  }
+
+  if !quest.aliases.is_empty() {
+    out += "## Aliases\n\n";
+    for alias in &quest.aliases {
+      let kind = match alias.kind {
+        AliasKind::Reference => "reference",
+        AliasKind::Location => "location",
+      };
+      out += &format!(
+        "- {} {}: {}\n",
+        kind,
+        alias.id,
+        alias.name.as_deref().unwrap_or("")
+      );
+    }
+    out += "\n";
+  }
+
+  if !quest.scenes.is_empty() {
+    out += "## Scenes\n\n";
+    for scene in &quest.scenes {
+      out += &format!(
+        "- {} {}\n",
+        scene.form_id,
+        scene.editor_id.as_deref().unwrap_or("")
+      );
+    }
+    out += "\n";
+  }
+  out
+}
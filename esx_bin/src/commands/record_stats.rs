@@ -0,0 +1,77 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  path::PathBuf,
+};
+
+use clap::Args;
+use esx_lib::{options::ProcessOptions, types::Signature, Record};
+use serde::Serialize;
+
+use crate::{
+  error::Result,
+  output::{signature_keys, Format},
+  statistics::{get_signatures, RecordTypeLayout},
+};
+
+#[derive(Debug, Args)]
+pub struct RecordStatsArgs {
+  /// The plugin to count
+  file: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+struct RecordStats<'a> {
+  header: &'a Record,
+  records: usize,
+  signatures: BTreeSet<Signature>,
+  /// The number of records of each form version, by signature
+  #[serde(serialize_with = "signature_keys")]
+  form_version_counts: BTreeMap<Signature, BTreeMap<u16, usize>>,
+  #[serde(serialize_with = "signature_keys")]
+  layouts: BTreeMap<Signature, RecordTypeLayout>,
+}
+
+pub fn run(args: &RecordStatsArgs, format: Format) -> Result<()> {
+  let esx = super::load_processed(&args.file, &ProcessOptions::new())?;
+  let records = esx.get_all_records();
+
+  let mut stats = RecordStats {
+    header: esx.get_header_record(),
+    records: records.len(),
+    signatures: get_signatures(&records),
+    form_version_counts: BTreeMap::new(),
+    layouts: BTreeMap::new(),
+  };
+  for record in &records {
+    let signature = *record.get_signature();
+    *stats
+      .form_version_counts
+      .entry(signature)
+      .or_default()
+      .entry(*record.get_form_version())
+      .or_default() += 1;
+    stats
+      .layouts
+      .entry(signature)
+      .or_default()
+      .process_record(record);
+  }
+
+  format.print(&stats, |stats| {
+    println!("Header {:#?}", stats.header);
+    println!("Records: {:#?}", stats.records);
+    println!("Signatures {:#?}", stats.signatures);
+
+    println!("Form Version Counts {{");
+    for (signature, counts) in &stats.form_version_counts {
+      println!("\t{}: {{", signature);
+      for (form_version, count) in counts {
+        println!("\t\t{}: {}", form_version, count);
+      }
+      println!("\t}}")
+    }
+    println!("}}");
+
+    println!("Record Layouts {:#?}", stats.layouts);
+  })
+}
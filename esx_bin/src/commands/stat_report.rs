@@ -0,0 +1,46 @@
+//! A statistical report of the records and fields of a plugin. Records are grouped by signature
+//! and form version, with the minimum, mean and maximum number of each field they contain. Each
+//! field also has the minimum, mean and maximum length of its data. `--format ron` or
+//! `--format json` writes the full report.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::{error::Result, load, output::Format, statistics::StatReport};
+
+#[derive(Debug, Args)]
+pub struct StatReportArgs {
+  /// The plugin to report on
+  file: PathBuf,
+}
+
+pub fn run(args: &StatReportArgs, format: Format) -> Result<()> {
+  let esx = load::from_file(&args.file)?;
+  let stats = StatReport::new(esx);
+  crate::diagnostics::print_summary(stats.diagnostics());
+
+  format.print(&stats, |stats| {
+    println!("Signatures: {:?}", stats.signatures());
+    println!("Form Versions: {:?}", stats.form_versions());
+    for (sig, versions) in stats.record_data_layouts() {
+      println!("Signature: {}", sig);
+      for (version, layout) in versions {
+        println!("\tVersion: {}", version);
+        println!("\t\tTotal: {}", layout.total_count());
+        for (field, lengths) in layout.field_lengths() {
+          println!(
+            "\t\tField: {} ( |{}|{}|{}| ) Length: ( |{}|{:.1}|{}| )",
+            field,
+            layout.field_min(field),
+            layout.field_mean(field),
+            layout.field_max(field),
+            lengths.min(),
+            lengths.mean(),
+            lengths.max()
+          );
+        }
+      }
+    }
+  })
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use esx_lib::{group::GroupLabel, options::ProcessOptions, types::Signature, Record};
+use serde::Serialize;
+
+use crate::{error::Result, output::Format};
+
+#[derive(Debug, Args)]
+pub struct TopGroupsArgs {
+  /// The plugin to list
+  file: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+struct TopGroups<'a> {
+  header: &'a Record,
+  groups: Vec<TopGroup>,
+}
+#[derive(Debug, Serialize)]
+struct TopGroup {
+  signature: Signature,
+  /// The serialized size of the group's contents
+  size: usize,
+}
+
+pub fn run(args: &TopGroupsArgs, format: Format) -> Result<()> {
+  let options = ProcessOptions::new().with_top_groups_only(true);
+  let esx = super::load_processed(&args.file, &options)?;
+
+  let mut groups = vec![];
+  for group in esx.get_top_groups() {
+    if let GroupLabel::Top(signature) = group.get_label() {
+      groups.push(TopGroup {
+        signature: *signature,
+        size: group.get_data().serialized_size()?,
+      });
+    }
+  }
+  let top_groups = TopGroups {
+    header: esx.get_header_record(),
+    groups,
+  };
+  format.print(&top_groups, |top_groups| {
+    println!("Header: {:#?}", top_groups.header);
+    for group in &top_groups.groups {
+      println!("{} - Len: {:0x?}", group.signature.as_string(), group.size);
+    }
+  })
+}
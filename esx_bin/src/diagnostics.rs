@@ -0,0 +1,20 @@
+use std::collections::BTreeMap;
+
+use esx_lib::{diagnostics::Severity, Diagnostic};
+
+/// Print each processing diagnostic and a count per severity to stderr
+pub fn print_summary(diagnostics: &[Diagnostic]) {
+  if diagnostics.is_empty() {
+    return;
+  }
+  let mut counts: BTreeMap<Severity, usize> = BTreeMap::new();
+  for diagnostic in diagnostics {
+    eprintln!("{}", diagnostic);
+    *counts.entry(*diagnostic.get_severity()).or_default() += 1;
+  }
+  let counts: Vec<String> = counts
+    .iter()
+    .map(|(severity, count)| format!("{} {}(s)", count, severity))
+    .collect();
+  eprintln!("Processing finished with {}", counts.join(", "));
+}
@@ -5,6 +5,7 @@ pub enum Error {
   Io(std::io::Error),
   Ron(ron::Error),
   SpannedRon(ron::error::SpannedError),
+  Json(serde_json::Error),
   Esx(esx_lib::Error),
 }
 
@@ -23,6 +24,11 @@ impl From<ron::error::SpannedError> for Error {
     Error::SpannedRon(err)
   }
 }
+impl From<serde_json::Error> for Error {
+  fn from(err: serde_json::Error) -> Self {
+    Error::Json(err)
+  }
+}
 impl From<esx_lib::Error> for Error {
   fn from(err: esx_lib::Error) -> Self {
     Error::Esx(err)
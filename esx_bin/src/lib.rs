@@ -1,17 +1,24 @@
+pub mod commands;
+pub mod diagnostics;
 pub mod error;
+pub mod output;
 pub mod statistics;
 
 pub use error::{Error, Result};
 pub mod load {
-  use std::fs::File;
-  use std::path::Path;
+  use std::fs::{self, File};
+  use std::path::{Path, PathBuf};
 
   use crate::error::Result;
-  use esx_lib::esx::ESx;
+  use esx_lib::{esx::ESx, options::ParseMode};
 
   pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ESx> {
+    from_file_with_mode(path, ParseMode::Strict)
+  }
+
+  pub fn from_file_with_mode<P: AsRef<Path>>(path: P, mode: ParseMode) -> Result<ESx> {
     let file = File::open(path)?;
-    let esx = ESx::from_file(&file)?;
+    let esx = ESx::from_file_with_mode(&file, mode)?;
 
     Ok(esx)
   }
@@ -22,6 +29,48 @@ pub mod load {
 
     Ok(esx)
   }
+
+  /// Whether the path has a plugin extension: `.esp`, `.esm` or `.esl`
+  pub fn is_plugin<P: AsRef<Path>>(path: P) -> bool {
+    let Some(extension) = path.as_ref().extension().and_then(|e| e.to_str()) else {
+      return false;
+    };
+    ["esp", "esm", "esl"]
+      .iter()
+      .any(|e| extension.eq_ignore_ascii_case(e))
+  }
+
+  /// Every plugin in the directory and its subdirectories, sorted by path
+  pub fn plugin_paths<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    let mut dirs = vec![dir.as_ref().to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+      for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+          dirs.push(path);
+        } else if is_plugin(&path) {
+          paths.push(path);
+        }
+      }
+    }
+    paths.sort();
+    Ok(paths)
+  }
+
+  /// The plugins of a load order file such as `plugins.txt`, in order and joined to `data`.
+  /// Blank lines and lines starting with `#` are skipped, and the `*` marking active plugins
+  /// is ignored.
+  pub fn load_order<P: AsRef<Path>, Q: AsRef<Path>>(file: P, data: Q) -> Result<Vec<PathBuf>> {
+    let text = fs::read_to_string(file)?;
+    let paths = text
+      .lines()
+      .map(|line| line.trim().trim_start_matches('*'))
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(|name| data.as_ref().join(name))
+      .collect();
+    Ok(paths)
+  }
 }
 
 pub mod save {
@@ -32,10 +81,24 @@ pub mod save {
   use esx_lib::esx::ESx;
   use ron::ser::PrettyConfig;
 
+  pub fn to_file<P: AsRef<Path>>(esx: &ESx, path: P) -> Result<()> {
+    let file = File::create(path)?;
+    esx.try_write(&file)?;
+
+    Ok(())
+  }
+
   pub fn to_ron_file<P: AsRef<Path>>(esx: &ESx, path: P) -> Result<()> {
     let file = File::create(path)?;
     ron::ser::to_writer_pretty(file, esx, PrettyConfig::default())?;
 
     Ok(())
   }
+
+  pub fn to_json_file<P: AsRef<Path>>(esx: &ESx, path: P) -> Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(file, esx)?;
+
+    Ok(())
+  }
 }
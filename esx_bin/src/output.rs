@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use esx_lib::types::Signature;
+use ron::ser::PrettyConfig;
+use serde::{Serialize, Serializer};
+
+use crate::error::Result;
+
+/// How a command writes its results to stdout
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+  /// Human readable text, specific to each command
+  #[default]
+  Text,
+  Ron,
+  Json,
+}
+
+impl Format {
+  /// The value as pretty RON or JSON, or `None` for text, which each command writes itself
+  pub fn serialize<T: Serialize + ?Sized>(&self, value: &T) -> Result<Option<String>> {
+    let output = match self {
+      Format::Text => return Ok(None),
+      Format::Ron => ron::ser::to_string_pretty(value, PrettyConfig::default())?,
+      Format::Json => serde_json::to_string_pretty(value)?,
+    };
+    Ok(Some(output))
+  }
+  /// Prints the value as RON or JSON, or calls `text` to print it as text
+  pub fn print<T: Serialize + ?Sized>(&self, value: &T, text: impl FnOnce(&T)) -> Result<()> {
+    match self.serialize(value)? {
+      Some(output) => println!("{}", output),
+      None => text(value),
+    }
+    Ok(())
+  }
+}
+
+/// Serializes a map keyed by signatures with the signatures as strings, since JSON keys must be
+/// strings. Use with `#[serde(serialize_with = "signature_keys")]`.
+pub fn signature_keys<V: Serialize, S: Serializer>(
+  map: &BTreeMap<Signature, V>,
+  serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+  serializer.collect_map(map.iter().map(|(k, v)| (k.to_string(), v)))
+}
@@ -1,25 +1,87 @@
 use std::{
   collections::{BTreeMap, BTreeSet, HashSet},
   ops::AddAssign,
-  usize,
 };
 
-use esx_lib::{types::Signature, ESx, Record};
+use esx_lib::{types::Signature, Diagnostic, ESx, Record};
+use serde::Serialize;
 
+use crate::output::signature_keys;
+
+pub mod corpus;
 pub mod fingerprinting;
 
-#[derive(Debug, Default)]
+/// The number of values added, such as field lengths, and their minimum, maximum and mean
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ValueStats {
+  count: usize,
+  min: usize,
+  max: usize,
+  mean: f64,
+  #[serde(skip)]
+  total: usize,
+}
+impl ValueStats {
+  pub fn count(&self) -> &usize {
+    &self.count
+  }
+  pub fn min(&self) -> &usize {
+    &self.min
+  }
+  pub fn max(&self) -> &usize {
+    &self.max
+  }
+  pub fn mean(&self) -> &f64 {
+    &self.mean
+  }
+  pub fn total(&self) -> &usize {
+    &self.total
+  }
+}
+impl ValueStats {
+  pub fn add(&mut self, length: usize) {
+    if self.count == 0 || length < self.min {
+      self.min = length;
+    }
+    self.max = self.max.max(length);
+    self.count += 1;
+    self.total += length;
+    self.mean = self.total as f64 / self.count as f64;
+  }
+  pub fn merge(&mut self, other: &ValueStats) {
+    if other.count == 0 {
+      return;
+    }
+    if self.count == 0 || other.min < self.min {
+      self.min = other.min;
+    }
+    self.max = self.max.max(other.max);
+    self.count += other.count;
+    self.total += other.total;
+    self.mean = self.total as f64 / self.count as f64;
+  }
+}
+
+#[derive(Debug, Default, Serialize)]
 pub struct RecordTypeLayout {
   total_count: usize,
-  fields: BTreeMap<Signature, Vec<usize>>,
+  /// How many of each field the records that have it hold
+  #[serde(serialize_with = "signature_keys")]
+  fields: BTreeMap<Signature, ValueStats>,
+  /// The lengths of the data of every field
+  #[serde(serialize_with = "signature_keys")]
+  field_lengths: BTreeMap<Signature, ValueStats>,
 }
 impl RecordTypeLayout {
   pub fn total_count(&self) -> &usize {
     &self.total_count
   }
-  pub fn fields(&self) -> &BTreeMap<Signature, Vec<usize>> {
+  pub fn fields(&self) -> &BTreeMap<Signature, ValueStats> {
     &self.fields
   }
+  pub fn field_lengths(&self) -> &BTreeMap<Signature, ValueStats> {
+    &self.field_lengths
+  }
 }
 impl RecordTypeLayout {
   pub fn new() -> Self {
@@ -31,9 +93,25 @@ impl RecordTypeLayout {
     for field in record.get_data().get_fields() {
       let field_sig = *field.get_signature();
       record_fields.entry(field_sig).or_insert(0).add_assign(1);
+      self
+        .field_lengths
+        .entry(field_sig)
+        .or_default()
+        .add(field.len());
     }
     for (sig, count) in record_fields {
-      self.fields.entry(sig).or_default().push(count);
+      self.fields.entry(sig).or_default().add(count);
+    }
+  }
+
+  /// Adds the records of another layout of the same record type and form version
+  pub fn merge(&mut self, other: &RecordTypeLayout) {
+    self.total_count += other.total_count;
+    for (sig, counts) in &other.fields {
+      self.fields.entry(*sig).or_default().merge(counts);
+    }
+    for (sig, lengths) in &other.field_lengths {
+      self.field_lengths.entry(*sig).or_default().merge(lengths);
     }
   }
 
@@ -41,29 +119,28 @@ impl RecordTypeLayout {
     let Some(field) = self.fields.get(sig) else {
       return false;
     };
-    field.len() == self.total_count
+    *field.count() == self.total_count
   }
   pub fn field_mean(&self, sig: &Signature) -> f64 {
     let Some(field) = self.fields.get(sig) else {
       return 0.0;
     };
-    let sum: usize = field.iter().sum();
-    sum as f64 / self.total_count as f64
+    *field.total() as f64 / self.total_count as f64
   }
   pub fn field_min(&self, sig: &Signature) -> usize {
     let Some(field) = self.fields.get(sig) else {
       return 0;
     };
-    if field.len() != self.total_count {
+    if *field.count() != self.total_count {
       return 0;
     }
-    *field.iter().min().unwrap()
+    *field.min()
   }
   pub fn field_max(&self, field: &Signature) -> usize {
     let Some(field) = self.fields.get(field) else {
       return 0;
     };
-    *field.iter().max().unwrap()
+    *field.max()
   }
 }
 
@@ -83,13 +160,15 @@ pub fn get_form_versions(records: &Vec<&Record>) -> BTreeSet<u16> {
   form_versions
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct StatReport {
   header: Record,
   record_count: usize,
   signatures: BTreeSet<Signature>,
   form_versions: BTreeSet<u16>,
+  #[serde(serialize_with = "signature_keys")]
   record_data_layouts: BTreeMap<Signature, BTreeMap<u16, RecordTypeLayout>>,
+  diagnostics: Vec<Diagnostic>,
 }
 impl StatReport {
   pub fn header(&self) -> &Record {
@@ -107,6 +186,9 @@ impl StatReport {
   pub fn record_data_layouts(&self) -> &BTreeMap<Signature, BTreeMap<u16, RecordTypeLayout>> {
     &self.record_data_layouts
   }
+  pub fn diagnostics(&self) -> &Vec<Diagnostic> {
+    &self.diagnostics
+  }
 }
 impl StatReport {
   pub fn new(mut esx: ESx) -> Self {
@@ -116,8 +198,9 @@ impl StatReport {
       signatures: BTreeSet::new(),
       form_versions: BTreeSet::new(),
       record_data_layouts: BTreeMap::new(),
+      diagnostics: vec![],
     };
-    esx.process();
+    report.diagnostics = esx.process();
 
     let records = esx.get_all_records();
     report.process_records(records);
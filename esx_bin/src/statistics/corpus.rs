@@ -0,0 +1,128 @@
+//! Record layouts combined across many plugins, to compare how each record type and form version
+//! is laid out across a whole corpus of mods.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use esx_lib::types::{Game, Signature};
+use serde::{Serialize, Serializer};
+
+use super::{RecordTypeLayout, StatReport};
+use crate::output::signature_keys;
+
+/// The records of one record type and form version across the corpus, for one game
+#[derive(Debug, Serialize)]
+pub struct CorpusLayout {
+  game: Option<Game>,
+  signature: Signature,
+  form_version: u16,
+  layout: RecordTypeLayout,
+  /// The plugins with records of this layout
+  plugins: BTreeSet<String>,
+  /// The plugins with each field in records of this layout
+  #[serde(serialize_with = "signature_keys")]
+  field_plugins: BTreeMap<Signature, BTreeSet<String>>,
+}
+impl CorpusLayout {
+  pub fn game(&self) -> &Option<Game> {
+    &self.game
+  }
+  pub fn signature(&self) -> &Signature {
+    &self.signature
+  }
+  pub fn form_version(&self) -> &u16 {
+    &self.form_version
+  }
+  pub fn layout(&self) -> &RecordTypeLayout {
+    &self.layout
+  }
+  pub fn plugins(&self) -> &BTreeSet<String> {
+    &self.plugins
+  }
+  pub fn field_plugins(&self) -> &BTreeMap<Signature, BTreeSet<String>> {
+    &self.field_plugins
+  }
+}
+
+type LayoutKey = (Option<Game>, Signature, u16);
+
+#[derive(Debug, Default, Serialize)]
+pub struct CorpusReport {
+  /// Every plugin added, in order
+  plugins: Vec<String>,
+  /// Plugins that could not be loaded, with the error
+  failures: BTreeMap<String, String>,
+  /// The number of processing diagnostics of each plugin that had any
+  diagnostics: BTreeMap<String, usize>,
+  #[serde(serialize_with = "values")]
+  layouts: BTreeMap<LayoutKey, CorpusLayout>,
+}
+impl CorpusReport {
+  pub fn plugins(&self) -> &Vec<String> {
+    &self.plugins
+  }
+  pub fn failures(&self) -> &BTreeMap<String, String> {
+    &self.failures
+  }
+  pub fn diagnostics(&self) -> &BTreeMap<String, usize> {
+    &self.diagnostics
+  }
+  /// The layouts sorted by game, signature and form version
+  pub fn layouts(&self) -> impl Iterator<Item = &CorpusLayout> {
+    self.layouts.values()
+  }
+}
+impl CorpusReport {
+  pub fn new() -> Self {
+    CorpusReport::default()
+  }
+  /// Adds the layouts of a plugin's report, under the game its header belongs to
+  pub fn add_report(&mut self, plugin: &str, report: &StatReport) {
+    self.plugins.push(plugin.to_string());
+    if !report.diagnostics().is_empty() {
+      self
+        .diagnostics
+        .insert(plugin.to_string(), report.diagnostics().len());
+    }
+
+    let header = report.header();
+    let hedr_version = header
+      .read_field(Signature::new(b"HEDR"))
+      .and_then(|f| f.get_f32());
+    let game = Game::from_header(*header.get_form_version(), hedr_version);
+    for (signature, versions) in report.record_data_layouts() {
+      for (form_version, layout) in versions {
+        let key = (game, *signature, *form_version);
+        let corpus_layout = self.layouts.entry(key).or_insert_with(|| CorpusLayout {
+          game,
+          signature: *signature,
+          form_version: *form_version,
+          layout: RecordTypeLayout::new(),
+          plugins: BTreeSet::new(),
+          field_plugins: BTreeMap::new(),
+        });
+        corpus_layout.layout.merge(layout);
+        corpus_layout.plugins.insert(plugin.to_string());
+        for field in layout.fields().keys() {
+          corpus_layout
+            .field_plugins
+            .entry(*field)
+            .or_default()
+            .insert(plugin.to_string());
+        }
+      }
+    }
+  }
+  /// Records a plugin that could not be loaded
+  pub fn add_failure(&mut self, plugin: &str, error: String) {
+    self.plugins.push(plugin.to_string());
+    self.failures.insert(plugin.to_string(), error);
+  }
+}
+
+/// Serializes the layouts as a list, as their keys are repeated in each layout
+fn values<S: Serializer>(
+  layouts: &BTreeMap<LayoutKey, CorpusLayout>,
+  serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+  serializer.collect_seq(layouts.values())
+}
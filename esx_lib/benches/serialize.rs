@@ -0,0 +1,153 @@
+//! Serialization of a large synthetic worldspace, comparing the single-pass writer with copying
+//! each group's contents into its parent as `as_bytes` used to.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use esx_lib::{
+  group::{GroupData, GroupDataComponent},
+  ESx, Group,
+};
+
+const BLOCKS: u32 = 4;
+const SUB_BLOCKS: u32 = 4;
+const CELLS: u32 = 8;
+const REFERENCES: u32 = 128;
+
+fn record(buf: &mut BytesMut, signature: &[u8; 4], form_id: u32, fields: &[(&[u8; 4], &[u8])]) {
+  let size: usize = fields.iter().map(|(_, data)| 6 + data.len()).sum();
+  buf.put_slice(signature);
+  buf.put_u32_le(size as u32);
+  buf.put_u32_le(0);
+  buf.put_u32_le(form_id);
+  buf.put_u32_le(0);
+  buf.put_u16_le(131);
+  buf.put_u16_le(0);
+  for (signature, data) in fields {
+    buf.put_slice(*signature);
+    buf.put_u16_le(data.len() as u16);
+    buf.put_slice(data);
+  }
+}
+
+fn group(
+  buf: &mut BytesMut,
+  label: [u8; 4],
+  label_type: u32,
+  contents: impl FnOnce(&mut BytesMut),
+) {
+  let mut data = BytesMut::new();
+  contents(&mut data);
+  buf.put_slice(b"GRUP");
+  buf.put_u32_le(24 + data.len() as u32);
+  buf.put_slice(&label);
+  buf.put_u32_le(label_type);
+  buf.put_u32_le(0);
+  buf.put_u32_le(0);
+  buf.put(data);
+}
+
+/// A plugin with one worldspace holding `BLOCKS * SUB_BLOCKS * CELLS` cells of `REFERENCES`
+/// placed objects each
+fn synthetic_plugin() -> BytesMut {
+  let mut buf = BytesMut::new();
+  record(&mut buf, b"TES4", 0, &[(b"HEDR", &[0; 12])]);
+  let mut next_id = 0x800;
+  let mut form_id = || {
+    next_id += 1;
+    next_id
+  };
+  let world = form_id();
+  group(&mut buf, *b"WRLD", 0, |buf| {
+    record(buf, b"WRLD", world, &[(b"EDID", b"Synthetic\0")]);
+    group(buf, world.to_le_bytes(), 1, |buf| {
+      for block in 0..BLOCKS {
+        group(buf, block.to_le_bytes(), 4, |buf| {
+          for sub_block in 0..SUB_BLOCKS {
+            group(buf, sub_block.to_le_bytes(), 5, |buf| {
+              for _ in 0..CELLS {
+                let cell = form_id();
+                record(buf, b"CELL", cell, &[(b"XCLC", &[0; 12])]);
+                group(buf, cell.to_le_bytes(), 6, |buf| {
+                  group(buf, cell.to_le_bytes(), 9, |buf| {
+                    for _ in 0..REFERENCES {
+                      let fields: [(&[u8; 4], &[u8]); 3] = [
+                        (b"NAME", &[0x10, 0, 0, 0]),
+                        (b"XSCL", &[0, 0, 0x80, 0x3F]),
+                        (b"DATA", &[0; 24]),
+                      ];
+                      record(buf, b"REFR", form_id(), &fields);
+                    }
+                  });
+                });
+              }
+            });
+          }
+        });
+      }
+    });
+  });
+  buf
+}
+
+/// The previous serializer: every group builds its contents into a new buffer, which its parent
+/// then copies into its own
+fn nested_copy_group(group: &Group) -> Bytes {
+  let data = match group.get_data() {
+    GroupData::Structured(components) => {
+      let mut bytes = BytesMut::new();
+      for component in components {
+        match component {
+          GroupDataComponent::Group(g) => bytes.put(nested_copy_group(g)),
+          GroupDataComponent::Record(r) => bytes.put(r.as_bytes()),
+          GroupDataComponent::Raw(r) => bytes.put(r.get_data().as_ref()),
+          GroupDataComponent::Empty => {}
+        }
+      }
+      bytes.freeze()
+    }
+    data => data.as_bytes(),
+  };
+  let mut bytes = BytesMut::new();
+  bytes.put_slice(b"GRUP");
+  bytes.put_u32_le((data.len() + Group::HEADER_SIZE) as u32);
+  bytes.put(group.get_label().as_bytes());
+  bytes.put_u16_le((*group.get_timestamp()).into());
+  bytes.put_u16_le((*group.get_vcs_info()).into());
+  bytes.put_u32_le(*group.get_unknown());
+  bytes.put(data);
+  bytes.freeze()
+}
+fn nested_copy(esx: &ESx) -> Bytes {
+  let mut data = BytesMut::new();
+  for group in esx.get_top_groups() {
+    data.put(nested_copy_group(group));
+  }
+  let mut bytes = BytesMut::new();
+  bytes.put(esx.get_header_record().as_bytes());
+  bytes.put(data);
+  bytes.freeze()
+}
+
+fn serialize(c: &mut Criterion) {
+  let buf = synthetic_plugin();
+  let mut esx = ESx::from_bytes(&mut buf.clone().freeze()).unwrap();
+  assert!(esx.process().is_empty());
+  assert_eq!(esx.as_bytes(), buf);
+  assert_eq!(nested_copy(&esx), buf);
+
+  let mut group = c.benchmark_group("serialize");
+  group.throughput(Throughput::Bytes(buf.len() as u64));
+  group.bench_function("nested_copy", |b| b.iter(|| nested_copy(&esx)));
+  group.bench_function("as_bytes", |b| b.iter(|| esx.as_bytes()));
+  group.bench_function("to_writer", |b| {
+    b.iter_batched_ref(
+      || Vec::with_capacity(buf.len()),
+      |out| esx.to_writer(out).unwrap(),
+      BatchSize::LargeInput,
+    )
+  });
+  group.finish();
+}
+
+criterion_group!(benches, serialize);
+criterion_main!(benches);
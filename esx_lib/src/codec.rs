@@ -0,0 +1,305 @@
+//! Typed decoding of fields and records that never loses data.
+//!
+//! Whatever a codec doesn't understand is kept alongside the decoded value and written back
+//! exactly as it was read: bytes after a decoded field value are kept as its remainder, fields a
+//! record codec doesn't read are kept at their original positions, and records of form versions
+//! a codec doesn't support are not decoded at all.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use std::collections::VecDeque;
+
+use crate::{
+  record::RecordData,
+  types::{FormID, Signature},
+  Error, Field, Record, Result,
+};
+
+/// A value stored at the start of a field's data
+pub trait FieldCodec: Sized {
+  /// Decodes a value from the start of `data`, returning it with the number of bytes it used
+  fn decode(data: &[u8]) -> Result<(Self, usize)>;
+  fn encode(&self, buf: &mut BytesMut);
+}
+
+macro_rules! impl_le_codec {
+  ($($t:ty),*) => {$(
+    impl FieldCodec for $t {
+      fn decode(data: &[u8]) -> Result<(Self, usize)> {
+        const SIZE: usize = std::mem::size_of::<$t>();
+        match data.get(..SIZE) {
+          Some(bytes) => Ok((<$t>::from_le_bytes(bytes.try_into()?), SIZE)),
+          None => Err(Error::BufferTooShort),
+        }
+      }
+      fn encode(&self, buf: &mut BytesMut) {
+        buf.put_slice(&self.to_le_bytes());
+      }
+    }
+  )*};
+}
+impl_le_codec!(u8, u16, u32, i32, f32);
+
+impl FieldCodec for FormID {
+  fn decode(data: &[u8]) -> Result<(Self, usize)> {
+    u32::decode(data).map(|(v, used)| (v.into(), used))
+  }
+  fn encode(&self, buf: &mut BytesMut) {
+    u32::from(*self).encode(buf)
+  }
+}
+
+/// A null-terminated string. Strings without a terminator or that aren't UTF-8 are not decoded,
+/// so they can't be changed when written back.
+impl FieldCodec for String {
+  fn decode(data: &[u8]) -> Result<(Self, usize)> {
+    let end = data
+      .iter()
+      .position(|b| *b == 0)
+      .ok_or(Error::BufferTooShort)?;
+    match std::str::from_utf8(&data[..end]) {
+      Ok(s) => Ok((s.to_string(), end + 1)),
+      Err(_) => Err(Error::InvalidString(data[..end].to_vec())),
+    }
+  }
+  fn encode(&self, buf: &mut BytesMut) {
+    buf.put_slice(self.as_bytes());
+    buf.put_u8(0);
+  }
+}
+
+/// A decoded value and the bytes after it that its codec didn't read
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lossless<T> {
+  value: T,
+  remainder: Bytes,
+}
+/// Conversion
+impl<T: FieldCodec> Lossless<T> {
+  pub fn new(value: T) -> Self {
+    Lossless {
+      value,
+      remainder: Bytes::new(),
+    }
+  }
+  pub fn decode(data: &Bytes) -> Result<Self> {
+    let (value, used) = T::decode(data)?;
+    Ok(Lossless {
+      value,
+      remainder: data.slice(used..),
+    })
+  }
+  pub fn encode(&self) -> Bytes {
+    let mut buf = BytesMut::with_capacity(self.remainder.len() + 4);
+    self.value.encode(&mut buf);
+    buf.put_slice(&self.remainder);
+    buf.freeze()
+  }
+}
+/// Getters
+impl<T> Lossless<T> {
+  pub fn get_value(&self) -> &T {
+    &self.value
+  }
+  pub fn get_value_mut(&mut self) -> &mut T {
+    &mut self.value
+  }
+  pub fn into_value(self) -> T {
+    self.value
+  }
+  pub fn get_remainder(&self) -> &Bytes {
+    &self.remainder
+  }
+  pub fn has_remainder(&self) -> bool {
+    !self.remainder.is_empty()
+  }
+}
+
+/// Conversion
+impl Field {
+  /// Decodes the start of the field's data, keeping any bytes after the value
+  pub fn decode<T: FieldCodec>(&self) -> Result<Lossless<T>> {
+    Lossless::decode(&self.get_bytes())
+  }
+  pub fn from_lossless<T: FieldCodec>(signature: Signature, value: &Lossless<T>) -> Self {
+    Field::new(signature, value.encode())
+  }
+}
+
+/// A record whose fields can be decoded into a typed value
+pub trait RecordCodec: Sized {
+  const SIGNATURE: Signature;
+  /// The field signatures the codec reads. Any other field is kept aside as unknown.
+  const FIELDS: &'static [Signature];
+
+  /// Whether the codec reads records of this signature, for codecs shared by several record types
+  fn supports_signature(signature: Signature) -> bool {
+    signature == Self::SIGNATURE
+  }
+  /// Whether the codec understands records of this form version
+  fn supports_form_version(_form_version: u16) -> bool {
+    true
+  }
+  fn decode(reader: &mut FieldReader) -> Result<Self>;
+  /// Writes the fields of the value in order
+  fn encode(&self, fields: &mut Vec<Field>);
+}
+
+/// The fields of a record in order, as seen by a [`RecordCodec`]. Fields the codec doesn't know
+/// are set aside with their positions before decoding starts.
+#[derive(Debug, Clone)]
+pub struct FieldReader {
+  known: VecDeque<(usize, Field)>,
+  unknown: Vec<(usize, Field)>,
+  /// The position of the last field read
+  last_read: Option<usize>,
+}
+impl FieldReader {
+  pub fn new(fields: &[&Field], known: &[Signature]) -> Self {
+    let (known, unknown): (Vec<_>, Vec<_>) = fields
+      .iter()
+      .map(|f| (*f).clone())
+      .enumerate()
+      .partition(|(_, f)| known.contains(f.get_signature()));
+    FieldReader {
+      known: known.into(),
+      unknown,
+      last_read: None,
+    }
+  }
+  pub fn peek(&self) -> Option<&Field> {
+    self.known.front().map(|(_, f)| f)
+  }
+  pub fn next_field(&mut self) -> Option<Field> {
+    let (i, field) = self.known.pop_front()?;
+    self.last_read = Some(i);
+    Some(field)
+  }
+  /// The next field if it has this signature
+  pub fn next_if(&mut self, signature: Signature) -> Option<Field> {
+    match self.peek()?.get_signature() == &signature {
+      true => self.next_field(),
+      false => None,
+    }
+  }
+  /// All consecutive fields with this signature, for repeated fields
+  pub fn next_while(&mut self, signature: Signature) -> Vec<Field> {
+    std::iter::from_fn(|| self.next_if(signature)).collect()
+  }
+  /// Decodes the next field if it has this signature
+  pub fn decode_if<T: FieldCodec>(&mut self, signature: Signature) -> Result<Option<Lossless<T>>> {
+    match self.peek() {
+      Some(f) if f.get_signature() == &signature => {
+        let value = f.decode()?;
+        self.next_field();
+        Ok(Some(value))
+      }
+      _ => Ok(None),
+    }
+  }
+  /// The fields the codec didn't read, with their original positions, and how many of them come
+  /// before the last field it read
+  fn finish(self) -> (Vec<(usize, Field)>, usize) {
+    let mut unknown = self.unknown;
+    unknown.extend(self.known);
+    unknown.sort_by_key(|(i, _)| *i);
+    let leading = match self.last_read {
+      Some(last) => unknown.partition_point(|(i, _)| *i < last),
+      None => unknown.len(),
+    };
+    (unknown, leading)
+  }
+}
+
+/// A decoded record and everything its codec didn't read: unknown fields at their original
+/// positions and any bytes after the last field
+#[derive(Debug, Clone)]
+pub struct LosslessRecord<T> {
+  value: T,
+  unknown: Vec<(usize, Field)>,
+  /// How many unknown fields come before the last decoded field. The rest follow the decoded
+  /// fields however many the value encodes.
+  leading: usize,
+  remainder: Option<Bytes>,
+}
+/// Conversion
+impl<T: RecordCodec> LosslessRecord<T> {
+  /// Decodes the record, or `None` if it has another signature or a form version the codec
+  /// doesn't support
+  pub fn decode(record: &Record) -> Result<Option<Self>> {
+    if !T::supports_signature(*record.get_signature())
+      || !T::supports_form_version(*record.get_form_version())
+    {
+      return Ok(None);
+    }
+    let mut data = record.get_data().clone();
+    data.process()?;
+
+    let mut reader = FieldReader::new(&data.get_fields(), T::FIELDS);
+    let value = T::decode(&mut reader)?;
+    let (unknown, leading) = reader.finish();
+    Ok(Some(LosslessRecord {
+      value,
+      unknown,
+      leading,
+      remainder: data.get_remainder().cloned(),
+    }))
+  }
+  /// The fields of the value with the unknown fields put back at their original positions.
+  /// Unknown fields that followed the last decoded field stay after the value's fields, so they
+  /// keep their place when the value encodes more or fewer fields than it read.
+  pub fn encode(&self) -> Vec<Field> {
+    let mut encoded = vec![];
+    self.value.encode(&mut encoded);
+
+    let (leading, trailing) = self.unknown.split_at(self.leading);
+    let total = encoded.len() + leading.len();
+    let mut fields = Vec::with_capacity(total + trailing.len());
+    let mut encoded = encoded.into_iter();
+    let mut unknown = leading.iter().peekable();
+    while fields.len() < total {
+      let field = match unknown.next_if(|(i, _)| *i == fields.len()) {
+        Some((_, f)) => f.clone(),
+        None => match encoded.next() {
+          Some(f) => f,
+          None => unknown.next().map(|(_, f)| f.clone()).unwrap(),
+        },
+      };
+      fields.push(field);
+    }
+    fields.extend(trailing.iter().map(|(_, f)| f.clone()));
+    fields
+  }
+  pub fn to_data(&self) -> RecordData {
+    match &self.remainder {
+      Some(remainder) => RecordData::Partial(self.encode(), remainder.clone()),
+      None => RecordData::Generic(self.encode()),
+    }
+  }
+}
+/// Getters
+impl<T> LosslessRecord<T> {
+  pub fn get_value(&self) -> &T {
+    &self.value
+  }
+  pub fn get_value_mut(&mut self) -> &mut T {
+    &mut self.value
+  }
+  pub fn get_unknown_fields(&self) -> &[(usize, Field)] {
+    &self.unknown
+  }
+  pub fn get_remainder(&self) -> Option<&Bytes> {
+    self.remainder.as_ref()
+  }
+}
+
+/// Conversion
+impl Record {
+  /// Decodes the record with a codec, see [`LosslessRecord::decode`]
+  pub fn decode<T: RecordCodec>(&self) -> Result<Option<LosslessRecord<T>>> {
+    LosslessRecord::decode(self)
+  }
+  /// Replaces the record's data with a decoded record
+  pub fn encode<T: RecordCodec>(&mut self, record: &LosslessRecord<T>) {
+    self.set_data(record.to_data())
+  }
+}
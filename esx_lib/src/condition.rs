@@ -0,0 +1,390 @@
+//! Conditions, stored in CTDA fields.
+//!
+//! A CTDA field compares the result of a condition function, run on some subject, against a value
+//! or a global. Functions are numbered, and their names and parameter types are looked up in
+//! [`registry::condition_function`]. String parameters follow the CTDA in CIS1 and CIS2 fields.
+//!
+//! Oblivion writes 24-byte CTDA fields without the reference or third parameter, and Fallout 3 and
+//! New Vegas 28-byte fields without the third parameter. The length is kept so a condition encodes
+//! back to the same bytes.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use bytes::{BufMut, BytesMut};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  registry::{self, FunctionInfo, ParamType},
+  types::{FormID, Game, Signature},
+  ESx, Error, Field, Record, Result,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Condition {
+  pub operator: Operator,
+  /// The low bits of the first byte, see the flag constants
+  pub flags: u8,
+  pub comparison: Comparison,
+  pub function: u16,
+  /// The parameters as stored; see [`Condition::get_params`] for their typed values
+  pub params: [u32; 2],
+  pub run_on: RunOn,
+  /// The reference the function runs on when `run_on` is [`RunOn::Reference`]; not stored by
+  /// Oblivion
+  pub reference: FormID,
+  /// The alias or package data index for some run-on targets; not stored by older games
+  pub param3: Option<i32>,
+  /// The CIS1 and CIS2 string parameters
+  pub strings: [Option<String>; 2],
+  /// Padding after the first byte and after the function index
+  padding: [u8; 5],
+  /// The length of the field: 24, 28 or 32 bytes
+  length: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operator {
+  Equal,
+  NotEqual,
+  Greater,
+  GreaterOrEqual,
+  Less,
+  LessOrEqual,
+}
+
+/// What a function's result is compared against
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Comparison {
+  Value(f32),
+  Global(FormID),
+}
+
+/// The subject a function runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunOn {
+  Subject,
+  Target,
+  Reference,
+  CombatTarget,
+  LinkedReference,
+  QuestAlias,
+  PackageData,
+  EventData,
+  Other(u32),
+}
+
+/// A parameter read with the type the function expects
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Param {
+  Int(i32),
+  Form(FormID),
+  Axis(char),
+  ActorValue(u32),
+  Sex(u32),
+  Alias(i32),
+  String(String),
+}
+
+/// Constants
+impl Condition {
+  /// Joined to the next condition with OR rather than AND
+  pub const OR: u8 = 0x01;
+  /// Form parameters are quest alias IDs
+  pub const USE_ALIASES: u8 = 0x02;
+  /// The comparison is the value of a global
+  pub const USE_GLOBAL: u8 = 0x04;
+  pub const USE_PACKAGE_DATA: u8 = 0x08;
+  pub const SWAP_SUBJECT_AND_TARGET: u8 = 0x10;
+}
+/// Conversion
+impl Condition {
+  /// Reads a CTDA field of 24, 28 or 32 bytes
+  pub fn decode(data: &[u8]) -> Result<Self> {
+    if data.len() < 24 {
+      return Err(Error::BufferTooShort);
+    }
+    let u32_at =
+      |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+    let flags = data[0] & 0x1F;
+    let operator = Operator::from_bits(data[0] >> 5)?;
+    let comparison = match flags & Self::USE_GLOBAL {
+      0 => Comparison::Value(f32::from_bits(u32_at(4))),
+      _ => Comparison::Global(u32_at(4).into()),
+    };
+    let length = match data.len() {
+      32.. => 32,
+      28.. => 28,
+      _ => 24,
+    };
+    let reference = match length >= 28 {
+      true => u32_at(24).into(),
+      false => FormID::from(0),
+    };
+    let param3 = match length >= 32 {
+      true => Some(u32_at(28) as i32),
+      false => None,
+    };
+    Ok(Condition {
+      operator,
+      flags,
+      comparison,
+      function: u16::from_le_bytes([data[8], data[9]]),
+      params: [u32_at(12), u32_at(16)],
+      run_on: RunOn::from(u32_at(20)),
+      reference,
+      param3,
+      strings: [None, None],
+      padding: [data[1], data[2], data[3], data[10], data[11]],
+      length,
+    })
+  }
+  /// Writes the CTDA field data, without the string parameters
+  pub fn encode(&self, buf: &mut BytesMut) {
+    buf.put_u8(self.operator.to_bits() << 5 | self.flags);
+    buf.put_slice(&self.padding[..3]);
+    match self.comparison {
+      Comparison::Value(value) => buf.put_f32_le(value),
+      Comparison::Global(global) => buf.put_u32_le(global.raw()),
+    }
+    buf.put_u16_le(self.function);
+    buf.put_slice(&self.padding[3..]);
+    buf.put_u32_le(self.params[0]);
+    buf.put_u32_le(self.params[1]);
+    buf.put_u32_le(self.run_on.into());
+    if self.length >= 28 {
+      buf.put_u32_le(self.reference.raw());
+    }
+    if self.length >= 32 {
+      buf.put_i32_le(self.param3.unwrap_or_default());
+    }
+  }
+  /// Every condition in a record, with the CIS1 and CIS2 fields that follow each one
+  pub fn read_all(record: &Record) -> Result<Vec<Self>> {
+    Condition::from_fields(&record.read_fields()?)
+  }
+  /// Every condition in fields already read from a record
+  pub fn from_fields(fields: &[Field]) -> Result<Vec<Self>> {
+    let mut conditions: Vec<Condition> = vec![];
+    for field in fields {
+      let slot = match field.get_signature().as_array() {
+        b"CTDA" => {
+          conditions.push(Condition::decode(field.get_data().as_slice())?);
+          continue;
+        }
+        b"CIS1" => 0,
+        b"CIS2" => 1,
+        _ => continue,
+      };
+      if let Some(condition) = conditions.last_mut() {
+        condition.strings[slot] = Some(field.get_zstring());
+      }
+    }
+    Ok(conditions)
+  }
+  /// The CTDA field followed by any CIS1 and CIS2 fields
+  pub fn to_fields(&self) -> Vec<Field> {
+    let mut buf = BytesMut::new();
+    self.encode(&mut buf);
+    let mut fields = vec![Field::new(Signature::new(b"CTDA"), buf.freeze())];
+    for (signature, string) in [b"CIS1", b"CIS2"].into_iter().zip(&self.strings) {
+      if let Some(string) = string {
+        fields.push(Field::from_zstring(Signature::new(signature), string));
+      }
+    }
+    fields
+  }
+}
+/// Getters
+impl Condition {
+  pub fn is_or(&self) -> bool {
+    self.flags & Self::OR != 0
+  }
+  /// The function's entry in the function table, if it is a known one
+  pub fn get_function_info(&self, game: Option<Game>) -> Option<&'static FunctionInfo> {
+    match game {
+      Some(game) => registry::condition_function(game, self.function),
+      None => registry::condition_function_any(self.function),
+    }
+  }
+  pub fn get_function_name(&self, game: Option<Game>) -> String {
+    match self.get_function_info(game) {
+      Some(info) => info.name.to_string(),
+      None => format!("Function{}", self.function),
+    }
+  }
+  /// The parameters the function takes, read as their types. Parameters of unknown functions are
+  /// read as integers.
+  pub fn get_params(&self, game: Option<Game>) -> Vec<Param> {
+    let Some(info) = self.get_function_info(game) else {
+      return self.params.iter().map(|p| Param::Int(*p as i32)).collect();
+    };
+    let mut params = vec![];
+    for (i, kind) in info.params.iter().enumerate() {
+      let raw = self.params[i];
+      params.push(match kind {
+        ParamType::None => break,
+        ParamType::Form if self.flags & Self::USE_ALIASES != 0 => Param::Alias(raw as i32),
+        ParamType::Form => Param::Form(raw.into()),
+        ParamType::Int => Param::Int(raw as i32),
+        ParamType::Axis => Param::Axis(char::from_u32(raw).unwrap_or('?')),
+        ParamType::ActorValue => Param::ActorValue(raw),
+        ParamType::Sex => Param::Sex(raw),
+        ParamType::Alias => Param::Alias(raw as i32),
+        ParamType::String => Param::String(self.strings[i].clone().unwrap_or_default()),
+      });
+    }
+    params
+  }
+}
+/// Process
+impl Condition {
+  /// Renders the condition as `GetIsID(NPC_:00013BBF) == 1`, naming the signature of each form
+  /// that `lookup` knows
+  pub fn render(&self, game: Option<Game>, lookup: &dyn Fn(FormID) -> Option<Signature>) -> String {
+    let form = |id: FormID| match lookup(id) {
+      Some(signature) => format!("{}:{:08X}", signature, id.raw()),
+      None => format!("{:08X}", id.raw()),
+    };
+    let params: Vec<String> = self
+      .get_params(game)
+      .into_iter()
+      .map(|param| match param {
+        Param::Int(value) => value.to_string(),
+        Param::Form(id) => form(id),
+        Param::Axis(axis) => axis.to_string(),
+        Param::ActorValue(index) => format!("AV{}", index),
+        Param::Sex(0) => "Male".to_string(),
+        Param::Sex(1) => "Female".to_string(),
+        Param::Sex(value) => value.to_string(),
+        Param::Alias(id) => format!("Alias[{}]", id),
+        Param::String(string) => format!("{:?}", string),
+      })
+      .collect();
+    let run_on = match self.run_on {
+      RunOn::Subject => String::new(),
+      RunOn::Reference => format!("{}.", form(self.reference)),
+      RunOn::QuestAlias => format!("Alias[{}].", self.param3.unwrap_or_default()),
+      RunOn::PackageData => format!("PackageData[{}].", self.param3.unwrap_or_default()),
+      run_on => format!("{:?}.", run_on),
+    };
+    let comparison = match self.comparison {
+      Comparison::Value(value) => value.to_string(),
+      Comparison::Global(global) => form(global),
+    };
+    format!(
+      "{}{}({}) {} {}",
+      run_on,
+      self.get_function_name(game),
+      params.join(", "),
+      self.operator,
+      comparison
+    )
+  }
+  /// Renders a list of conditions joined by AND and OR, each joined to the next by its own OR flag
+  pub fn render_all(
+    conditions: &[Condition],
+    game: Option<Game>,
+    lookup: &dyn Fn(FormID) -> Option<Signature>,
+  ) -> String {
+    let mut rendered = String::new();
+    for (i, condition) in conditions.iter().enumerate() {
+      if i > 0 {
+        let joiner = match conditions[i - 1].is_or() {
+          true => " OR ",
+          false => " AND ",
+        };
+        rendered.push_str(joiner);
+      }
+      rendered.push_str(&condition.render(game, lookup));
+    }
+    rendered
+  }
+}
+impl Display for Condition {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.render(None, &|_| None))
+  }
+}
+
+/// Conversion
+impl Operator {
+  fn from_bits(bits: u8) -> Result<Self> {
+    match bits {
+      0 => Ok(Operator::Equal),
+      1 => Ok(Operator::NotEqual),
+      2 => Ok(Operator::Greater),
+      3 => Ok(Operator::GreaterOrEqual),
+      4 => Ok(Operator::Less),
+      5 => Ok(Operator::LessOrEqual),
+      bits => Err(Error::UnknownConditionOperator(bits)),
+    }
+  }
+  fn to_bits(self) -> u8 {
+    match self {
+      Operator::Equal => 0,
+      Operator::NotEqual => 1,
+      Operator::Greater => 2,
+      Operator::GreaterOrEqual => 3,
+      Operator::Less => 4,
+      Operator::LessOrEqual => 5,
+    }
+  }
+}
+impl Display for Operator {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let symbol = match self {
+      Operator::Equal => "==",
+      Operator::NotEqual => "!=",
+      Operator::Greater => ">",
+      Operator::GreaterOrEqual => ">=",
+      Operator::Less => "<",
+      Operator::LessOrEqual => "<=",
+    };
+    write!(f, "{}", symbol)
+  }
+}
+
+impl From<u32> for RunOn {
+  fn from(val: u32) -> Self {
+    match val {
+      0 => RunOn::Subject,
+      1 => RunOn::Target,
+      2 => RunOn::Reference,
+      3 => RunOn::CombatTarget,
+      4 => RunOn::LinkedReference,
+      5 => RunOn::QuestAlias,
+      6 => RunOn::PackageData,
+      7 => RunOn::EventData,
+      other => RunOn::Other(other),
+    }
+  }
+}
+impl From<RunOn> for u32 {
+  fn from(val: RunOn) -> Self {
+    match val {
+      RunOn::Subject => 0,
+      RunOn::Target => 1,
+      RunOn::Reference => 2,
+      RunOn::CombatTarget => 3,
+      RunOn::LinkedReference => 4,
+      RunOn::QuestAlias => 5,
+      RunOn::PackageData => 6,
+      RunOn::EventData => 7,
+      RunOn::Other(other) => other,
+    }
+  }
+}
+
+/// Getters
+impl ESx {
+  /// The signature of every record in the plugin by FormID, for naming forms when rendering
+  /// conditions
+  pub fn get_form_signatures(&self) -> HashMap<FormID, Signature> {
+    self
+      .get_all_records()
+      .into_iter()
+      .map(|record| (*record.get_form_id(), *record.get_signature()))
+      .collect()
+  }
+}
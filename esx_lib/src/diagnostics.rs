@@ -0,0 +1,89 @@
+use std::fmt::{Display, Formatter, Result as fmtResult};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  group::GroupLabel,
+  types::{FormID, Signature},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Hash)]
+pub enum Severity {
+  /// The data was kept unchanged but could not be fully decoded
+  Warning,
+  /// The data could not be read and was left unprocessed
+  Error,
+}
+
+/// One step of the path from the top of the file to the data a diagnostic refers to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum PathSegment {
+  Group(GroupLabel),
+  Record { signature: Signature, form_id: FormID },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub struct Diagnostic {
+  path: Vec<PathSegment>,
+  severity: Severity,
+  message: String,
+}
+/// Conversion
+impl Diagnostic {
+  pub fn new(severity: Severity, message: String) -> Self {
+    Self {
+      path: vec![],
+      severity,
+      message,
+    }
+  }
+  pub fn warning(message: String) -> Self {
+    Self::new(Severity::Warning, message)
+  }
+  pub fn error(message: String) -> Self {
+    Self::new(Severity::Error, message)
+  }
+  /// Prefix the path with the segment of the containing group or record
+  pub fn within(mut self, segment: PathSegment) -> Self {
+    self.path.insert(0, segment);
+    self
+  }
+}
+/// Getters
+impl Diagnostic {
+  pub fn get_path(&self) -> &Vec<PathSegment> {
+    &self.path
+  }
+  pub fn get_severity(&self) -> &Severity {
+    &self.severity
+  }
+  pub fn get_message(&self) -> &str {
+    &self.message
+  }
+}
+
+impl Display for Severity {
+  fn fmt(&self, f: &mut Formatter) -> fmtResult {
+    match self {
+      Severity::Warning => write!(f, "warning"),
+      Severity::Error => write!(f, "error"),
+    }
+  }
+}
+impl Display for PathSegment {
+  fn fmt(&self, f: &mut Formatter) -> fmtResult {
+    match self {
+      PathSegment::Group(label) => write!(f, "{}", label),
+      PathSegment::Record { signature, form_id } => write!(f, "{} [{}]", signature, form_id),
+    }
+  }
+}
+impl Display for Diagnostic {
+  fn fmt(&self, f: &mut Formatter) -> fmtResult {
+    write!(f, "{}: ", self.severity)?;
+    for segment in &self.path {
+      write!(f, "{} > ", segment)?;
+    }
+    write!(f, "{}", self.message)
+  }
+}
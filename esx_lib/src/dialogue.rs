@@ -0,0 +1,218 @@
+//! Dialogue topics and their responses.
+//!
+//! Topics are DIAL records and their responses the INFO records in the topic children group that
+//! follows each one. Skyrim keeps topics in the DIAL top group, Fallout 4 inside the groups of the
+//! quests that own them; both are found. Only groups that have been processed are visible, but
+//! records are read on demand.
+
+use std::{
+  cell::OnceCell,
+  collections::{HashMap, HashSet},
+};
+
+use crate::{
+  condition::{Condition, Param},
+  group::GroupLabel,
+  types::{FormID, Signature},
+  ESx, Field, Group, Record,
+};
+
+/// The condition function that checks the speaker's base object
+const GET_IS_ID: u16 = 72;
+
+/// A DIAL record and the INFO records of its topic children group
+#[derive(Debug, Clone, Copy)]
+pub struct Topic<'a> {
+  record: &'a Record,
+  children: Option<&'a Group>,
+  /// The quest whose group holds the topic, for games that nest topics in quests
+  quest_group: Option<FormID>,
+}
+/// Getters
+impl<'a> Topic<'a> {
+  pub fn get_record(&self) -> &'a Record {
+    self.record
+  }
+  pub fn get_form_id(&self) -> FormID {
+    *self.record.get_form_id()
+  }
+  pub fn get_editor_id(&self) -> Option<String> {
+    read_zstring(self.record, b"EDID")
+  }
+  /// The player prompt or topic text. Localized plugins hold a string table ID here instead.
+  pub fn get_name(&self) -> Option<String> {
+    read_zstring(self.record, b"FULL")
+  }
+  /// The quest that owns the topic, from its QNAM field or the quest group holding it
+  pub fn get_quest(&self) -> Option<FormID> {
+    self
+      .record
+      .read_field(Signature::new(b"QNAM"))
+      .and_then(|f| f.get_form_id())
+      .or(self.quest_group)
+  }
+  /// The responses in the order the game considers them, following each INFO's link to the one
+  /// before it. Responses whose links don't lead back to the start follow in file order.
+  pub fn get_responses(&self) -> Vec<Response<'a>> {
+    let Some(children) = self.children else {
+      return vec![];
+    };
+    let responses: Vec<Response<'a>> = children
+      .get_data()
+      .get_records()
+      .into_iter()
+      .filter(|r| *r.get_signature() == Signature::new(b"INFO"))
+      .map(Response::new)
+      .collect();
+
+    let ids: HashSet<FormID> = responses.iter().map(|r| r.get_form_id()).collect();
+    let mut next: HashMap<Option<FormID>, Vec<usize>> = HashMap::new();
+    for (i, response) in responses.iter().enumerate() {
+      let previous = response.get_previous().filter(|p| ids.contains(p));
+      next.entry(previous).or_default().push(i);
+    }
+
+    let mut order: Vec<usize> = Vec::with_capacity(responses.len());
+    let mut visited = vec![false; responses.len()];
+    let mut stack: Vec<usize> = next
+      .get(&None)
+      .into_iter()
+      .flatten()
+      .rev()
+      .copied()
+      .collect();
+    while let Some(i) = stack.pop() {
+      if std::mem::replace(&mut visited[i], true) {
+        continue;
+      }
+      order.push(i);
+      let following = next.get(&Some(responses[i].get_form_id()));
+      stack.extend(following.into_iter().flatten().rev());
+    }
+    order.extend((0..responses.len()).filter(|i| !visited[*i]));
+    let mut responses: Vec<Option<Response<'a>>> = responses.into_iter().map(Some).collect();
+    order
+      .into_iter()
+      .filter_map(|i| responses[i].take())
+      .collect()
+  }
+}
+
+/// An INFO record: one response to a topic, with its fields read when first needed
+#[derive(Debug, Clone)]
+pub struct Response<'a> {
+  record: &'a Record,
+  fields: OnceCell<Vec<Field>>,
+}
+/// Conversion
+impl<'a> Response<'a> {
+  fn new(record: &'a Record) -> Self {
+    Response {
+      record,
+      fields: OnceCell::new(),
+    }
+  }
+}
+/// Getters
+impl<'a> Response<'a> {
+  pub fn get_record(&self) -> &'a Record {
+    self.record
+  }
+  pub fn get_form_id(&self) -> FormID {
+    *self.record.get_form_id()
+  }
+  /// The response this one follows, if it isn't the first
+  pub fn get_previous(&self) -> Option<FormID> {
+    self
+      .read_fields(b"PNAM")
+      .next()
+      .and_then(Field::get_form_id)
+      .filter(|id| !id.is_null())
+  }
+  /// The spoken lines of the response in order
+  pub fn get_texts(&self) -> Vec<String> {
+    self.read_fields(b"NAM1").map(Field::get_zstring).collect()
+  }
+  /// The player's line that leads to this response
+  pub fn get_prompt(&self) -> Option<String> {
+    self.read_fields(b"RNAM").next().map(Field::get_zstring)
+  }
+  /// The conditions of the response, in order. Malformed conditions give none.
+  pub fn get_conditions(&self) -> Vec<Condition> {
+    Condition::from_fields(self.fields()).unwrap_or_default()
+  }
+  /// The speakers the response is limited to: its ANAM speaker and the subjects of its
+  /// GetIsID conditions
+  pub fn get_speakers(&self) -> Vec<FormID> {
+    let mut speakers: Vec<FormID> = self
+      .read_fields(b"ANAM")
+      .filter_map(Field::get_form_id)
+      .collect();
+    for condition in self.get_conditions() {
+      if let (GET_IS_ID, Some(Param::Form(subject))) =
+        (condition.function, condition.get_params(None).first())
+      {
+        speakers.push(*subject);
+      }
+    }
+    speakers
+  }
+  fn fields(&self) -> &[Field] {
+    self
+      .fields
+      .get_or_init(|| self.record.read_fields().unwrap_or_default())
+  }
+  fn read_fields(&self, signature: &[u8; 4]) -> impl Iterator<Item = &Field> {
+    let signature = Signature::new(signature);
+    self
+      .fields()
+      .iter()
+      .filter(move |f| *f.get_signature() == signature)
+  }
+}
+
+/// Getters
+impl ESx {
+  /// Every dialogue topic, from the DIAL top group and from inside quest groups
+  pub fn get_topics(&self) -> Vec<Topic<'_>> {
+    let mut topics = vec![];
+    for signature in [b"DIAL", b"QUST"] {
+      if let Some(group) = self.get_top_group(Signature::new(signature)) {
+        topics_in(group, None, &mut topics);
+      }
+    }
+    topics
+  }
+  pub fn get_topic(&self, form_id: FormID) -> Option<Topic<'_>> {
+    self
+      .get_topics()
+      .into_iter()
+      .find(|topic| topic.get_form_id() == form_id)
+  }
+}
+
+fn topics_in<'a>(group: &'a Group, quest: Option<FormID>, topics: &mut Vec<Topic<'a>>) {
+  let quest = match group.get_label() {
+    GroupLabel::QuestScene(id) => Some(*id),
+    _ => quest,
+  };
+  for (record, children) in group.get_data().get_records_with_children() {
+    if *record.get_signature() == Signature::new(b"DIAL") {
+      topics.push(Topic {
+        record,
+        children,
+        quest_group: quest,
+      });
+    }
+  }
+  for subgroup in group.get_data().get_subgroups() {
+    if !matches!(subgroup.get_label(), GroupLabel::TopicChildren(_)) {
+      topics_in(subgroup, quest, topics);
+    }
+  }
+}
+fn read_zstring(record: &Record, signature: &[u8; 4]) -> Option<String> {
+  record
+    .read_field(Signature::new(signature))
+    .map(|f| f.get_zstring())
+}
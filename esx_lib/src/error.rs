@@ -2,6 +2,11 @@ use flate2::DecompressError;
 use std::array::TryFromSliceError;
 use std::io::Error as IoError;
 
+use crate::{
+  group::GroupLabel,
+  types::{FormID, Signature},
+};
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
@@ -12,10 +17,23 @@ pub enum Error {
   TryFromSliceError(TryFromSliceError),
   DecompressError(DecompressError),
   NonGroupSignature(Vec<u8>),
+  InvalidGroupSize(u32),
+  InvalidSignature(String),
+  InvalidFormID(String),
+  InvalidString(Vec<u8>),
+  InvalidBool(u8),
+  InvalidQuery(String),
+  FieldTooLarge(Signature, usize),
+  RecordTooLarge(Signature, FormID, usize),
+  GroupTooLarge(GroupLabel, usize),
   BufferTooShort,
+  EmptyLoadOrder,
   TES3Header,
   UnknownFileType,
   UnknownGroupLabelType(u32),
+  UnknownPropertyType(u8),
+  UnknownConditionOperator(u8),
+  UnexpectedField(Signature),
 }
 impl From<IoError> for Error {
   fn from(e: IoError) -> Self {
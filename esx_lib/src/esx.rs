@@ -1,55 +1,149 @@
-use std::{fs::File, io::Read};
+use std::{
+  cmp::Ordering,
+  fs::File,
+  io::{BufWriter, Read, Write},
+};
 
-use crate::{Error, Group, Record, Result};
+use crate::{
+  diagnostics::Diagnostic,
+  group::{GroupLabel, Placement, RawData},
+  options::{ParseMode, ProcessOptions},
+  registry,
+  types::{Game, Signature, Timestamp},
+  Error, Group, Record, Result,
+};
 use bytes::{BufMut, Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct ESx {
   header_record: Record,
   top_groups: Vec<Group>,
+  /// Unreadable top-level data kept in lenient mode, paired with the index of the top group it precedes
+  raw_data: Vec<(usize, RawData)>,
 }
 
 /// Conversion
 impl ESx {
+  /// A file with only a header record
+  pub fn new(header_record: Record) -> Self {
+    Self {
+      header_record,
+      top_groups: vec![],
+      raw_data: vec![],
+    }
+  }
   pub fn from_file(file: &File) -> Result<Self> {
+    Self::from_file_with_mode(file, ParseMode::Strict)
+  }
+  pub fn from_file_with_mode(file: &File, mode: ParseMode) -> Result<Self> {
     let mut file = file;
     let mut buf: Vec<u8> = vec![];
     file.read_to_end(&mut buf)?;
 
-    return Self::from_bytes(&mut BytesMut::from(buf.as_slice()));
+    Self::from_bytes_with_mode(&mut Bytes::from(buf), mode)
+  }
+  pub fn from_bytes(buf: &mut Bytes) -> Result<Self> {
+    Self::from_bytes_with_mode(buf, ParseMode::Strict)
   }
-  pub fn from_bytes(buf: &mut BytesMut) -> Result<Self> {
-    match &buf[0..4] {
-      b"TES4" => {}
-      b"TES3" => return Err(Error::TES3Header),
+  pub fn from_bytes_with_mode(buf: &mut Bytes, mode: ParseMode) -> Result<Self> {
+    match buf.get(0..4) {
+      Some(b"TES4") => {}
+      Some(b"TES3") => return Err(Error::TES3Header),
       _ => return Err(Error::UnknownFileType),
     };
     let header_record = Record::from_bytes(buf)?;
     let mut top_groups: Vec<Group> = vec![];
+    let mut raw_data: Vec<(usize, RawData)> = vec![];
 
     while !buf.is_empty() {
-      top_groups.push(Group::from_bytes(buf)?);
+      match Group::from_bytes(buf) {
+        Ok(group) => top_groups.push(group),
+        Err(e) if mode == ParseMode::Lenient => {
+          raw_data.push((top_groups.len(), RawData::recover(buf, e)))
+        }
+        Err(e) => return Err(e),
+      }
     }
 
     Ok(Self {
       header_record,
       top_groups,
+      raw_data,
     })
   }
 
+  /// # Panics
+  /// If a record, group or field is too large for its size field, or an edited compressed record
+  /// can't be compressed again. [`ESx::to_writer`] returns these as errors instead.
   pub fn as_bytes(&self) -> Bytes {
-    let mut bytes: BytesMut = BytesMut::new();
-    let header = self.header_record.as_bytes();
-    let mut data: BytesMut = BytesMut::new();
+    let mut sizes: Vec<usize> = vec![];
+    let size = self
+      .collect_sizes(&mut sizes)
+      .expect("plugin is too large to serialize");
+    let mut writer = BytesMut::with_capacity(size).writer();
+    self
+      .write_sized(&mut writer, &mut sizes.iter())
+      .expect("plugin is too large to serialize");
+    writer.into_inner().freeze()
+  }
+  /// Writes the file to `file`, failing before anything is written if a record, group or field
+  /// is too large for its size field
+  pub fn try_write(&self, file: &File) -> Result<()> {
+    let mut writer = BufWriter::new(file);
+    self.to_writer(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+  }
+  /// Streams the file to `writer`. Every size is measured first, so nothing is written if a
+  /// record, group or field is too large for its size field.
+  pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<()> {
+    let mut sizes: Vec<usize> = vec![];
+    self.collect_sizes(&mut sizes)?;
+    self.write_sized(writer, &mut sizes.iter())
+  }
+  /// Checks every size the file would be written with, without writing anything
+  pub fn check_sizes(&self) -> Result<()> {
+    self.collect_sizes(&mut vec![])?;
+    Ok(())
+  }
+  pub fn serialized_size(&self) -> Result<usize> {
+    let raw: usize = self.raw_data.iter().map(|(_, raw)| raw.len()).sum();
+    let groups: usize = self
+      .top_groups
+      .iter()
+      .map(Group::serialized_size)
+      .sum::<Result<usize>>()?;
+    Ok(self.header_record.serialized_size()? + raw + groups)
+  }
+  /// Calculates the size of every group in write order, returning the size of the whole file.
+  /// Fails if a group, record or field is too large for its size field.
+  fn collect_sizes(&self, sizes: &mut Vec<usize>) -> Result<usize> {
+    let raw: usize = self.raw_data.iter().map(|(_, raw)| raw.len()).sum();
+    let groups: usize = self
+      .top_groups
+      .iter()
+      .map(|g| g.collect_sizes(sizes))
+      .sum::<Result<usize>>()?;
+    Ok(self.header_record.checked_size()? + raw + groups)
+  }
+  fn write_sized<W: Write>(
+    &self,
+    writer: &mut W,
+    sizes: &mut std::slice::Iter<usize>,
+  ) -> Result<()> {
+    self.header_record.to_writer(writer)?;
 
-    for group in &self.top_groups {
-      data.put(group.as_bytes())
+    let mut raw_data = self.raw_data.iter().peekable();
+    for (index, group) in self.top_groups.iter().enumerate() {
+      while let Some((_, raw)) = raw_data.next_if(|(i, _)| *i <= index) {
+        writer.write_all(raw.get_data())?
+      }
+      group.write_sized(writer, sizes)?
     }
-
-    bytes.put(header);
-    bytes.put(data);
-
-    bytes.freeze()
+    for (_, raw) in raw_data {
+      writer.write_all(raw.get_data())?
+    }
+    Ok(())
   }
 }
 /// Getters
@@ -57,9 +151,22 @@ impl ESx {
   pub fn get_header_record(&self) -> &Record {
     &self.header_record
   }
+  pub fn get_header_record_mut(&mut self) -> &mut Record {
+    &mut self.header_record
+  }
   pub fn get_top_groups(&self) -> &Vec<Group> {
     &self.top_groups
   }
+  pub fn get_top_groups_mut(&mut self) -> &mut Vec<Group> {
+    &mut self.top_groups
+  }
+  /// The top group of this signature, once its label has been processed
+  pub fn get_top_group(&self, signature: Signature) -> Option<&Group> {
+    self
+      .top_groups
+      .iter()
+      .find(|g| *g.get_label() == GroupLabel::Top(signature))
+  }
   pub fn get_all_records(&self) -> Vec<&Record> {
     let mut records: Vec<&Record> = vec![&self.header_record];
     for group in &self.top_groups {
@@ -67,19 +174,158 @@ impl ESx {
     }
     records
   }
+  pub fn get_all_records_mut(&mut self) -> Vec<&mut Record> {
+    let mut records: Vec<&mut Record> = vec![&mut self.header_record];
+    for group in &mut self.top_groups {
+      records.append(&mut group.get_data_mut().get_records_recurse_mut());
+    }
+    records
+  }
+  /// The master files listed in the header, in order
+  pub fn get_masters(&self) -> Vec<String> {
+    let mut data = self.header_record.get_data().clone();
+    if data.process().is_err() {
+      return vec![];
+    }
+    data
+      .get_fields()
+      .into_iter()
+      .filter(|field| *field.get_signature() == Signature::new(b"MAST"))
+      .map(|field| field.get_zstring())
+      .collect()
+  }
+  /// The game this file was made for, if its header makes that clear
+  pub fn detect_game(&self) -> Option<Game> {
+    let hedr_version = self
+      .header_record
+      .read_field(Signature::new(b"HEDR"))
+      .and_then(|f| f.get_f32());
+    Game::from_header(*self.header_record.get_form_version(), hedr_version)
+  }
+  /// Unreadable top-level data, with the index of the top group each blob precedes
+  pub fn get_raw_data(&self) -> &Vec<(usize, RawData)> {
+    &self.raw_data
+  }
+}
+/// Setters
+impl ESx {
+  /// Set the timestamp of the header and of every group and record, structuring groups that
+  /// have not been processed. Contents that can't be read keep their timestamps and are reported,
+  /// as is unreadable top-level data.
+  pub fn set_timestamps(&mut self, timestamp: Timestamp) -> Vec<Diagnostic> {
+    self.header_record.set_timestamp(timestamp);
+    let mut diagnostics = self.raw_data_diagnostics();
+    for group in &mut self.top_groups {
+      diagnostics.append(&mut group.set_timestamps(timestamp));
+    }
+    diagnostics
+  }
+  /// Zero every timestamp so that rebuilding the same content gives identical bytes, see
+  /// [`ESx::set_timestamps`]
+  pub fn clear_timestamps(&mut self) -> Vec<Diagnostic> {
+    self.set_timestamps(Timestamp::default())
+  }
+  /// A diagnostic for each blob of unreadable top-level data, which header edits leave unchanged
+  fn raw_data_diagnostics(&self) -> Vec<Diagnostic> {
+    let message = |raw: &RawData| format!("{} unreadable bytes were left unchanged", raw.len());
+    self
+      .raw_data
+      .iter()
+      .map(|(_, raw)| Diagnostic::error(message(raw)))
+      .collect()
+  }
+  /// The top group of this signature, added at the end if there is none. Top group labels are
+  /// processed while searching.
+  pub fn get_or_insert_top_group(&mut self, signature: Signature) -> &mut Group {
+    let label = GroupLabel::Top(signature);
+    let found = self.top_groups.iter_mut().position(|g| {
+      g.process_label();
+      *g.get_label() == label
+    });
+    let i = match found {
+      Some(i) => i,
+      None => {
+        self.top_groups.push(Group::new(label));
+        self.top_groups.len() - 1
+      }
+    };
+    &mut self.top_groups[i]
+  }
+  /// Add a record, creating its top group and any nested groups `placement` needs, such as the
+  /// cell block, sub-block and children groups for a placed reference. Groups on the way are
+  /// structured if they haven't been processed.
+  pub fn insert_record(&mut self, record: Record, placement: Placement) -> Result<()> {
+    let path = placement.group_path(*record.get_signature(), *record.get_form_id());
+    let mut labels = path.into_iter();
+    let Some(GroupLabel::Top(top)) = labels.next() else {
+      unreachable!("group paths start with a top group")
+    };
+    let mut group = self.get_or_insert_top_group(top);
+    for label in labels {
+      group = group.get_or_insert_subgroup(label)?;
+    }
+    group.insert_record(record)
+  }
+  /// Reorder the file the way the Creation Kit writes it for the detected game, see
+  /// [`ESx::normalise_for`]
+  pub fn normalise(&mut self) {
+    self.normalise_for(self.detect_game());
+  }
+  /// Sort the top groups in the game's canonical order and the contents of every processed group,
+  /// see [`GroupData::normalise`](crate::group::GroupData::normalise). Top groups the game doesn't
+  /// order follow the known ones by label. Files with unreadable top-level data keep their top
+  /// group order, since that data is placed by top group index.
+  pub fn normalise_for(&mut self, game: Option<Game>) {
+    for group in &mut self.top_groups {
+      group.normalise();
+    }
+    if !self.raw_data.is_empty() {
+      return;
+    }
+    let order = game.map_or(&[][..], registry::top_group_order);
+    let position = |group: &Group| match group.get_label() {
+      GroupLabel::Top(s) => order.iter().position(|o| o == s),
+      _ => None,
+    };
+    self
+      .top_groups
+      .sort_by(|a, b| match (position(a), position(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+      });
+  }
 }
 /// Process
 impl ESx {
-  pub fn process(&mut self) {
-    self.process_header();
-    self.process_groups();
+  pub fn process(&mut self) -> Vec<Diagnostic> {
+    self.process_with_mode(ParseMode::Strict)
+  }
+  pub fn process_with_mode(&mut self, mode: ParseMode) -> Vec<Diagnostic> {
+    self.process_with_options(&mode.into())
+  }
+  /// Process the header and whatever `options` selects from the groups, leaving the rest raw
+  pub fn process_with_options(&mut self, options: &ProcessOptions) -> Vec<Diagnostic> {
+    let mut diagnostics = self.process_header();
+    diagnostics.append(&mut self.process_groups_with_options(options));
+    diagnostics
+  }
+  pub fn process_header(&mut self) -> Vec<Diagnostic> {
+    self.header_record.process()
+  }
+  pub fn process_groups(&mut self) -> Vec<Diagnostic> {
+    self.process_groups_with_mode(ParseMode::Strict)
   }
-  pub fn process_header(&mut self) {
-    self.header_record.process();
+  pub fn process_groups_with_mode(&mut self, mode: ParseMode) -> Vec<Diagnostic> {
+    self.process_groups_with_options(&mode.into())
   }
-  pub fn process_groups(&mut self) {
+  pub fn process_groups_with_options(&mut self, options: &ProcessOptions) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> =
+      self.raw_data.iter().map(|(_, r)| r.diagnose()).collect();
     for group in &mut self.top_groups {
-      group.process();
+      diagnostics.append(&mut group.process_with_options(options));
     }
+    diagnostics
   }
 }
@@ -1,6 +1,10 @@
-use crate::{types::Signature, Error, Result};
+use crate::{
+  types::{FormID, Signature},
+  Error, Result,
+};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
+use std::io::Write;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub enum FieldData {
@@ -8,13 +12,20 @@ pub enum FieldData {
   Raw(Bytes),
 }
 impl FieldData {
-  fn to_bytes(&self) -> Bytes {
+  pub fn to_bytes(&self) -> Bytes {
     match self {
       FieldData::Empty => Bytes::new(),
       FieldData::Raw(b) => b.clone(),
     }
   }
 
+  pub fn as_slice(&self) -> &[u8] {
+    match self {
+      FieldData::Empty => &[],
+      FieldData::Raw(b) => b,
+    }
+  }
+
   pub fn len(&self) -> usize {
     match self {
       FieldData::Empty => 0,
@@ -39,70 +50,131 @@ pub struct Field {
 /// Constants
 impl Field {
   pub const HEADER_SIZE: usize = 6;
+  /// The largest field that can be written without an `XXXX` field before it
   pub const MAXIMUM_SIZE: usize = Self::HEADER_SIZE + u16::MAX as usize;
+  /// An `XXXX` field and the header of the field it precedes
+  pub const OVERSIZE_HEADER_SIZE: usize = Self::HEADER_SIZE + 4 + Self::HEADER_SIZE;
+  pub const MAXIMUM_OVERSIZED_SIZE: usize = Self::OVERSIZE_HEADER_SIZE + u32::MAX as usize;
 }
 /// Conversion
 impl Field {
-  pub fn from_bytes(buf: &mut BytesMut) -> Result<Self> {
-    if buf.len() < 6 {
+  pub fn new(signature: Signature, data: impl Into<Bytes>) -> Self {
+    Field {
+      signature,
+      data: FieldData::Raw(data.into()),
+    }
+  }
+  pub fn empty(signature: Signature) -> Self {
+    Field {
+      signature,
+      data: FieldData::Empty,
+    }
+  }
+  pub fn from_u8(signature: Signature, val: u8) -> Self {
+    Field::new(signature, vec![val])
+  }
+  pub fn from_u16(signature: Signature, val: u16) -> Self {
+    Field::new(signature, val.to_le_bytes().to_vec())
+  }
+  pub fn from_u32(signature: Signature, val: u32) -> Self {
+    Field::new(signature, val.to_le_bytes().to_vec())
+  }
+  pub fn from_i32(signature: Signature, val: i32) -> Self {
+    Field::new(signature, val.to_le_bytes().to_vec())
+  }
+  pub fn from_f32(signature: Signature, val: f32) -> Self {
+    Field::new(signature, val.to_le_bytes().to_vec())
+  }
+  pub fn from_form_id(signature: Signature, val: FormID) -> Self {
+    Field::from_u32(signature, val.into())
+  }
+  /// Builds a null-terminated string field
+  pub fn from_zstring(signature: Signature, val: &str) -> Self {
+    let mut data = Vec::with_capacity(val.len() + 1);
+    data.extend_from_slice(val.as_bytes());
+    data.push(0);
+    Field::new(signature, data)
+  }
+
+  pub fn from_bytes(buf: &mut Bytes) -> Result<Self> {
+    if buf.len() < Self::HEADER_SIZE {
       return Err(Error::BufferTooShort);
     }
-    let signature: Signature = Signature::new(&buf.get_u32_le().to_le_bytes());
-    let data_size: u16 = buf.get_u16_le();
+    let signature: Signature = Signature::new(&buf[0..4].try_into()?);
+    let data_size = u16::from_le_bytes(buf[4..6].try_into()?) as usize;
 
-    if signature == Signature::new(b"XXXX") {
-      let size: u32 = buf.get_u32_le();
-      return Field::from_oversized_field(buf, size);
+    if signature == Signature::XXXX {
+      return Field::from_oversized_field(buf);
     }
-    if buf.len() < data_size as usize {
+    if buf.len() - Self::HEADER_SIZE < data_size {
       return Err(Error::BufferTooShort);
     }
 
-    let data: Bytes = buf.split_to(data_size as usize).clone().freeze();
+    buf.advance(Self::HEADER_SIZE);
+    let data: Bytes = buf.split_to(data_size);
 
     Ok(Field {
       signature,
       data: FieldData::Raw(data),
     })
   }
-  fn from_oversized_field(buf: &mut BytesMut, size: u32) -> Result<Self> {
-    if buf.len() < 6 {
+  /// Reads an `XXXX` field holding the real size followed by the field it applies to, whose own
+  /// size is ignored
+  fn from_oversized_field(buf: &mut Bytes) -> Result<Self> {
+    if buf.len() < Self::OVERSIZE_HEADER_SIZE {
       return Err(Error::BufferTooShort);
     }
-    let signature: Signature = Signature::new(&buf.get_u32_le().to_le_bytes());
-    let _: u16 = buf.get_u16_le();
+    let size = u32::from_le_bytes(buf[6..10].try_into()?) as usize;
+    let signature: Signature = Signature::new(&buf[10..14].try_into()?);
 
-    if buf.len() < size as usize {
+    if buf.len() - Self::OVERSIZE_HEADER_SIZE < size {
       return Err(Error::BufferTooShort);
     }
-    let data: Bytes = buf.split_to(size as usize).clone().freeze();
+    buf.advance(Self::OVERSIZE_HEADER_SIZE);
+    let data: Bytes = buf.split_to(size);
     Ok(Field {
       signature,
       data: FieldData::Raw(data),
     })
   }
 
+  /// # Panics
+  /// If the field is larger than [`Field::MAXIMUM_OVERSIZED_SIZE`], see [`Field::to_writer`]
   pub fn as_bytes(&self) -> Bytes {
-    let mut bytes: BytesMut = BytesMut::new();
-    let data = self.data.to_bytes();
-    let data_len = data.len();
-
-    // If oversize
-    if data_len > u16::MAX as usize {
-      // Preface with oversize field
-      bytes.put(Signature::new(b"XXXX").as_bytes());
-      bytes.put_u16_le(4);
-      bytes.put_u32_le(data_len as u32);
+    let mut writer = BytesMut::with_capacity(self.serialized_size()).writer();
+    self
+      .to_writer(&mut writer)
+      .expect("field is too large to serialize");
+    writer.into_inner().freeze()
+  }
+  /// Writes the field, preceded by an `XXXX` field if it is larger than [`Field::MAXIMUM_SIZE`]
+  pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<()> {
+    let data = self.data.as_slice();
+    if self.serialized_size() > Self::MAXIMUM_OVERSIZED_SIZE {
+      return Err(Error::FieldTooLarge(self.signature, data.len()));
+    }
 
-      bytes.put(self.signature.as_slice());
-      bytes.put_u16_le(0);
+    let mut header = [0; Self::HEADER_SIZE];
+    header[0..4].copy_from_slice(self.signature.as_slice());
+    if self.is_oversized() {
+      let mut oversize = [0; Self::OVERSIZE_HEADER_SIZE - Self::HEADER_SIZE];
+      oversize[0..4].copy_from_slice(Signature::XXXX.as_slice());
+      oversize[4..6].copy_from_slice(&4u16.to_le_bytes());
+      oversize[6..10].copy_from_slice(&(data.len() as u32).to_le_bytes());
+      writer.write_all(&oversize)?;
     } else {
-      bytes.put(self.signature.as_slice());
-      bytes.put_u16_le(data_len as u16);
+      header[4..6].copy_from_slice(&(data.len() as u16).to_le_bytes());
+    }
+    writer.write_all(&header)?;
+    writer.write_all(data)?;
+    Ok(())
+  }
+  /// The number of bytes [`Field::to_writer`] writes, including any `XXXX` field
+  pub fn serialized_size(&self) -> usize {
+    match self.is_oversized() {
+      true => Self::OVERSIZE_HEADER_SIZE + self.data.len(),
+      false => Self::HEADER_SIZE + self.data.len(),
     }
-
-    bytes.put(data);
-    bytes.freeze()
   }
 }
 /// Getters
@@ -113,6 +185,47 @@ impl Field {
   pub fn get_data(&self) -> &FieldData {
     &self.data
   }
+  pub fn get_bytes(&self) -> Bytes {
+    self.data.to_bytes()
+  }
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+  /// Whether this field needs an `XXXX` field before it to hold its size
+  pub fn is_oversized(&self) -> bool {
+    self.data.len() > u16::MAX as usize
+  }
+  pub fn get_u8(&self) -> Option<u8> {
+    self.get_array().map(u8::from_le_bytes)
+  }
+  pub fn get_u16(&self) -> Option<u16> {
+    self.get_array().map(u16::from_le_bytes)
+  }
+  pub fn get_u32(&self) -> Option<u32> {
+    self.get_array().map(u32::from_le_bytes)
+  }
+  pub fn get_i32(&self) -> Option<i32> {
+    self.get_array().map(i32::from_le_bytes)
+  }
+  pub fn get_f32(&self) -> Option<f32> {
+    self.get_array().map(f32::from_le_bytes)
+  }
+  pub fn get_form_id(&self) -> Option<FormID> {
+    self.get_u32().map(FormID::from)
+  }
+  /// Reads the field as a string, stopping at the first null byte
+  pub fn get_zstring(&self) -> String {
+    let bytes = self.data.as_slice();
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+  }
+  /// The field's data when it is exactly `N` bytes long
+  fn get_array<const N: usize>(&self) -> Option<[u8; N]> {
+    self.data.as_slice().try_into().ok()
+  }
 }
 /// Process
 impl Field {
@@ -1,14 +1,21 @@
 use crate::{
+  diagnostics::{Diagnostic, PathSegment},
+  options::{ParseMode, ProcessOptions},
   types::{Timestamp, VcsInfo},
-  Error, Result,
+  Error, Record, Result,
 };
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
+use std::io::Write;
 
 mod group_data;
 mod group_type;
-pub use group_data::GroupData;
+mod placement;
+mod raw_data;
+pub use group_data::{GroupData, GroupDataComponent};
 pub use group_type::GroupLabel;
+pub use placement::Placement;
+pub use raw_data::RawData;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct Group {
@@ -25,20 +32,36 @@ impl Group {
 }
 /// Conversion
 impl Group {
-  pub fn from_bytes(buf: &mut BytesMut) -> Result<Self> {
+  /// An empty structured group
+  pub fn new(label: GroupLabel) -> Self {
+    Group {
+      label,
+      timestamp: Timestamp::default(),
+      vcs_info: VcsInfo::default(),
+      _unknown_1: 0,
+      data: GroupData::Structured(vec![]),
+    }
+  }
+  pub fn from_bytes(buf: &mut Bytes) -> Result<Self> {
+    if buf.len() < Self::HEADER_SIZE {
+      return Err(Error::BufferTooShort);
+    }
     if &buf[0..4] != b"GRUP".as_slice() {
       return Err(Error::NonGroupSignature(buf[0..4].to_vec()));
     }
 
-    let mut header: BytesMut = buf.split_to(24);
-    let _signature = header.split_to(4);
-    let data_size: u32 = header.get_u32_le();
-
-    if buf.len() < (data_size as usize - 24) {
+    // Validate the size before consuming anything so a failed read leaves the buffer untouched
+    let data_size: u32 = u32::from_le_bytes(buf[4..8].try_into()?);
+    if (data_size as usize) < Self::HEADER_SIZE {
+      return Err(Error::InvalidGroupSize(data_size));
+    }
+    if buf.len() < data_size as usize {
       return Err(Error::BufferTooShort);
     };
 
-    let data: BytesMut = buf.split_to(data_size as usize - 24);
+    let mut header: Bytes = buf.split_to(Self::HEADER_SIZE);
+    header.advance(8);
+    let data: Bytes = buf.split_to(data_size as usize - Self::HEADER_SIZE);
 
     let mut label: [u8; 4] = [0; 4];
     header.copy_to_slice(&mut label);
@@ -55,20 +78,60 @@ impl Group {
     })
   }
 
+  /// # Panics
+  /// If the group or anything in it is too large for its size field, see [`Group::to_writer`]
   pub fn as_bytes(&self) -> Bytes {
-    let mut bytes: BytesMut = BytesMut::new();
-    let data = self.data.as_bytes();
-    let data_len = data.len() + Self::HEADER_SIZE;
-
-    bytes.put(b"GRUP".as_slice());
-    bytes.put_u32_le(data_len as u32);
-    bytes.put(self.label.as_bytes());
-    bytes.put_u16_le(self.timestamp.into());
-    bytes.put_u16_le(self.vcs_info.into());
-    bytes.put_u32_le(self._unknown_1);
-    bytes.put(data);
+    let mut sizes: Vec<usize> = vec![];
+    let size = self
+      .collect_sizes(&mut sizes)
+      .expect("group is too large to serialize");
+    let mut writer = BytesMut::with_capacity(size).writer();
+    self
+      .write_sized(&mut writer, &mut sizes.iter())
+      .expect("group is too large to serialize");
+    writer.into_inner().freeze()
+  }
+  /// Writes the group and its contents, failing if it is larger than [`Group::MAXIMUM_SIZE`]
+  pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<()> {
+    let mut sizes: Vec<usize> = vec![];
+    self.collect_sizes(&mut sizes)?;
+    self.write_sized(writer, &mut sizes.iter())
+  }
+  /// The number of bytes [`Group::to_writer`] writes, which is also the size in its header
+  pub fn serialized_size(&self) -> Result<usize> {
+    Ok(Self::HEADER_SIZE + self.data.serialized_size()?)
+  }
+  /// Calculates the size of this group and every group inside it, pushing them in write order so
+  /// that writing never has to measure a group again. Fails if a group, record or field is too
+  /// large for its size field.
+  pub(crate) fn collect_sizes(&self, sizes: &mut Vec<usize>) -> Result<usize> {
+    let slot = sizes.len();
+    sizes.push(0);
+    let size = Self::HEADER_SIZE + self.data.collect_sizes(sizes)?;
+    if size > Self::MAXIMUM_SIZE {
+      return Err(Error::GroupTooLarge(self.label, size));
+    }
+    sizes[slot] = size;
+    Ok(size)
+  }
+  /// Writes the group using sizes from [`Group::collect_sizes`]
+  pub(crate) fn write_sized<W: Write>(
+    &self,
+    writer: &mut W,
+    sizes: &mut std::slice::Iter<usize>,
+  ) -> Result<()> {
+    let size = *sizes.next().expect("sizes collected for every group");
+    if size > Self::MAXIMUM_SIZE {
+      return Err(Error::GroupTooLarge(self.label, size));
+    }
 
-    bytes.freeze()
+    writer.write_all(b"GRUP")?;
+    writer.write_all(&(size as u32).to_le_bytes())?;
+    writer.write_all(&self.label.as_bytes())?;
+    writer.write_all(&u16::from(self.timestamp).to_le_bytes())?;
+    writer.write_all(&u16::from(self.vcs_info).to_le_bytes())?;
+    writer.write_all(&self._unknown_1.to_le_bytes())?;
+    self.data.write_sized(writer, sizes)
   }
 }
 /// Getters
@@ -82,27 +145,198 @@ impl Group {
   pub fn get_vcs_info(&self) -> &VcsInfo {
     &self.vcs_info
   }
+  pub fn get_unknown(&self) -> &u32 {
+    &self._unknown_1
+  }
   pub fn get_data(&self) -> &GroupData {
     &self.data
   }
+  pub fn get_data_mut(&mut self) -> &mut GroupData {
+    &mut self.data
+  }
 }
-/// Processing
+/// Setters
 impl Group {
-  pub fn process(&mut self) {
+  pub fn set_timestamp(&mut self, timestamp: Timestamp) {
+    self.timestamp = timestamp;
+  }
+  pub fn set_vcs_info(&mut self, vcs_info: VcsInfo) {
+    self.vcs_info = vcs_info;
+  }
+  pub fn set_unknown(&mut self, unknown: u32) {
+    self._unknown_1 = unknown;
+  }
+  /// Set the timestamp of this group and every group and record inside it, structuring raw
+  /// contents first. Contents that can't be read keep their timestamps and are reported.
+  pub fn set_timestamps(&mut self, timestamp: Timestamp) -> Vec<Diagnostic> {
+    self.visit_mut(&mut |g| g.set_timestamp(timestamp), &mut |r| {
+      r.set_timestamp(timestamp)
+    })
+  }
+  /// Apply `on_group` to this group and every group inside it and `on_record` to every record
+  /// inside it, structuring raw contents first. Contents that can't be read are left unchanged
+  /// and reported.
+  pub(crate) fn visit_mut(
+    &mut self,
+    on_group: &mut dyn FnMut(&mut Group),
+    on_record: &mut dyn FnMut(&mut Record),
+  ) -> Vec<Diagnostic> {
+    on_group(self);
     self.process_label();
-    self.process_data();
+    let segment = self.path_segment();
+    let components = match self.data.structure() {
+      Ok(components) => components,
+      Err(e) => {
+        let message = format!(
+          "Contents could not be read and were left unchanged: {:?}",
+          e
+        );
+        return vec![Diagnostic::error(message).within(segment)];
+      }
+    };
+    let mut diagnostics = vec![];
+    for component in components {
+      match component {
+        GroupDataComponent::Group(g) => diagnostics.append(&mut g.visit_mut(on_group, on_record)),
+        GroupDataComponent::Record(r) => on_record(r),
+        GroupDataComponent::Raw(raw) => {
+          let message = format!("{} unreadable bytes were left unchanged", raw.len());
+          diagnostics.push(Diagnostic::error(message));
+        }
+        GroupDataComponent::Empty => {}
+      }
+    }
+    diagnostics
+      .into_iter()
+      .map(|d| d.within(segment.clone()))
+      .collect()
+  }
+  /// The subgroup with this label, added if there is none. A group of a record's children is
+  /// added right after the record, or before any cell blocks without it, any other group at the
+  /// end.
+  pub fn get_or_insert_subgroup(&mut self, label: GroupLabel) -> Result<&mut Group> {
+    let components = self.data.structure()?;
+    let mut found = None;
+    let mut parent = None;
+    for (i, component) in components.iter_mut().enumerate() {
+      match component {
+        GroupDataComponent::Group(g) => {
+          g.process_label();
+          if *g.get_label() == label {
+            found = Some(i);
+            break;
+          }
+        }
+        GroupDataComponent::Record(r) if Some(*r.get_form_id()) == label.get_parent_record() => {
+          parent = Some(i + 1)
+        }
+        _ => {}
+      }
+    }
+    let i = match (
+      found,
+      parent.or_else(|| label.get_parent_record().and(first_cell_block(components))),
+    ) {
+      (Some(i), _) => i,
+      (None, Some(i)) => {
+        components.insert(i, Group::new(label).into());
+        i
+      }
+      (None, None) => {
+        components.push(Group::new(label).into());
+        components.len() - 1
+      }
+    };
+    match &mut components[i] {
+      GroupDataComponent::Group(g) => Ok(g),
+      _ => unreachable!("component {} is a group", i),
+    }
+  }
+  /// Add a record to this group, before the group of its children if there is one. In a
+  /// worldspace's children group, records such as the persistent cell go before the cell blocks.
+  pub fn insert_record(&mut self, record: Record) -> Result<()> {
+    let components = self.data.structure()?;
+    let children = components.iter().position(|c| match c {
+      GroupDataComponent::Group(g) => {
+        g.get_label()
+          .process()
+          .ok()
+          .and_then(|l| l.get_parent_record())
+          == Some(*record.get_form_id())
+      }
+      _ => false,
+    });
+    match children.or_else(|| first_cell_block(components)) {
+      Some(i) => components.insert(i, record.into()),
+      None => components.push(record.into()),
+    }
+    Ok(())
+  }
+  /// Sort the contents of this group and every group inside it, see [`GroupData::normalise`]
+  pub fn normalise(&mut self) {
+    self.data.normalise();
+  }
+}
+/// Processing
+impl Group {
+  pub fn process(&mut self) -> Vec<Diagnostic> {
+    self.process_with_mode(ParseMode::Strict)
+  }
+  pub fn process_with_mode(&mut self, mode: ParseMode) -> Vec<Diagnostic> {
+    self.process_with_options(&mode.into())
+  }
+  /// Process the label, then the contents if `options` selects them, treating this group as a
+  /// top group
+  pub fn process_with_options(&mut self, options: &ProcessOptions) -> Vec<Diagnostic> {
+    self.process_at_depth(options, 0)
   }
-  pub fn process_label(&mut self) {
+  pub(crate) fn process_at_depth(
+    &mut self,
+    options: &ProcessOptions,
+    depth: usize,
+  ) -> Vec<Diagnostic> {
+    let mut diagnostics = self.process_label();
+    let skip_contents = depth == 0 && options.top_groups_only;
+    if !skip_contents && options.selects_group(&self.label) && options.structures_depth(depth) {
+      diagnostics.append(&mut self.process_data_at_depth(options, depth));
+    }
+    diagnostics
+  }
+  pub fn process_label(&mut self) -> Vec<Diagnostic> {
     match self.label.process() {
-      Ok(l) => self.label = l,
-      Err(e) => eprintln!("Error processing Group Label: {:?}", e),
+      Ok(l) => {
+        self.label = l;
+        vec![]
+      }
+      Err(e) => {
+        let message = format!("Error processing Group Label: {:?}", e);
+        vec![Diagnostic::warning(message).within(self.path_segment())]
+      }
     }
   }
-  pub fn process_data(&mut self) {
-    match self.data.process() {
-      Ok(d) => self.data = d,
-      Err(e) => eprintln!("Error processing Group Data: {:?}", e),
+  pub fn process_data(&mut self) -> Vec<Diagnostic> {
+    self.process_data_with_mode(ParseMode::Strict)
+  }
+  pub fn process_data_with_mode(&mut self, mode: ParseMode) -> Vec<Diagnostic> {
+    self.process_data_with_options(&mode.into())
+  }
+  pub fn process_data_with_options(&mut self, options: &ProcessOptions) -> Vec<Diagnostic> {
+    self.process_data_at_depth(options, 0)
+  }
+  fn process_data_at_depth(&mut self, options: &ProcessOptions, depth: usize) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+    if let Err(e) = self.data.process_at_depth(options, depth, &mut diagnostics) {
+      let message = format!("Error processing Group Data: {:?}", e);
+      diagnostics.push(Diagnostic::error(message));
     }
+    let segment = self.path_segment();
+    diagnostics
+      .into_iter()
+      .map(|d| d.within(segment.clone()))
+      .collect()
+  }
+  pub fn path_segment(&self) -> PathSegment {
+    PathSegment::Group(self.label)
   }
 }
 
@@ -116,3 +350,15 @@ impl PartialOrd for Group {
     Some(self.cmp(other))
   }
 }
+
+/// The position of the first exterior cell block in a worldspace's children group, which the
+/// persistent cell and its children come before
+fn first_cell_block(components: &[GroupDataComponent]) -> Option<usize> {
+  components.iter().position(|c| match c {
+    GroupDataComponent::Group(g) => matches!(
+      g.get_label().process(),
+      Ok(GroupLabel::ExteriorCellBlock { .. })
+    ),
+    _ => false,
+  })
+}
@@ -1,37 +1,87 @@
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter, Result as fmtResult};
 
-use crate::{Group, Record, Result};
+use super::RawData;
+use crate::{
+  diagnostics::Diagnostic,
+  options::{ParseMode, ProcessOptions},
+  Group, Record, Result,
+};
 use bytes::{BufMut, Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
+use std::io::Write;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub enum GroupData {
   Empty,
-  Raw(BytesMut),
+  Raw(Bytes),
   Structured(Vec<GroupDataComponent>),
 }
 /// Conversion
 impl GroupData {
+  /// # Panics
+  /// If anything in the group is too large for its size field, see [`GroupData::to_writer`]
   pub fn as_bytes(&self) -> Bytes {
     match self {
       Self::Empty => Bytes::new(),
-      Self::Raw(b) => b.clone().freeze(),
-      Self::Structured(s) => Self::structure_as_bytes(s),
+      Self::Raw(b) => b.clone(),
+      Self::Structured(_) => {
+        let mut sizes: Vec<usize> = vec![];
+        let size = self
+          .collect_sizes(&mut sizes)
+          .expect("group is too large to serialize");
+        let mut writer = BytesMut::with_capacity(size).writer();
+        self
+          .write_sized(&mut writer, &mut sizes.iter())
+          .expect("group is too large to serialize");
+        writer.into_inner().freeze()
+      }
     }
   }
 
-  fn structure_as_bytes(s: &Vec<GroupDataComponent>) -> Bytes {
-    let mut bytes: BytesMut = BytesMut::new();
-    for component in s {
-      bytes.put(component.as_bytes())
+  pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<()> {
+    let mut sizes: Vec<usize> = vec![];
+    self.collect_sizes(&mut sizes)?;
+    self.write_sized(writer, &mut sizes.iter())
+  }
+  pub(crate) fn collect_sizes(&self, sizes: &mut Vec<usize>) -> Result<usize> {
+    match self {
+      Self::Structured(s) => s.iter().map(|c| c.collect_sizes(sizes)).sum(),
+      _ => self.serialized_size(),
     }
-    bytes.freeze()
   }
-  fn structure_from_bytes(buf: &mut BytesMut) -> Result<Vec<GroupDataComponent>> {
+  pub(crate) fn write_sized<W: Write>(
+    &self,
+    writer: &mut W,
+    sizes: &mut std::slice::Iter<usize>,
+  ) -> Result<()> {
+    match self {
+      Self::Empty => {}
+      Self::Raw(b) => writer.write_all(b)?,
+      Self::Structured(s) => {
+        for component in s {
+          component.write_sized(writer, sizes)?
+        }
+      }
+    }
+    Ok(())
+  }
+  pub fn serialized_size(&self) -> Result<usize> {
+    match self {
+      Self::Empty => Ok(0),
+      Self::Raw(b) => Ok(b.len()),
+      Self::Structured(s) => s.iter().map(GroupDataComponent::serialized_size).sum(),
+    }
+  }
+
+  fn structure_from_bytes(buf: &mut Bytes, mode: ParseMode) -> Result<Vec<GroupDataComponent>> {
     let mut components: Vec<GroupDataComponent> = vec![];
     while !buf.is_empty() {
-      let component = GroupDataComponent::from_bytes(buf)?;
-      components.push(component);
+      match GroupDataComponent::from_bytes(buf) {
+        Ok(component) => components.push(component),
+        Err(e) if mode == ParseMode::Lenient => components.push(RawData::recover(buf, e).into()),
+        Err(e) => return Err(e),
+      }
     }
     Ok(components)
   }
@@ -44,18 +94,28 @@ impl GroupData {
       Self::Structured(s) => Some(s),
     }
   }
+  /// The contents as components, read from a copy of the data if it hasn't been structured
+  pub fn read_components(&self) -> Result<Vec<GroupDataComponent>> {
+    match self {
+      Self::Structured(s) => Ok(s.clone()),
+      _ => Ok(std::mem::take(self.clone().structure()?)),
+    }
+  }
+  pub fn get_components_mut(&mut self) -> Option<&mut Vec<GroupDataComponent>> {
+    match self {
+      Self::Empty | Self::Raw(_) => None,
+      Self::Structured(s) => Some(s),
+    }
+  }
   pub fn get_records(&self) -> Vec<&Record> {
     let mut records: Vec<&Record> = vec![];
-    match self {
-      Self::Structured(s) => {
-        records.reserve_exact(s.len());
-        for component in s {
-          if let GroupDataComponent::Record(r) = component {
-            records.push(r)
-          }
+    if let Self::Structured(s) = self {
+      records.reserve_exact(s.len());
+      for component in s {
+        if let GroupDataComponent::Record(r) = component {
+          records.push(r)
         }
       }
-      _ => {}
     };
     records.shrink_to_fit();
     records
@@ -63,19 +123,16 @@ impl GroupData {
   pub fn get_records_recurse(&self) -> Vec<&Record> {
     let mut records: Vec<&Record> = vec![];
     let mut groups_expanded: Vec<Vec<&Record>> = vec![];
-    match self {
-      Self::Structured(s) => {
-        records.reserve_exact(s.len());
-        groups_expanded.reserve_exact(s.len());
-        for component in s {
-          match component {
-            GroupDataComponent::Record(r) => records.push(r),
-            GroupDataComponent::Group(g) => records.append(&mut g.get_data().get_records_recurse()),
-            _ => {}
-          }
+    if let Self::Structured(s) = self {
+      records.reserve_exact(s.len());
+      groups_expanded.reserve_exact(s.len());
+      for component in s {
+        match component {
+          GroupDataComponent::Record(r) => records.push(r),
+          GroupDataComponent::Group(g) => records.append(&mut g.get_data().get_records_recurse()),
+          _ => {}
         }
       }
-      _ => {}
     };
     let mut add_cap = 0;
     for g in &groups_expanded {
@@ -88,44 +145,173 @@ impl GroupData {
     records.shrink_to_fit();
     records
   }
+  pub fn get_records_recurse_mut(&mut self) -> Vec<&mut Record> {
+    let mut records: Vec<&mut Record> = vec![];
+    if let Self::Structured(s) = self {
+      for component in s {
+        match component {
+          GroupDataComponent::Record(r) => records.push(r),
+          GroupDataComponent::Group(g) => {
+            records.append(&mut g.get_data_mut().get_records_recurse_mut())
+          }
+          _ => {}
+        }
+      }
+    };
+    records
+  }
+  pub fn get_raw_data(&self) -> Vec<&RawData> {
+    let mut raw_data: Vec<&RawData> = vec![];
+    if let Self::Structured(s) = self {
+      for component in s {
+        if let GroupDataComponent::Raw(r) = component {
+          raw_data.push(r)
+        }
+      }
+    };
+    raw_data
+  }
   pub fn get_subgroups(&self) -> Vec<&Group> {
     let mut groups: Vec<&Group> = vec![];
-    match self {
-      Self::Structured(s) => {
-        groups.reserve_exact(s.len());
-        for component in s {
-          if let GroupDataComponent::Group(g) = component {
-            groups.push(g)
-          }
+    if let Self::Structured(s) = self {
+      groups.reserve_exact(s.len());
+      for component in s {
+        if let GroupDataComponent::Group(g) = component {
+          groups.push(g)
         }
       }
-      _ => {}
     };
     groups.shrink_to_fit();
     groups
   }
+  /// The records in this group, each with the group of its children if one follows it
+  pub fn get_records_with_children(&self) -> Vec<(&Record, Option<&Group>)> {
+    let mut records: Vec<(&Record, Option<&Group>)> = vec![];
+    let Self::Structured(s) = self else {
+      return records;
+    };
+    let mut components = s.iter().peekable();
+    while let Some(component) = components.next() {
+      let GroupDataComponent::Record(r) = component else {
+        continue;
+      };
+      let children = components.next_if(|c| match c {
+        GroupDataComponent::Group(g) => g.get_label().get_parent_record() == Some(*r.get_form_id()),
+        _ => false,
+      });
+      match children {
+        Some(GroupDataComponent::Group(g)) => records.push((r, Some(g))),
+        _ => records.push((r, None)),
+      }
+    }
+    records
+  }
 }
 
 /// Process
 impl GroupData {
-  pub fn process(&self) -> Result<Self> {
+  pub fn process(&mut self, diagnostics: &mut Vec<Diagnostic>) -> Result<()> {
+    self.process_with_mode(ParseMode::Strict, diagnostics)
+  }
+  /// Structure and process the group's contents in place, collecting problems with its children
+  /// into `diagnostics`. Children share the group's buffer rather than copying it, and if the
+  /// contents can't be structured the group is left unchanged.
+  pub fn process_with_mode(
+    &mut self,
+    mode: ParseMode,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Result<()> {
+    self.process_with_options(&mode.into(), diagnostics)
+  }
+  /// Structure the group's contents in place and process the children `options` selects
+  pub fn process_with_options(
+    &mut self,
+    options: &ProcessOptions,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Result<()> {
+    self.process_at_depth(options, 0, diagnostics)
+  }
+  /// Process the contents of a group at `depth`, so its subgroups are at `depth + 1`
+  pub(crate) fn process_at_depth(
+    &mut self,
+    options: &ProcessOptions,
+    depth: usize,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Result<()> {
+    if let Self::Raw(b) = self {
+      let structured = GroupData::structure_from_bytes(&mut b.clone(), options.parse_mode)?;
+      *self = Self::Structured(structured);
+    }
+    if let Self::Structured(s) = self {
+      for component in s {
+        diagnostics.append(&mut component.process(options, depth + 1));
+      }
+    }
+    Ok(())
+  }
+}
+/// Setters
+impl GroupData {
+  /// The contents as components, structuring raw or empty data first
+  pub fn structure(&mut self) -> Result<&mut Vec<GroupDataComponent>> {
     match self {
+      Self::Empty => *self = Self::Structured(vec![]),
       Self::Raw(b) => {
-        let mut structured = GroupData::structure_from_bytes(&mut b.clone())?;
-        for component in &mut structured {
-          component.process();
-        }
-        Ok(Self::Structured(structured))
+        let structured = GroupData::structure_from_bytes(&mut b.clone(), ParseMode::Strict)?;
+        *self = Self::Structured(structured);
       }
-      Self::Structured(s) => {
-        let mut s = s.clone();
-        for component in &mut s {
-          component.process();
+      Self::Structured(_) => {}
+    }
+    match self {
+      Self::Structured(s) => Ok(s),
+      _ => unreachable!("group data was structured above"),
+    }
+  }
+  /// Sort the contents the way the Creation Kit does: records by FormID, each keeping its
+  /// children group right after it, followed by the subgroups by label. Subgroup labels are read
+  /// first so children groups are recognised. Contents that hold unreadable data keep their order,
+  /// as do groups that have not been processed.
+  pub fn normalise(&mut self) {
+    let Self::Structured(components) = self else {
+      return;
+    };
+    for component in components.iter_mut() {
+      if let GroupDataComponent::Group(g) = component {
+        g.process_label();
+        g.normalise();
+      }
+    }
+    if components
+      .iter()
+      .any(|c| matches!(c, GroupDataComponent::Empty | GroupDataComponent::Raw(_)))
+    {
+      return;
+    }
+
+    // A record and the group of its children move together
+    let mut units: Vec<Vec<GroupDataComponent>> = vec![];
+    for component in components.drain(..) {
+      let is_child = match (&component, units.last().and_then(|u| u.last())) {
+        (GroupDataComponent::Group(g), Some(GroupDataComponent::Record(r))) => {
+          g.get_label().get_parent_record() == Some(*r.get_form_id())
         }
-        Ok(self.clone())
+        _ => false,
+      };
+      match (is_child, units.last_mut()) {
+        (true, Some(unit)) => unit.push(component),
+        _ => units.push(vec![component]),
       }
-      Self::Empty => Ok(Self::Empty),
     }
+    units.sort_by(|a, b| match (&a[0], &b[0]) {
+      (GroupDataComponent::Record(a), GroupDataComponent::Record(b)) => {
+        a.get_form_id().cmp(b.get_form_id())
+      }
+      (GroupDataComponent::Record(_), _) => Ordering::Less,
+      (_, GroupDataComponent::Record(_)) => Ordering::Greater,
+      (GroupDataComponent::Group(a), GroupDataComponent::Group(b)) => a.cmp(b),
+      _ => Ordering::Equal,
+    });
+    *components = units.into_iter().flatten().collect();
   }
 }
 
@@ -144,29 +330,57 @@ pub enum GroupDataComponent {
   Empty,
   Group(Group),
   Record(Record),
+  Raw(RawData),
 }
 /// Conversion
 impl GroupDataComponent {
-  fn as_bytes(&self) -> Bytes {
+  pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<()> {
     match self {
-      Self::Empty => Bytes::new(),
-      Self::Group(g) => g.as_bytes(),
-      Self::Record(r) => r.as_bytes(),
+      Self::Group(g) => g.to_writer(writer),
+      _ => self.write_sized(writer, &mut [].iter()),
+    }
+  }
+  fn collect_sizes(&self, sizes: &mut Vec<usize>) -> Result<usize> {
+    match self {
+      Self::Group(g) => g.collect_sizes(sizes),
+      Self::Record(r) => r.checked_size(),
+      _ => self.serialized_size(),
+    }
+  }
+  fn write_sized<W: Write>(
+    &self,
+    writer: &mut W,
+    sizes: &mut std::slice::Iter<usize>,
+  ) -> Result<()> {
+    match self {
+      Self::Empty => Ok(()),
+      Self::Group(g) => g.write_sized(writer, sizes),
+      Self::Record(r) => r.to_writer(writer),
+      Self::Raw(r) => Ok(writer.write_all(r.get_data())?),
+    }
+  }
+  pub fn serialized_size(&self) -> Result<usize> {
+    match self {
+      Self::Empty => Ok(0),
+      Self::Group(g) => g.serialized_size(),
+      Self::Record(r) => r.serialized_size(),
+      Self::Raw(r) => Ok(r.len()),
     }
   }
 
-  fn from_bytes(buf: &mut BytesMut) -> Result<GroupDataComponent> {
-    match &buf[0..4] {
-      b"GRUP" => Ok(Group::from_bytes(buf)?.into()),
-      _ => Ok(Record::from_bytes(buf)?.into()),
+  fn from_bytes(buf: &mut Bytes) -> Result<GroupDataComponent> {
+    match buf.starts_with(b"GRUP") {
+      true => Ok(Group::from_bytes(buf)?.into()),
+      false => Ok(Record::from_bytes(buf)?.into()),
     }
   }
 
-  fn process(&mut self) {
+  fn process(&mut self, options: &ProcessOptions, depth: usize) -> Vec<Diagnostic> {
     match self {
-      Self::Group(g) => g.process(),
-      Self::Record(r) => r.process(),
-      _ => {}
+      Self::Group(g) => g.process_at_depth(options, depth),
+      Self::Record(r) => r.process_with_options(options),
+      Self::Raw(r) => vec![r.diagnose()],
+      Self::Empty => vec![],
     }
   }
 }
@@ -181,3 +395,8 @@ impl From<Record> for GroupDataComponent {
     Self::Record(r)
   }
 }
+impl From<RawData> for GroupDataComponent {
+  fn from(r: RawData) -> Self {
+    Self::Raw(r)
+  }
+}
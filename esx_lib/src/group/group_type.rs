@@ -25,6 +25,29 @@ pub enum GroupLabel {
 }
 /// Conversion
 impl GroupLabel {
+  /// The block and sub-block holding an interior cell: the last and second to last decimal digits
+  /// of its object ID
+  pub fn interior_cell_blocks(cell: FormID) -> (Self, Self) {
+    let id = cell.object_id() as i32;
+    (
+      GroupLabel::InteriorCellBlock(id % 10),
+      GroupLabel::InteriorCellSubBlock((id / 10) % 10),
+    )
+  }
+  /// The block and sub-block holding an exterior cell. Blocks span 32 cells and sub-blocks 8 on
+  /// each axis, rounding towards negative infinity.
+  pub fn exterior_cell_blocks(x: i32, y: i32) -> (Self, Self) {
+    (
+      GroupLabel::ExteriorCellBlock {
+        x: (x >> 5) as i16,
+        y: (y >> 5) as i16,
+      },
+      GroupLabel::ExteriorCellSubBlock {
+        x: (x >> 3) as i16,
+        y: (y >> 3) as i16,
+      },
+    )
+  }
   pub fn as_bytes(&self) -> Bytes {
     return match self {
       GroupLabel::Raw { label, label_type } => {
@@ -156,6 +179,20 @@ impl GroupLabel {
     };
   }
 }
+/// Getters
+impl GroupLabel {
+  /// The record a group of children belongs to and must directly follow, such as the CELL before
+  /// its cell children group
+  pub fn get_parent_record(&self) -> Option<FormID> {
+    match self {
+      GroupLabel::WorldChildren(id)
+      | GroupLabel::CellChildren(id)
+      | GroupLabel::TopicChildren(id)
+      | GroupLabel::QuestScene(id) => Some(*id),
+      _ => None,
+    }
+  }
+}
 
 impl Display for GroupLabel {
   fn fmt(&self, f: &mut Formatter) -> fmtResult {
@@ -0,0 +1,97 @@
+use super::GroupLabel;
+use crate::types::{FormID, Signature};
+
+/// Where a record belongs in a plugin's group tree, see [`ESx::insert_record`](crate::ESx::insert_record)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+  /// Directly in the top group of the record's signature
+  Top,
+  /// An interior CELL record, in the block and sub-block of the CELL top group for its FormID
+  InteriorCell,
+  /// An exterior CELL record at grid coordinates in a worldspace
+  ExteriorCell { world: FormID, x: i32, y: i32 },
+  /// The persistent CELL record of a worldspace, directly in the worldspace's children group
+  PersistentCell { world: FormID },
+  /// A reference, navmesh or other child of an interior cell
+  InteriorCellChild { cell: FormID, persistent: bool },
+  /// A reference, navmesh or other child of an exterior cell at grid coordinates
+  ExteriorCellChild {
+    world: FormID,
+    cell: FormID,
+    x: i32,
+    y: i32,
+    persistent: bool,
+  },
+  /// A persistent reference in a worldspace, kept with the worldspace's persistent `cell` rather
+  /// than the cell at its grid coordinates
+  WorldPersistentChild { world: FormID, cell: FormID },
+  /// An INFO record below its dialogue topic
+  TopicChild { topic: FormID },
+  /// A SCEN or other record in the group of its quest
+  QuestChild { quest: FormID },
+}
+/// Conversion
+impl Placement {
+  /// The labels of the groups holding a record, from its top group down
+  pub fn group_path(&self, signature: Signature, form_id: FormID) -> Vec<GroupLabel> {
+    match *self {
+      Placement::Top => vec![GroupLabel::Top(signature)],
+      Placement::InteriorCell => interior_cell_path(form_id).to_vec(),
+      Placement::ExteriorCell { world, x, y } => exterior_cell_path(world, x, y).to_vec(),
+      Placement::PersistentCell { world } => world_path(world).to_vec(),
+      Placement::InteriorCellChild { cell, persistent } => {
+        let mut path = interior_cell_path(cell).to_vec();
+        path.extend(cell_children_path(cell, persistent));
+        path
+      }
+      Placement::ExteriorCellChild {
+        world,
+        cell,
+        x,
+        y,
+        persistent,
+      } => {
+        let mut path = exterior_cell_path(world, x, y).to_vec();
+        path.extend(cell_children_path(cell, persistent));
+        path
+      }
+      Placement::WorldPersistentChild { world, cell } => {
+        let mut path = world_path(world).to_vec();
+        path.extend(cell_children_path(cell, true));
+        path
+      }
+      Placement::TopicChild { topic } => vec![
+        GroupLabel::Top(Signature::new(b"DIAL")),
+        GroupLabel::TopicChildren(topic),
+      ],
+      Placement::QuestChild { quest } => vec![
+        GroupLabel::Top(Signature::new(b"QUST")),
+        GroupLabel::QuestScene(quest),
+      ],
+    }
+  }
+}
+
+/// Interior cells are grouped by the last and second to last decimal digits of their object ID
+fn interior_cell_path(cell: FormID) -> [GroupLabel; 3] {
+  let (block, sub_block) = GroupLabel::interior_cell_blocks(cell);
+  [GroupLabel::Top(Signature::new(b"CELL")), block, sub_block]
+}
+fn world_path(world: FormID) -> [GroupLabel; 2] {
+  [
+    GroupLabel::Top(Signature::new(b"WRLD")),
+    GroupLabel::WorldChildren(world),
+  ]
+}
+fn exterior_cell_path(world: FormID, x: i32, y: i32) -> [GroupLabel; 4] {
+  let (block, sub_block) = GroupLabel::exterior_cell_blocks(x, y);
+  let [top, children] = world_path(world);
+  [top, children, block, sub_block]
+}
+fn cell_children_path(cell: FormID, persistent: bool) -> [GroupLabel; 2] {
+  let children = match persistent {
+    true => GroupLabel::CellPersistentChildren(cell),
+    false => GroupLabel::CellTemporaryChildren(cell),
+  };
+  [GroupLabel::CellChildren(cell), children]
+}
@@ -0,0 +1,56 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::{diagnostics::Diagnostic, Error, Group};
+
+/// Bytes that could not be read as a record or group, kept as-is so they are written back unchanged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub struct RawData {
+  data: Bytes,
+  diagnostic: String,
+}
+/// Conversion
+impl RawData {
+  pub fn new(data: Bytes, diagnostic: String) -> Self {
+    Self { data, diagnostic }
+  }
+  /// Split off everything up to the next plausible group header after the start of the buffer, or
+  /// the rest of the buffer if there is none. Always consumes at least one byte.
+  pub fn recover(buf: &mut Bytes, error: Error) -> Self {
+    let end = (1..buf.len())
+      .find(|&p| Self::is_group_header(&buf[p..]))
+      .unwrap_or(buf.len());
+    Self {
+      data: buf.split_to(end),
+      diagnostic: format!("{:?}", error),
+    }
+  }
+  /// A `GRUP` signature with a known label type and a size that fits in the buffer
+  fn is_group_header(buf: &[u8]) -> bool {
+    if buf.len() < Group::HEADER_SIZE || &buf[0..4] != b"GRUP" {
+      return false;
+    }
+    let size = u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]) as usize;
+    let label_type = u32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]);
+    (Group::HEADER_SIZE..=buf.len()).contains(&size) && label_type <= 10
+  }
+}
+/// Getters
+impl RawData {
+  pub fn get_data(&self) -> &Bytes {
+    &self.data
+  }
+  pub fn get_diagnostic(&self) -> &str {
+    &self.diagnostic
+  }
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+  pub fn diagnose(&self) -> Diagnostic {
+    let message = format!("{} unreadable bytes kept as raw data: {}", self.len(), self.diagnostic);
+    Diagnostic::error(message)
+  }
+}
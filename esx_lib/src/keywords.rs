@@ -0,0 +1,174 @@
+//! Keywords, stored as a KSIZ count followed by a KWDA array of KYWD FormIDs.
+//!
+//! Keywords are FormIDs in the plugin's own master list. Keywords defined in a master are found by
+//! editor ID when the masters are given, and their FormIDs are remapped to the plugin's master
+//! list.
+
+use std::collections::HashMap;
+
+use bytes::{BufMut, BytesMut};
+
+use crate::{
+  record::RecordData,
+  types::{FormID, Signature},
+  ESx, Field, Record, Result,
+};
+
+const KSIZ: Signature = Signature::new(b"KSIZ");
+const KWDA: Signature = Signature::new(b"KWDA");
+
+/// Getters
+impl Record {
+  /// The FormIDs in the record's KWDA field, in order. Records that haven't been processed are
+  /// read from a copy of their data on every call.
+  pub fn get_keywords(&self) -> Vec<FormID> {
+    let field = match self.get_data().is_decoded() {
+      true => self
+        .get_data()
+        .get_fields()
+        .into_iter()
+        .find(|f| *f.get_signature() == KWDA)
+        .cloned(),
+      false => self.read_field(KWDA),
+    };
+    let Some(field) = field else {
+      return vec![];
+    };
+    field
+      .get_data()
+      .as_slice()
+      .chunks_exact(4)
+      .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]).into())
+      .collect()
+  }
+  pub fn has_keyword(&self, keyword: FormID) -> bool {
+    self.get_keywords().contains(&keyword)
+  }
+}
+/// Setters
+impl Record {
+  /// Replaces the record's keywords and sets KSIZ to their number. An empty list removes both
+  /// fields. Records without keywords get them after their other fields; the Creation Kit moves
+  /// them to their usual place when it saves the plugin.
+  pub fn set_keywords(&mut self, keywords: &[FormID]) -> Result<()> {
+    let mut data = self.get_data().clone();
+    data.process()?;
+    let remainder = data.get_remainder().cloned();
+    let mut fields: Vec<Field> = data.get_fields().into_iter().cloned().collect();
+
+    let position = fields
+      .iter()
+      .position(|f| *f.get_signature() == KSIZ || *f.get_signature() == KWDA)
+      .unwrap_or(fields.len());
+    fields.retain(|f| *f.get_signature() != KSIZ && *f.get_signature() != KWDA);
+    if !keywords.is_empty() {
+      let mut kwda = BytesMut::with_capacity(keywords.len() * 4);
+      for keyword in keywords {
+        kwda.put_u32_le(keyword.raw());
+      }
+      let count = Field::from_u32(KSIZ, keywords.len() as u32);
+      let position = position.min(fields.len());
+      fields.splice(position..position, [count, Field::new(KWDA, kwda.freeze())]);
+    }
+
+    self.set_data(match remainder {
+      Some(remainder) => RecordData::Partial(fields, remainder),
+      None => RecordData::Generic(fields),
+    });
+    Ok(())
+  }
+  /// Adds a keyword after the others, returning whether the record didn't already have it
+  pub fn add_keyword(&mut self, keyword: FormID) -> Result<bool> {
+    let mut keywords = self.get_keywords();
+    if keywords.contains(&keyword) {
+      return Ok(false);
+    }
+    keywords.push(keyword);
+    self.set_keywords(&keywords)?;
+    Ok(true)
+  }
+  /// Removes a keyword, returning whether the record had it
+  pub fn remove_keyword(&mut self, keyword: FormID) -> Result<bool> {
+    let mut keywords = self.get_keywords();
+    let count = keywords.len();
+    keywords.retain(|k| *k != keyword);
+    if keywords.len() == count {
+      return Ok(false);
+    }
+    self.set_keywords(&keywords)?;
+    Ok(true)
+  }
+}
+
+/// Getters
+impl ESx {
+  /// The FormID of the KYWD record in this plugin with this editor ID, ignoring case
+  pub fn get_keyword_id(&self, editor_id: &str) -> Option<FormID> {
+    let group = self.get_top_group(Signature::new(b"KYWD"))?;
+    group
+      .get_data()
+      .get_records_recurse()
+      .into_iter()
+      .find(|record| {
+        record
+          .read_field(Signature::new(b"EDID"))
+          .is_some_and(|f| f.get_zstring().eq_ignore_ascii_case(editor_id))
+      })
+      .map(|record| *record.get_form_id())
+  }
+  /// The FormID in this plugin's master list of the keyword with this editor ID, from this plugin
+  /// or the last of its masters that has it. `masters` pairs the file name of each master with its
+  /// contents, in load order; keywords of files that aren't in this plugin's master list are
+  /// skipped, as the plugin can't refer to them.
+  pub fn get_keyword_id_in(&self, masters: &[(&str, &ESx)], editor_id: &str) -> Option<FormID> {
+    if let Some(keyword) = self.get_keyword_id(editor_id) {
+      return Some(keyword);
+    }
+    let own_masters = self.get_masters();
+    masters.iter().rev().find_map(|(name, esx)| {
+      let keyword = esx.get_keyword_id(editor_id)?;
+      let masters = esx.get_masters();
+      let file = masters
+        .get(keyword.master_index() as usize)
+        .map_or(*name, String::as_str);
+      let index = own_masters
+        .iter()
+        .position(|m| m.eq_ignore_ascii_case(file))?;
+      Some(FormID::from((index as u32) << 24 | keyword.object_id()))
+    })
+  }
+  /// The records with each keyword, reading each record's keywords once for any number of
+  /// lookups
+  pub fn get_keyword_index(&self) -> HashMap<FormID, Vec<&Record>> {
+    let mut index: HashMap<FormID, Vec<&Record>> = HashMap::new();
+    for record in self.get_all_records() {
+      for keyword in record.get_keywords() {
+        let records = index.entry(keyword).or_default();
+        if records.last().is_none_or(|r| !std::ptr::eq(*r, record)) {
+          records.push(record);
+        }
+      }
+    }
+    index
+  }
+  /// Every record with this keyword
+  pub fn get_records_with_keyword(&self, keyword: FormID) -> Vec<&Record> {
+    self
+      .get_all_records()
+      .into_iter()
+      .filter(|record| record.has_keyword(keyword))
+      .collect()
+  }
+  /// Every record with the keyword of this editor ID, from this plugin or its masters, see
+  /// [`ESx::get_keyword_id_in`]
+  pub fn get_records_with_keyword_named(
+    &self,
+    masters: &[(&str, &ESx)],
+    editor_id: &str,
+  ) -> Vec<&Record> {
+    match self.get_keyword_id_in(masters, editor_id) {
+      Some(keyword) => self.get_records_with_keyword(keyword),
+      None => vec![],
+    }
+  }
+}
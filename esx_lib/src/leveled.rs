@@ -0,0 +1,468 @@
+//! Leveled lists and merged leveled list patches.
+//!
+//! Leveled items, actors and spells (LVLI, LVLN and LVSP) share one layout: a few list-wide
+//! fields followed by an LLCT count and an LVLO field for each entry, each optionally followed by
+//! a COED field with ownership data. Fallout 4 adds a maximum count and a chance none per entry.
+//!
+//! When several plugins override the same list only the last one wins, dropping the entries the
+//! others added. [`merge_leveled_lists`] builds a plugin that combines them.
+
+use std::collections::HashMap;
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::{
+  codec::{FieldCodec, FieldReader, Lossless, RecordCodec},
+  group::Placement,
+  types::{FormID, Signature},
+  Diagnostic, ESx, Error, Field, Record, Result,
+};
+
+const LVLD: Signature = Signature::new(b"LVLD");
+const LVLM: Signature = Signature::new(b"LVLM");
+const LVLF: Signature = Signature::new(b"LVLF");
+const LVLG: Signature = Signature::new(b"LVLG");
+const LLCT: Signature = Signature::new(b"LLCT");
+const LVLO: Signature = Signature::new(b"LVLO");
+const COED: Signature = Signature::new(b"COED");
+
+/// The record types that are leveled lists
+pub const LEVELED_LISTS: [Signature; 3] = [
+  Signature::new(b"LVLI"),
+  Signature::new(b"LVLN"),
+  Signature::new(b"LVSP"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeveledList {
+  pub chance_none: Option<Lossless<u8>>,
+  /// The most entries picked, in Fallout 4
+  pub max_count: Option<Lossless<u8>>,
+  /// Calculate from all levels, for each item in count and similar flags. Fallout 4 stores two
+  /// bytes; the second is kept as the remainder.
+  pub flags: Option<Lossless<u8>>,
+  /// A global whose value replaces the chance none
+  pub global: Option<Lossless<FormID>>,
+  /// The LLCT field, rewritten from the number of entries
+  count: Option<Lossless<u8>>,
+  pub entries: Vec<LeveledEntry>,
+}
+
+/// An LVLO field and the COED field that follows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeveledEntry {
+  pub level: u16,
+  pub reference: FormID,
+  pub count: u16,
+  /// The entry's own chance none in Fallout 4, padding in earlier games
+  pub chance_none: u8,
+  pub owner: Option<Lossless<Owner>>,
+  padding: [u8; 3],
+  /// Bytes after the 12 that are read
+  remainder: Bytes,
+}
+
+/// Ownership of an entry's item, from a COED field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Owner {
+  pub owner: FormID,
+  /// The required faction rank, or a global for NPC owners
+  pub value: u32,
+  /// The item's health
+  pub condition: f32,
+}
+
+/// Conversion
+impl LeveledEntry {
+  pub fn new(level: u16, reference: FormID, count: u16) -> Self {
+    LeveledEntry {
+      level,
+      reference,
+      count,
+      chance_none: 0,
+      owner: None,
+      padding: [0; 3],
+      remainder: Bytes::new(),
+    }
+  }
+  fn to_fields(&self) -> Vec<Field> {
+    let mut buf = BytesMut::with_capacity(12 + self.remainder.len());
+    self.encode(&mut buf);
+    buf.put_slice(&self.remainder);
+    let mut fields = vec![Field::new(LVLO, buf.freeze())];
+    if let Some(owner) = &self.owner {
+      fields.push(Field::from_lossless(COED, owner));
+    }
+    fields
+  }
+}
+impl FieldCodec for LeveledEntry {
+  fn decode(data: &[u8]) -> Result<(Self, usize)> {
+    let Some(data) = data.get(..12) else {
+      return Err(Error::BufferTooShort);
+    };
+    let entry = LeveledEntry {
+      level: u16::from_le_bytes([data[0], data[1]]),
+      reference: FormID::decode(&data[4..])?.0,
+      count: u16::from_le_bytes([data[8], data[9]]),
+      chance_none: data[10],
+      owner: None,
+      padding: [data[2], data[3], data[11]],
+      remainder: Bytes::new(),
+    };
+    Ok((entry, 12))
+  }
+  /// Writes the 12 bytes that are read, without the remainder
+  fn encode(&self, buf: &mut BytesMut) {
+    buf.put_u16_le(self.level);
+    buf.put_slice(&self.padding[..2]);
+    self.reference.encode(buf);
+    buf.put_u16_le(self.count);
+    buf.put_u8(self.chance_none);
+    buf.put_u8(self.padding[2]);
+  }
+}
+impl FieldCodec for Owner {
+  fn decode(data: &[u8]) -> Result<(Self, usize)> {
+    let (owner, _) = FormID::decode(data)?;
+    let (value, _) = u32::decode(data.get(4..).unwrap_or_default())?;
+    let (condition, _) = f32::decode(data.get(8..).unwrap_or_default())?;
+    let owner = Owner {
+      owner,
+      value,
+      condition,
+    };
+    Ok((owner, 12))
+  }
+  fn encode(&self, buf: &mut BytesMut) {
+    self.owner.encode(buf);
+    self.value.encode(buf);
+    self.condition.encode(buf);
+  }
+}
+
+impl RecordCodec for LeveledList {
+  const SIGNATURE: Signature = LEVELED_LISTS[0];
+  const FIELDS: &'static [Signature] = &[LVLD, LVLM, LVLF, LVLG, LLCT, LVLO, COED];
+
+  fn supports_signature(signature: Signature) -> bool {
+    LEVELED_LISTS.contains(&signature)
+  }
+  fn decode(reader: &mut FieldReader) -> Result<Self> {
+    let mut list = LeveledList {
+      chance_none: None,
+      max_count: None,
+      flags: None,
+      global: None,
+      count: None,
+      entries: vec![],
+    };
+    while let Some(field) = reader.next_field() {
+      match field.get_signature().as_array() {
+        b"LVLD" => list.chance_none = Some(field.decode()?),
+        b"LVLM" => list.max_count = Some(field.decode()?),
+        b"LVLF" => list.flags = Some(field.decode()?),
+        b"LVLG" => list.global = Some(field.decode()?),
+        b"LLCT" => list.count = Some(field.decode()?),
+        b"LVLO" => {
+          let decoded = field.decode::<LeveledEntry>()?;
+          let remainder = decoded.get_remainder().clone();
+          let mut entry = decoded.into_value();
+          entry.remainder = remainder;
+          list.entries.push(entry);
+        }
+        _ => match list.entries.last_mut() {
+          Some(entry) => entry.owner = Some(field.decode()?),
+          None => return Err(Error::UnexpectedField(COED)),
+        },
+      }
+    }
+    Ok(list)
+  }
+  fn encode(&self, fields: &mut Vec<Field>) {
+    for (signature, value) in [
+      (LVLD, &self.chance_none),
+      (LVLM, &self.max_count),
+      (LVLF, &self.flags),
+    ] {
+      if let Some(value) = value {
+        fields.push(Field::from_lossless(signature, value));
+      }
+    }
+    if let Some(global) = &self.global {
+      fields.push(Field::from_lossless(LVLG, global));
+    }
+    if let Some(count) = &self.count {
+      let mut count = count.clone();
+      *count.get_value_mut() = self.entries.len().min(u8::MAX as usize) as u8;
+      fields.push(Field::from_lossless(LLCT, &count));
+    }
+    for entry in &self.entries {
+      fields.extend(entry.to_fields());
+    }
+  }
+}
+
+/// Builds a plugin overriding every leveled list that at least two plugins of the load order
+/// override, with the entries any of them add and without the entries any of them remove. Other
+/// list settings come from the last plugin that changes them.
+///
+/// `load_order` pairs each plugin's file name with its contents, whose top groups must have been
+/// processed. The patch lists the plugins it takes lists from, and their masters, as its masters.
+/// Fields the codec doesn't read are copied from the last override as they are, without
+/// remapping any FormIDs in them.
+///
+/// Lists with a version that can't be decoded are left out of the patch, with a diagnostic naming
+/// the plugin for each malformed version.
+pub fn merge_leveled_lists(load_order: &[(&str, &ESx)]) -> Result<(ESx, Vec<Diagnostic>)> {
+  let Some((_, last)) = load_order.last() else {
+    return Err(Error::EmptyLoadOrder);
+  };
+  let plugins: Vec<Plugin> = load_order
+    .iter()
+    .map(|(name, esx)| Plugin {
+      name: name.to_string(),
+      masters: esx.get_masters(),
+    })
+    .collect();
+
+  // Every version of each list, in load order
+  let mut lists: Vec<(FormKey, Vec<(usize, &Record)>)> = vec![];
+  let mut index: HashMap<FormKey, usize> = HashMap::new();
+  for (p, (_, esx)) in load_order.iter().enumerate() {
+    for signature in LEVELED_LISTS {
+      let Some(group) = esx.get_top_group(signature) else {
+        continue;
+      };
+      for record in group.get_data().get_records_recurse() {
+        if *record.get_signature() != signature {
+          continue;
+        }
+        let key = plugins[p].resolve(*record.get_form_id());
+        let i = *index.entry(key.clone()).or_insert_with(|| {
+          lists.push((key, vec![]));
+          lists.len() - 1
+        });
+        lists[i].1.push((p, record));
+      }
+    }
+  }
+  lists.retain(|(_, versions)| versions.len() > 2);
+
+  let mut used = vec![false; plugins.len()];
+  for (_, versions) in &lists {
+    for (p, _) in versions {
+      used[*p] = true;
+    }
+  }
+  let masters = Masters::new(&plugins, &used);
+
+  let mut patch = vec![];
+  let mut diagnostics = vec![];
+  for (key, versions) in &lists {
+    let mut decoded = vec![];
+    for (p, record) in versions {
+      let mut list = match record.decode::<LeveledList>() {
+        Ok(Some(list)) => list,
+        Ok(None) => break,
+        Err(e) => {
+          let message = format!(
+            "List in {} could not be read and is not merged: {:?}",
+            plugins[*p].name, e
+          );
+          diagnostics.push(Diagnostic::error(message).within(record.path_segment()));
+          break;
+        }
+      };
+      list
+        .get_value_mut()
+        .remap(&|id| masters.remap(plugins[*p].resolve(id)));
+      decoded.push(list);
+    }
+    if decoded.len() < versions.len() {
+      continue;
+    }
+    let (base, overrides) = decoded.split_first().unwrap();
+    let overrides: Vec<&LeveledList> = overrides.iter().map(|l| l.get_value()).collect();
+    let merged = base.get_value().merge(&overrides);
+
+    let (_, winner) = versions.last().unwrap();
+    let mut lossless = decoded.last().unwrap().clone();
+    *lossless.get_value_mut() = merged;
+    let mut record = Record::new(*winner.get_signature(), masters.remap(key.clone()), vec![]);
+    record.set_form_version(*winner.get_form_version());
+    record.set_flags(*winner.get_flags())?;
+    record.encode(&lossless);
+    patch.push(record);
+  }
+
+  let mut groups: Vec<Signature> = patch.iter().map(|r| *r.get_signature()).collect();
+  groups.sort();
+  groups.dedup();
+  let count = patch.len() + groups.len();
+  let mut esx = ESx::new(patch_header(last, &masters.names, count as u32));
+  for record in patch {
+    esx.insert_record(record, Placement::Top)?;
+  }
+  esx.normalise_for(last.detect_game());
+  Ok((esx, diagnostics))
+}
+
+/// Process
+impl LeveledList {
+  /// Combines the changes each override makes to this list. An entry added by an override is
+  /// kept, as many times as that override has it; an entry an override removes is removed.
+  pub fn merge(&self, overrides: &[&LeveledList]) -> LeveledList {
+    let count = |entries: &[LeveledEntry], entry: &LeveledEntry| {
+      entries.iter().filter(|e| *e == entry).count()
+    };
+    let mut merged = self.clone();
+    for list in overrides {
+      let mut seen: Vec<&LeveledEntry> = vec![];
+      for entry in self.entries.iter().chain(&list.entries) {
+        if seen.contains(&entry) {
+          continue;
+        }
+        seen.push(entry);
+        let (base, wanted) = (count(&self.entries, entry), count(&list.entries, entry));
+        let have = count(&merged.entries, entry);
+        if wanted > base && have < wanted {
+          merged
+            .entries
+            .extend(std::iter::repeat_n(entry.clone(), wanted - have));
+        }
+        if wanted < base && have > wanted {
+          for _ in wanted..have {
+            let last = merged.entries.iter().rposition(|e| e == entry).unwrap();
+            merged.entries.remove(last);
+          }
+        }
+      }
+    }
+    merged.entries.sort_by_key(|e| e.level);
+    merged.entries.truncate(u8::MAX as usize);
+
+    merged.chance_none = last_change(&self.chance_none, overrides, |l| &l.chance_none);
+    merged.max_count = last_change(&self.max_count, overrides, |l| &l.max_count);
+    merged.flags = last_change(&self.flags, overrides, |l| &l.flags);
+    merged.global = last_change(&self.global, overrides, |l| &l.global);
+    merged
+  }
+  /// Rewrites every FormID the list reads
+  pub fn remap(&mut self, remap: &dyn Fn(FormID) -> FormID) {
+    if let Some(global) = &mut self.global {
+      let id = global.get_value_mut();
+      *id = remap(*id);
+    }
+    for entry in &mut self.entries {
+      entry.reference = remap(entry.reference);
+      if let Some(owner) = &mut entry.owner {
+        let owner = owner.get_value_mut();
+        owner.owner = remap(owner.owner);
+      }
+    }
+  }
+}
+
+/// The value from the last override that changes it from the base list's
+fn last_change<T: Clone + PartialEq>(
+  base: &T,
+  overrides: &[&LeveledList],
+  get: impl Fn(&LeveledList) -> &T,
+) -> T {
+  overrides
+    .iter()
+    .rev()
+    .map(|list| get(list))
+    .find(|value| *value != base)
+    .unwrap_or(base)
+    .clone()
+}
+
+/// A form identified by the file that defines it, independent of any plugin's master list
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FormKey {
+  /// The lowercase file name, or `None` for the null FormID
+  file: Option<String>,
+  object_id: u32,
+}
+
+struct Plugin {
+  name: String,
+  masters: Vec<String>,
+}
+impl Plugin {
+  fn resolve(&self, id: FormID) -> FormKey {
+    if id.is_null() {
+      return FormKey {
+        file: None,
+        object_id: 0,
+      };
+    }
+    let file = match self.masters.get(id.master_index() as usize) {
+      Some(master) => master,
+      None => &self.name,
+    };
+    FormKey {
+      file: Some(file.to_lowercase()),
+      object_id: id.object_id(),
+    }
+  }
+}
+
+/// The master list of the patch
+struct Masters {
+  names: Vec<String>,
+  index: HashMap<String, usize>,
+}
+impl Masters {
+  /// Each used plugin's masters followed by the plugin, in load order
+  fn new(plugins: &[Plugin], used: &[bool]) -> Self {
+    let mut masters = Masters {
+      names: vec![],
+      index: HashMap::new(),
+    };
+    for (plugin, _) in plugins.iter().zip(used).filter(|(_, used)| **used) {
+      for name in plugin.masters.iter().chain([&plugin.name]) {
+        if !masters.index.contains_key(&name.to_lowercase()) {
+          masters
+            .index
+            .insert(name.to_lowercase(), masters.names.len());
+          masters.names.push(name.clone());
+        }
+      }
+    }
+    masters
+  }
+  fn remap(&self, key: FormKey) -> FormID {
+    match key.file.and_then(|file| self.index.get(&file)) {
+      Some(index) => FormID::from((*index as u32) << 24 | key.object_id),
+      None => FormID::from(0),
+    }
+  }
+}
+
+/// A header for the patch, with the version of the last plugin's header and the number of records
+/// and groups in the patch
+fn patch_header(last: &ESx, masters: &[String], count: u32) -> Record {
+  let header = last.get_header_record();
+  let version = header
+    .read_field(Signature::new(b"HEDR"))
+    .and_then(|f| f.get_f32())
+    .unwrap_or_default();
+  let mut hedr = BytesMut::with_capacity(12);
+  hedr.put_f32_le(version);
+  hedr.put_u32_le(count);
+  hedr.put_u32_le(0x800);
+  let mut fields = vec![
+    Field::new(Signature::new(b"HEDR"), hedr.freeze()),
+    Field::from_zstring(Signature::new(b"CNAM"), "DEFAULT"),
+  ];
+  for master in masters {
+    fields.push(Field::from_zstring(Signature::new(b"MAST"), master));
+    fields.push(Field::new(Signature::new(b"DATA"), vec![0; 8]));
+  }
+  let mut record = Record::new(Signature::new(b"TES4"), FormID::from(0), fields);
+  record.set_form_version(*header.get_form_version());
+  record
+}
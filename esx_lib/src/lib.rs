@@ -18,12 +18,26 @@ mod tests;
 pub mod error;
 pub use error::{Error, Result};
 
+pub mod codec;
+pub mod condition;
+pub mod diagnostics;
+pub mod dialogue;
 pub mod esx;
 pub mod field;
 pub mod group;
+pub mod keywords;
+pub mod leveled;
+pub mod options;
+pub mod query;
+pub mod quest;
 pub mod record;
+pub mod registry;
 pub mod types;
+pub mod vcs;
+pub mod vmad;
+pub mod world;
 
+pub use diagnostics::Diagnostic;
 pub use esx::ESx;
 pub use field::Field;
 pub use group::Group;
@@ -0,0 +1,87 @@
+use crate::{group::GroupLabel, registry, types::Signature};
+
+/// How corrupt records and groups are handled while parsing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ParseMode {
+  /// Fail on the first record or group that cannot be read
+  #[default]
+  Strict,
+  /// Keep unreadable data as an opaque [`RawData`](crate::group::RawData) blob and resume at the
+  /// next group boundary
+  Lenient,
+}
+
+/// What processing decodes. Anything that isn't selected stays raw and is written back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ProcessOptions {
+  /// Only decode records with these signatures, and only structure the top groups that can hold
+  /// them. `None` decodes every record.
+  pub signatures: Option<Vec<Signature>>,
+  /// Only read the labels of top groups, leaving their contents raw
+  pub top_groups_only: bool,
+  /// Leave compressed records compressed
+  pub skip_decompression: bool,
+  /// The deepest group to structure, where top groups are depth 0
+  pub max_depth: Option<usize>,
+  pub parse_mode: ParseMode,
+}
+/// Conversion
+impl ProcessOptions {
+  pub fn new() -> Self {
+    Self::default()
+  }
+  pub fn with_signatures(mut self, signatures: impl IntoIterator<Item = Signature>) -> Self {
+    self.signatures = Some(signatures.into_iter().collect());
+    self
+  }
+  pub fn with_top_groups_only(mut self, top_groups_only: bool) -> Self {
+    self.top_groups_only = top_groups_only;
+    self
+  }
+  pub fn with_skip_decompression(mut self, skip_decompression: bool) -> Self {
+    self.skip_decompression = skip_decompression;
+    self
+  }
+  pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+    self.max_depth = Some(max_depth);
+    self
+  }
+  pub fn with_parse_mode(mut self, parse_mode: ParseMode) -> Self {
+    self.parse_mode = parse_mode;
+    self
+  }
+}
+/// Getters
+impl ProcessOptions {
+  pub fn selects_record(&self, signature: &Signature) -> bool {
+    match &self.signatures {
+      Some(signatures) => signatures.contains(signature),
+      None => true,
+    }
+  }
+  /// Whether a top group holds records of a selected signature, directly or in nested groups.
+  /// Labels other than [`GroupLabel::Top`] are always selected.
+  pub fn selects_group(&self, label: &GroupLabel) -> bool {
+    match (label, &self.signatures) {
+      (GroupLabel::Top(top), Some(signatures)) => signatures
+        .iter()
+        .any(|s| s == top || registry::nested_records(*top).contains(s)),
+      _ => true,
+    }
+  }
+  pub fn structures_depth(&self, depth: usize) -> bool {
+    match self.max_depth {
+      Some(max_depth) => depth <= max_depth,
+      None => true,
+    }
+  }
+}
+
+impl From<ParseMode> for ProcessOptions {
+  fn from(parse_mode: ParseMode) -> Self {
+    Self {
+      parse_mode,
+      ..Default::default()
+    }
+  }
+}
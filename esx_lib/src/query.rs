@@ -0,0 +1,322 @@
+//! A small query language for finding records.
+//!
+//! A query names the record types to search, or `*` for every type, followed by an optional
+//! condition that may start with `where`:
+//!
+//! ```text
+//! WEAP where EDID ~ "Iron.*" and DATA.damage > 10
+//! * referencing 00012EB7
+//! NPC_ flags.Essential
+//! WEAP, ARMO where form_version < 44 or not FULL
+//! ```
+//!
+//! Conditions combine tests with `and`, `or`, `not` and parentheses. A test is one of:
+//! - `signature`, `form_id`, `form_version` or `flags`, from the record header
+//! - `flags.Essential`, a named flag of the record's schema or header (see
+//!   [`registry::record_flag`]), or `flags.0x400` for header bits
+//! - `EDID`, a field, which on its own tests that the record has one
+//! - `DATA[8]` or `DATA[8..10]`, bytes of a field
+//! - `DATA.damage`, a member of a field in the schema (see [`registry::schema_field`])
+//! - `referencing 00012EB7`, whether any field holds the FormID
+//!
+//! and may be compared with `=`, `!=`, `<`, `<=`, `>` or `>=`, or matched against a regular
+//! expression with `~` or `!~`. Fields compare as strings against `"text"`, as little-endian
+//! numbers against numbers and as bytes against `x"0A 00"`. Numbers of exactly eight hex digits
+//! are FormIDs. A test on a repeated field passes if any of its copies passes.
+
+use std::cell::OnceCell;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use bytes::Bytes;
+use regex::Regex;
+
+use crate::{
+  registry::{self, SchemaField, SchemaType},
+  types::{FormID, Game, Signature},
+  ESx, Error, Field, Record, Result,
+};
+
+mod parser;
+
+/// A parsed query, see the [module documentation](self)
+#[derive(Debug, Clone)]
+pub struct Query {
+  /// The record types searched, or `None` for every type
+  signatures: Option<Vec<Signature>>,
+  filter: Option<Expr>,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+  And(Box<Expr>, Box<Expr>),
+  Or(Box<Expr>, Box<Expr>),
+  Not(Box<Expr>),
+  /// A path on its own: whether a field is present, or whether a value is non-zero
+  Test(Path),
+  Compare(Path, Op, Literal),
+  Match(Path, Regex, bool),
+  Referencing(FormID),
+}
+
+#[derive(Debug, Clone)]
+enum Path {
+  Signature,
+  FormID,
+  FormVersion,
+  Flags,
+  Flag(String),
+  FlagMask(u32),
+  Field(Signature),
+  /// A byte range of a field
+  Bytes(Signature, usize, usize),
+  Member(Signature, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+  Equal,
+  NotEqual,
+  Less,
+  LessOrEqual,
+  Greater,
+  GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+  Int(i64),
+  Float(f64),
+  String(String),
+  Bytes(Vec<u8>),
+}
+
+/// A value read from a record
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+  Int(i64),
+  Float(f64),
+  String(String),
+  Bytes(Bytes),
+  Bool(bool),
+}
+
+/// Conversion
+impl Query {
+  pub fn parse(text: &str) -> Result<Self> {
+    parser::parse(text)
+  }
+}
+impl FromStr for Query {
+  type Err = Error;
+  fn from_str(s: &str) -> Result<Self> {
+    Self::parse(s)
+  }
+}
+/// Getters
+impl Query {
+  /// The record types the query searches, or `None` for every type. Only groups of these types
+  /// need to be processed.
+  pub fn get_signatures(&self) -> Option<&[Signature]> {
+    self.signatures.as_deref()
+  }
+}
+/// Process
+impl Query {
+  /// Whether the record matches. Schema members and flags are looked up for `game`, or for any
+  /// game if it is unknown.
+  pub fn matches(&self, record: &Record, game: Option<Game>) -> bool {
+    if let Some(signatures) = &self.signatures {
+      if !signatures.contains(record.get_signature()) {
+        return false;
+      }
+    }
+    let context = Context {
+      record,
+      game,
+      fields: OnceCell::new(),
+    };
+    match &self.filter {
+      Some(filter) => context.eval(filter),
+      None => true,
+    }
+  }
+}
+
+/// Process
+impl ESx {
+  /// Every processed record matching the query, for the detected game
+  pub fn query(&self, query: &Query) -> Vec<&Record> {
+    let game = self.detect_game();
+    self
+      .get_all_records()
+      .into_iter()
+      .filter(|record| query.matches(record, game))
+      .collect()
+  }
+}
+
+/// A record being tested, with its fields read when first needed
+struct Context<'a> {
+  record: &'a Record,
+  game: Option<Game>,
+  fields: OnceCell<Vec<Field>>,
+}
+impl Context<'_> {
+  fn fields(&self) -> &[Field] {
+    self
+      .fields
+      .get_or_init(|| self.record.read_fields().unwrap_or_default())
+  }
+  fn fields_of(&self, signature: Signature) -> impl Iterator<Item = &Field> {
+    self
+      .fields()
+      .iter()
+      .filter(move |f| *f.get_signature() == signature)
+  }
+  fn eval(&self, expr: &Expr) -> bool {
+    match expr {
+      Expr::And(a, b) => self.eval(a) && self.eval(b),
+      Expr::Or(a, b) => self.eval(a) || self.eval(b),
+      Expr::Not(a) => !self.eval(a),
+      Expr::Test(Path::Field(signature)) => self.fields_of(*signature).next().is_some(),
+      Expr::Test(path) => self.values(path).iter().any(Value::is_truthy),
+      Expr::Compare(path, op, literal) => self
+        .values(path)
+        .iter()
+        .any(|value| value.compare(literal).is_some_and(|o| op.test(o))),
+      Expr::Match(path, regex, negated) => {
+        let found = self
+          .values(path)
+          .iter()
+          .any(|value| regex.is_match(&value.to_text()));
+        found != *negated
+      }
+      Expr::Referencing(form_id) => {
+        let needle = form_id.raw().to_le_bytes();
+        self.fields().iter().any(|f| {
+          let data = f.get_data().as_slice();
+          data.windows(4).any(|w| w == needle)
+        })
+      }
+    }
+  }
+  fn values(&self, path: &Path) -> Vec<Value> {
+    let record = self.record;
+    let signature = *record.get_signature();
+    match path {
+      Path::Signature => vec![Value::String(signature.to_string())],
+      Path::FormID => vec![Value::Int(record.get_form_id().raw() as i64)],
+      Path::FormVersion => vec![Value::Int(*record.get_form_version() as i64)],
+      Path::Flags => vec![Value::Int(*record.get_flags() as i64)],
+      Path::FlagMask(mask) => vec![Value::Bool(record.get_flags() & mask != 0)],
+      Path::Flag(name) => match registry::record_flag(self.game, signature, name) {
+        Some((Some(member), bit)) => self
+          .member_values(member)
+          .into_iter()
+          .map(|value| Value::Bool(matches!(value, Value::Int(v) if v & bit as i64 != 0)))
+          .collect(),
+        Some((None, bit)) => vec![Value::Bool(record.get_flags() & bit != 0)],
+        None => vec![],
+      },
+      Path::Field(field) => self
+        .fields_of(*field)
+        .map(|f| Value::Bytes(f.get_bytes()))
+        .collect(),
+      Path::Bytes(field, start, end) => self
+        .fields_of(*field)
+        .filter(|f| *end <= f.len())
+        .map(|f| Value::Bytes(f.get_bytes().slice(*start..*end)))
+        .collect(),
+      Path::Member(field, name) => {
+        match registry::schema_field(self.game, signature, *field, name) {
+          Some(member) => self.member_values(member),
+          None => vec![],
+        }
+      }
+    }
+  }
+  fn member_values(&self, member: &SchemaField) -> Vec<Value> {
+    self
+      .fields_of(member.field)
+      .filter_map(|f| read_member(f.get_data().as_slice(), member))
+      .collect()
+  }
+}
+
+fn read_member(data: &[u8], member: &SchemaField) -> Option<Value> {
+  let data = data.get(member.offset..)?;
+  let value = match member.kind {
+    SchemaType::U8 => Value::Int(*data.first()? as i64),
+    SchemaType::U16 => Value::Int(u16::from_le_bytes(data.get(..2)?.try_into().ok()?) as i64),
+    SchemaType::I16 => Value::Int(i16::from_le_bytes(data.get(..2)?.try_into().ok()?) as i64),
+    SchemaType::U32 | SchemaType::FormID => {
+      Value::Int(u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as i64)
+    }
+    SchemaType::I32 => Value::Int(i32::from_le_bytes(data.get(..4)?.try_into().ok()?) as i64),
+    SchemaType::F32 => Value::Float(f32::from_le_bytes(data.get(..4)?.try_into().ok()?) as f64),
+  };
+  Some(value)
+}
+
+impl Op {
+  fn test(self, ordering: Ordering) -> bool {
+    match self {
+      Op::Equal => ordering.is_eq(),
+      Op::NotEqual => ordering.is_ne(),
+      Op::Less => ordering.is_lt(),
+      Op::LessOrEqual => ordering.is_le(),
+      Op::Greater => ordering.is_gt(),
+      Op::GreaterOrEqual => ordering.is_ge(),
+    }
+  }
+}
+
+impl Value {
+  fn is_truthy(&self) -> bool {
+    match self {
+      Value::Int(v) => *v != 0,
+      Value::Float(v) => *v != 0.0,
+      Value::String(s) => !s.is_empty(),
+      Value::Bytes(b) => b.iter().any(|b| *b != 0),
+      Value::Bool(b) => *b,
+    }
+  }
+  /// The value as text for regular expressions; field bytes are read as a zstring
+  fn to_text(&self) -> String {
+    match self {
+      Value::Int(v) => v.to_string(),
+      Value::Float(v) => v.to_string(),
+      Value::String(s) => s.clone(),
+      Value::Bytes(b) => zstring(b),
+      Value::Bool(b) => b.to_string(),
+    }
+  }
+  fn compare(&self, literal: &Literal) -> Option<Ordering> {
+    match (self, literal) {
+      (Value::Int(a), Literal::Int(b)) => Some(a.cmp(b)),
+      (Value::Int(a), Literal::Float(b)) => (*a as f64).partial_cmp(b),
+      (Value::Float(a), Literal::Int(b)) => a.partial_cmp(&(*b as f64)),
+      (Value::Float(a), Literal::Float(b)) => a.partial_cmp(b),
+      (Value::Bool(a), Literal::Int(b)) => Some((*a as i64).cmp(b)),
+      (Value::String(a), Literal::String(b)) => Some(a.as_str().cmp(b)),
+      (Value::Bytes(a), Literal::String(b)) => Some(zstring(a).as_str().cmp(b)),
+      (Value::Bytes(a), Literal::Bytes(b)) => Some(a.as_ref().cmp(b.as_slice())),
+      (Value::Bytes(a), Literal::Int(b)) if (1..=8).contains(&a.len()) => {
+        let mut bytes = [0; 8];
+        bytes[..a.len()].copy_from_slice(a);
+        Some(i64::from_le_bytes(bytes).cmp(b))
+      }
+      (Value::Bytes(a), Literal::Float(b)) => match a.len() {
+        4 => (f32::from_le_bytes(a[..].try_into().ok()?) as f64).partial_cmp(b),
+        8 => f64::from_le_bytes(a[..].try_into().ok()?).partial_cmp(b),
+        _ => None,
+      },
+      _ => None,
+    }
+  }
+}
+fn zstring(data: &[u8]) -> String {
+  let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+  String::from_utf8_lossy(&data[..end]).into_owned()
+}
@@ -0,0 +1,335 @@
+use regex::Regex;
+
+use super::{Expr, Literal, Op, Path, Query};
+use crate::{types::Signature, Error, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Ident(String),
+  Number(String),
+  String(String),
+  Bytes(Vec<u8>),
+  Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 17] = [
+  "..", "==", "!=", "<=", ">=", "!~", "*", ".", "[", "]", "(", ")", ",", "=", "<", ">", "~",
+];
+
+pub(super) fn parse(text: &str) -> Result<Query> {
+  let mut parser = Parser {
+    tokens: tokenize(text)?,
+    pos: 0,
+    end: text.len(),
+  };
+  let query = parser.query()?;
+  match parser.tokens.get(parser.pos) {
+    Some((at, _)) => Err(invalid(*at, "expected the end of the query")),
+    None => Ok(query),
+  }
+}
+
+fn invalid(at: usize, message: &str) -> Error {
+  Error::InvalidQuery(format!("{} at {}", message, at))
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>> {
+  let chars: Vec<(usize, char)> = text.char_indices().collect();
+  let mut tokens = vec![];
+  let mut i = 0;
+  while i < chars.len() {
+    let (at, c) = chars[i];
+    let next = chars.get(i + 1).map(|(_, c)| *c);
+    if c.is_whitespace() {
+      i += 1;
+    } else if c == '"' {
+      let mut string = String::new();
+      i += 1;
+      loop {
+        match chars.get(i) {
+          Some((_, '"')) => break,
+          Some((_, '\\')) => {
+            let (_, escaped) = chars.get(i + 1).ok_or(invalid(at, "unterminated string"))?;
+            string.push(*escaped);
+            i += 2;
+          }
+          Some((_, c)) => {
+            string.push(*c);
+            i += 1;
+          }
+          None => return Err(invalid(at, "unterminated string")),
+        }
+      }
+      i += 1;
+      tokens.push((at, Token::String(string)));
+    } else if c == 'x' && next == Some('"') {
+      let start = i + 2;
+      let end = (start..chars.len())
+        .find(|j| chars[*j].1 == '"')
+        .ok_or(invalid(at, "unterminated bytes"))?;
+      let hex: String = chars[start..end]
+        .iter()
+        .map(|(_, c)| *c)
+        .filter(|c| !c.is_whitespace())
+        .collect();
+      if !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid(at, "expected pairs of hex digits in bytes"));
+      }
+      let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|j| u8::from_str_radix(&hex[j..j + 2], 16).unwrap())
+        .collect();
+      tokens.push((at, Token::Bytes(bytes)));
+      i = end + 1;
+    } else if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) {
+      let mut number = String::from(c);
+      i += 1;
+      while let Some((_, c)) = chars.get(i) {
+        let after = chars.get(i + 1).map(|(_, c)| *c);
+        if c.is_ascii_alphanumeric() || (*c == '.' && after.is_some_and(|a| a.is_ascii_digit())) {
+          number.push(*c);
+          i += 1;
+        } else {
+          break;
+        }
+      }
+      tokens.push((at, Token::Number(number)));
+    } else if c.is_ascii_alphabetic() || c == '_' {
+      let mut ident = String::new();
+      while let Some((_, c)) = chars
+        .get(i)
+        .filter(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+      {
+        ident.push(*c);
+        i += 1;
+      }
+      tokens.push((at, Token::Ident(ident)));
+    } else {
+      let rest = &text[at..];
+      let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) else {
+        return Err(invalid(at, &format!("unexpected `{}`", c)));
+      };
+      tokens.push((at, Token::Symbol(symbol)));
+      i += symbol.len();
+    }
+  }
+  Ok(tokens)
+}
+
+struct Parser {
+  tokens: Vec<(usize, Token)>,
+  pos: usize,
+  /// The length of the query, reported as the position of a missing token
+  end: usize,
+}
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos).map(|(_, t)| t)
+  }
+  fn at(&self) -> usize {
+    self.tokens.get(self.pos).map_or(self.end, |(at, _)| *at)
+  }
+  fn next(&mut self) -> Option<Token> {
+    let token = self.peek().cloned();
+    self.pos += 1;
+    token
+  }
+  fn eat_symbol(&mut self, symbol: &str) -> bool {
+    match self.peek() {
+      Some(Token::Symbol(s)) if *s == symbol => {
+        self.pos += 1;
+        true
+      }
+      _ => false,
+    }
+  }
+  fn eat_keyword(&mut self, keyword: &str) -> bool {
+    match self.peek() {
+      Some(Token::Ident(i)) if i.eq_ignore_ascii_case(keyword) => {
+        self.pos += 1;
+        true
+      }
+      _ => false,
+    }
+  }
+  fn expect_symbol(&mut self, symbol: &str) -> Result<()> {
+    match self.eat_symbol(symbol) {
+      true => Ok(()),
+      false => Err(invalid(self.at(), &format!("expected `{}`", symbol))),
+    }
+  }
+
+  fn query(&mut self) -> Result<Query> {
+    let signatures = match self.eat_symbol("*") {
+      true => None,
+      false => {
+        let mut signatures = vec![self.signature()?];
+        while self.eat_symbol(",") {
+          signatures.push(self.signature()?);
+        }
+        Some(signatures)
+      }
+    };
+    let filter = match self.eat_keyword("where") || self.peek().is_some() {
+      true => Some(self.or()?),
+      false => None,
+    };
+    Ok(Query { signatures, filter })
+  }
+  fn signature(&mut self) -> Result<Signature> {
+    let at = self.at();
+    match self.next() {
+      Some(Token::Ident(ident)) if ident.len() == 4 => ident.parse(),
+      _ => Err(invalid(at, "expected a record signature")),
+    }
+  }
+  fn or(&mut self) -> Result<Expr> {
+    let mut expr = self.and()?;
+    while self.eat_keyword("or") {
+      expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+    }
+    Ok(expr)
+  }
+  fn and(&mut self) -> Result<Expr> {
+    let mut expr = self.unary()?;
+    while self.eat_keyword("and") {
+      expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+    }
+    Ok(expr)
+  }
+  fn unary(&mut self) -> Result<Expr> {
+    if self.eat_keyword("not") {
+      return Ok(Expr::Not(Box::new(self.unary()?)));
+    }
+    if self.eat_symbol("(") {
+      let expr = self.or()?;
+      self.expect_symbol(")")?;
+      return Ok(expr);
+    }
+    if self.eat_keyword("referencing") {
+      let at = self.at();
+      let n = match self.next() {
+        Some(Token::Number(n)) => n,
+        // FormIDs starting with a letter, such as FE000D62, are read as identifiers
+        Some(Token::Ident(n)) if is_form_id(&n) => n,
+        _ => return Err(invalid(at, "expected a FormID")),
+      };
+      let hex = n.trim_start_matches("0x").trim_start_matches("0X");
+      let id = u32::from_str_radix(hex, 16).map_err(|_| invalid(at, "expected a FormID"))?;
+      return Ok(Expr::Referencing(id.into()));
+    }
+    let path = self.path()?;
+    let Some(Token::Symbol(symbol)) = self.peek() else {
+      return Ok(Expr::Test(path));
+    };
+    let op = match *symbol {
+      "=" | "==" => Op::Equal,
+      "!=" => Op::NotEqual,
+      "<" => Op::Less,
+      "<=" => Op::LessOrEqual,
+      ">" => Op::Greater,
+      ">=" => Op::GreaterOrEqual,
+      "~" | "!~" => {
+        let negated = *symbol == "!~";
+        self.pos += 1;
+        return self.regex(path, negated);
+      }
+      _ => return Ok(Expr::Test(path)),
+    };
+    self.pos += 1;
+    Ok(Expr::Compare(path, op, self.literal()?))
+  }
+  fn regex(&mut self, path: Path, negated: bool) -> Result<Expr> {
+    let at = self.at();
+    match self.next() {
+      Some(Token::String(pattern)) => match Regex::new(&pattern) {
+        Ok(regex) => Ok(Expr::Match(path, regex, negated)),
+        Err(e) => Err(invalid(at, &e.to_string())),
+      },
+      _ => Err(invalid(at, "expected a quoted regular expression")),
+    }
+  }
+  fn literal(&mut self) -> Result<Literal> {
+    let at = self.at();
+    match self.next() {
+      Some(Token::Number(n)) => number(&n).ok_or(invalid(at, "invalid number")),
+      Some(Token::Ident(n)) if is_form_id(&n) => number(&n).ok_or(invalid(at, "invalid number")),
+      Some(Token::String(s)) => Ok(Literal::String(s)),
+      Some(Token::Bytes(b)) => Ok(Literal::Bytes(b)),
+      _ => Err(invalid(at, "expected a value")),
+    }
+  }
+  fn path(&mut self) -> Result<Path> {
+    let at = self.at();
+    let Some(Token::Ident(name)) = self.next() else {
+      return Err(invalid(at, "expected a field or property"));
+    };
+    match name.as_str() {
+      "signature" => return Ok(Path::Signature),
+      "form_id" => return Ok(Path::FormID),
+      "form_version" => return Ok(Path::FormVersion),
+      "flags" if self.eat_symbol(".") => {
+        let at = self.at();
+        return match self.next() {
+          Some(Token::Ident(flag)) => Ok(Path::Flag(flag)),
+          Some(Token::Number(n)) => match number(&n) {
+            Some(Literal::Int(mask)) => Ok(Path::FlagMask(mask as u32)),
+            _ => Err(invalid(at, "expected a flag mask")),
+          },
+          _ => Err(invalid(at, "expected a flag name")),
+        };
+      }
+      "flags" => return Ok(Path::Flags),
+      _ => {}
+    }
+    if name.len() != 4 {
+      return Err(invalid(at, &format!("unknown property `{}`", name)));
+    }
+    let field: Signature = name.parse()?;
+    if self.eat_symbol(".") {
+      let at = self.at();
+      return match self.next() {
+        Some(Token::Ident(member)) => Ok(Path::Member(field, member)),
+        _ => Err(invalid(at, "expected a member name")),
+      };
+    }
+    if self.eat_symbol("[") {
+      let start = self.index()?;
+      let end = match self.eat_symbol("..") {
+        true => self.index()?,
+        false => start + 1,
+      };
+      self.expect_symbol("]")?;
+      if end <= start {
+        return Err(invalid(at, "empty byte range"));
+      }
+      return Ok(Path::Bytes(field, start, end));
+    }
+    Ok(Path::Field(field))
+  }
+  fn index(&mut self) -> Result<usize> {
+    let at = self.at();
+    match self.next() {
+      Some(Token::Number(n)) => n.parse().map_err(|_| invalid(at, "expected an index")),
+      _ => Err(invalid(at, "expected an index")),
+    }
+  }
+}
+
+/// Whether text is exactly eight hex digits, which are a FormID
+fn is_form_id(text: &str) -> bool {
+  text.len() == 8 && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+/// Reads a decimal, `0x` hex or floating point number. Eight hex digits are a FormID.
+fn number(text: &str) -> Option<Literal> {
+  if let Some(hex) = text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+    return i64::from_str_radix(hex, 16).ok().map(Literal::Int);
+  }
+  if is_form_id(text) {
+    return i64::from_str_radix(text, 16).ok().map(Literal::Int);
+  }
+  if text.contains('.') {
+    return text.parse().ok().map(Literal::Float);
+  }
+  text.parse().ok().map(Literal::Int)
+}
@@ -0,0 +1,243 @@
+//! Quests read into a structured model: stages with their log entries, objectives, aliases and
+//! the scenes in the quest's scene group.
+//!
+//! Text fields are read as strings. Localized plugins hold string table IDs there instead, which
+//! come out garbled.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  group::GroupLabel,
+  types::{FormID, Signature},
+  ESx, Group, Record, Result,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quest {
+  pub form_id: FormID,
+  pub editor_id: Option<String>,
+  pub name: Option<String>,
+  pub stages: Vec<QuestStage>,
+  pub objectives: Vec<QuestObjective>,
+  pub aliases: Vec<QuestAlias>,
+  pub scenes: Vec<Scene>,
+}
+
+/// A stage, started by an INDX field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestStage {
+  pub index: u16,
+  pub flags: u8,
+  pub log_entries: Vec<LogEntry>,
+}
+
+/// A journal entry of a stage, started by a QSDT field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+  pub flags: u8,
+  pub text: Option<String>,
+  /// The quest started when this entry completes its quest
+  pub next_quest: Option<FormID>,
+  pub condition_count: usize,
+}
+
+/// An objective, started by a QOBJ field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestObjective {
+  pub index: u16,
+  pub flags: u32,
+  pub text: Option<String>,
+  /// The aliases the objective points the player at
+  pub target_aliases: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AliasKind {
+  Reference,
+  Location,
+}
+
+/// An alias, from its ALST or ALLS field to its ALED field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestAlias {
+  pub id: u32,
+  pub kind: AliasKind,
+  pub name: Option<String>,
+  pub flags: u32,
+}
+
+/// A SCEN record in the quest's scene group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+  pub form_id: FormID,
+  pub editor_id: Option<String>,
+}
+
+/// The part of a quest record the next field belongs to
+enum Section {
+  Header,
+  Stage,
+  Objective,
+  Alias,
+}
+
+/// Conversion
+impl Quest {
+  /// Reads a QUST record. Scenes are only known through the quest's group, see
+  /// [`ESx::get_quests`].
+  pub fn from_record(record: &Record) -> Result<Self> {
+    let mut quest = Quest {
+      form_id: *record.get_form_id(),
+      editor_id: None,
+      name: None,
+      stages: vec![],
+      objectives: vec![],
+      aliases: vec![],
+      scenes: vec![],
+    };
+    let mut section = Section::Header;
+    for field in record.read_fields()? {
+      let data = field.get_data().as_slice();
+      match (field.get_signature().as_array(), &section) {
+        (b"EDID", _) => quest.editor_id = Some(field.get_zstring()),
+        (b"FULL", Section::Header) => quest.name = Some(field.get_zstring()),
+        (b"INDX", _) => {
+          section = Section::Stage;
+          quest.stages.push(QuestStage {
+            index: read_u16(data),
+            flags: data.get(2).copied().unwrap_or_default(),
+            log_entries: vec![],
+          });
+        }
+        (b"QSDT", Section::Stage) => {
+          if let Some(stage) = quest.stages.last_mut() {
+            stage.log_entries.push(LogEntry {
+              flags: data.first().copied().unwrap_or_default(),
+              text: None,
+              next_quest: None,
+              condition_count: 0,
+            });
+          }
+        }
+        (b"CNAM", Section::Stage) => {
+          if let Some(entry) = last_log_entry(&mut quest) {
+            entry.text = Some(field.get_zstring());
+          }
+        }
+        (b"NAM0", Section::Stage) => {
+          if let Some(entry) = last_log_entry(&mut quest) {
+            entry.next_quest = field.get_form_id();
+          }
+        }
+        (b"CTDA", Section::Stage) => {
+          if let Some(entry) = last_log_entry(&mut quest) {
+            entry.condition_count += 1;
+          }
+        }
+        (b"QOBJ", _) => {
+          section = Section::Objective;
+          quest.objectives.push(QuestObjective {
+            index: read_u16(data),
+            flags: 0,
+            text: None,
+            target_aliases: vec![],
+          });
+        }
+        (b"FNAM", Section::Objective) => {
+          if let Some(objective) = quest.objectives.last_mut() {
+            objective.flags = field.get_u32().unwrap_or_default();
+          }
+        }
+        (b"NNAM", Section::Objective) => {
+          if let Some(objective) = quest.objectives.last_mut() {
+            objective.text = Some(field.get_zstring());
+          }
+        }
+        (b"QSTA", Section::Objective) => {
+          if let (Some(objective), Some(alias)) = (quest.objectives.last_mut(), data.get(0..4)) {
+            let alias = i32::from_le_bytes([alias[0], alias[1], alias[2], alias[3]]);
+            objective.target_aliases.push(alias);
+          }
+        }
+        (b"ALST" | b"ALLS", _) => {
+          section = Section::Alias;
+          let kind = match field.get_signature().as_array() {
+            b"ALST" => AliasKind::Reference,
+            _ => AliasKind::Location,
+          };
+          quest.aliases.push(QuestAlias {
+            id: field.get_u32().unwrap_or_default(),
+            kind,
+            name: None,
+            flags: 0,
+          });
+        }
+        (b"ALID", Section::Alias) => {
+          if let Some(alias) = quest.aliases.last_mut() {
+            alias.name = Some(field.get_zstring());
+          }
+        }
+        (b"FNAM", Section::Alias) => {
+          if let Some(alias) = quest.aliases.last_mut() {
+            alias.flags = field.get_u32().unwrap_or_default();
+          }
+        }
+        (b"ALED", _) => section = Section::Header,
+        _ => {}
+      }
+    }
+    Ok(quest)
+  }
+}
+
+/// Getters
+impl ESx {
+  /// Every quest in the QUST top group, with the scenes from its scene group
+  pub fn get_quests(&self) -> Result<Vec<Quest>> {
+    let Some(group) = self.get_top_group(Signature::new(b"QUST")) else {
+      return Ok(vec![]);
+    };
+    let mut quests = vec![];
+    for (record, children) in group.get_data().get_records_with_children() {
+      if *record.get_signature() != Signature::new(b"QUST") {
+        continue;
+      }
+      let mut quest = Quest::from_record(record)?;
+      if let Some(children) = children {
+        quest.scenes = scenes_in(children);
+      }
+      quests.push(quest);
+    }
+    Ok(quests)
+  }
+}
+
+fn scenes_in(group: &Group) -> Vec<Scene> {
+  let mut scenes: Vec<Scene> = group
+    .get_data()
+    .get_records()
+    .into_iter()
+    .filter(|r| *r.get_signature() == Signature::new(b"SCEN"))
+    .map(|r| Scene {
+      form_id: *r.get_form_id(),
+      editor_id: r
+        .read_field(Signature::new(b"EDID"))
+        .map(|f| f.get_zstring()),
+    })
+    .collect();
+  for subgroup in group.get_data().get_subgroups() {
+    if !matches!(subgroup.get_label(), GroupLabel::TopicChildren(_)) {
+      scenes.append(&mut scenes_in(subgroup));
+    }
+  }
+  scenes
+}
+fn last_log_entry(quest: &mut Quest) -> Option<&mut LogEntry> {
+  quest.stages.last_mut()?.log_entries.last_mut()
+}
+fn read_u16(data: &[u8]) -> u16 {
+  match data.get(0..2) {
+    Some(b) => u16::from_le_bytes([b[0], b[1]]),
+    None => 0,
+  }
+}
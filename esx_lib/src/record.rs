@@ -1,29 +1,42 @@
 use bytes::{Buf, BufMut, Bytes, BytesMut};
-use flate2::Decompress;
+use flate2::{write::ZlibEncoder, Compression, Decompress};
 use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::sync::OnceLock;
 
-use crate::{types::*, Error, Field, Result};
+use crate::{
+  diagnostics::{Diagnostic, PathSegment},
+  options::ProcessOptions,
+  types::*,
+  Error, Field, Result,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub enum RecordData {
   Empty,
-  Raw(BytesMut),
-  Compressed(BytesMut),
+  Raw(Bytes),
+  Compressed(Bytes),
   Generic(Vec<Field>),
+  /// Fields followed by bytes that can't be read as another field, kept so they are written back
+  /// unchanged
+  Partial(Vec<Field>, Bytes),
   //Structured(Vec<StructuredField>),
 }
 /// Conversion
 impl RecordData {
+  /// # Panics
+  /// If a field is too large for its size field, see [`RecordData::to_writer`]
   pub fn as_bytes(&self) -> Bytes {
     match self {
       RecordData::Empty => Bytes::new(),
-      RecordData::Raw(v) | RecordData::Compressed(v) => v.clone().freeze(),
-      RecordData::Generic(f) => {
-        let mut bytes: BytesMut = BytesMut::new();
-        for field in f {
-          bytes.put(field.as_bytes())
-        }
-        bytes.freeze()
+      RecordData::Raw(v) | RecordData::Compressed(v) => v.clone(),
+      RecordData::Generic(_) | RecordData::Partial(..) => {
+        let mut writer = BytesMut::with_capacity(self.serialized_size()).writer();
+        self
+          .to_writer(&mut writer)
+          .expect("field is too large to serialize");
+        writer.into_inner().freeze()
       } // RecordData::Structured(f) => {
         //   let mut vec: Vec<u8> = vec![];
         //   for field in f.to_generic_fields() {
@@ -34,25 +47,72 @@ impl RecordData {
     }
   }
 
-  fn generic_from_bytes(buf: &mut BytesMut) -> Result<Self> {
+  pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<()> {
+    match self {
+      RecordData::Empty => {}
+      RecordData::Raw(v) | RecordData::Compressed(v) => writer.write_all(v)?,
+      RecordData::Generic(f) => {
+        for field in f {
+          field.to_writer(writer)?
+        }
+      }
+      RecordData::Partial(f, remainder) => {
+        for field in f {
+          field.to_writer(writer)?
+        }
+        writer.write_all(remainder)?
+      }
+    }
+    Ok(())
+  }
+  pub fn serialized_size(&self) -> usize {
+    match self {
+      RecordData::Empty => 0,
+      RecordData::Raw(v) | RecordData::Compressed(v) => v.len(),
+      RecordData::Generic(f) => f.iter().map(Field::serialized_size).sum(),
+      RecordData::Partial(f, remainder) => {
+        f.iter().map(Field::serialized_size).sum::<usize>() + remainder.len()
+      }
+    }
+  }
+
+  /// Reads fields until the buffer runs out. Bytes too short to hold the next field are kept as
+  /// the remainder of a [`RecordData::Partial`].
+  fn generic_from_bytes(buf: &mut Bytes) -> Result<Self> {
     let mut fields: Vec<Field> = vec![];
     while !buf.is_empty() {
-      let field = Field::from_bytes(buf)?;
-      fields.push(field);
+      match Field::from_bytes(buf) {
+        Ok(field) => fields.push(field),
+        Err(Error::BufferTooShort) => return Ok(Self::Partial(fields, std::mem::take(buf))),
+        Err(e) => return Err(e),
+      }
     }
     Ok(Self::Generic(fields))
   }
-  fn generic_from_zlib_bytes(buf: &mut BytesMut) -> Result<Self> {
+  fn generic_from_zlib_bytes(buf: &mut Bytes) -> Result<Self> {
+    if buf.len() < 4 {
+      return Err(Error::BufferTooShort);
+    }
     let mut d = Decompress::new(true);
-    let mut output: BytesMut = BytesMut::new();
     let mut outvec: Vec<u8> = vec![];
     let len = buf.get_u32_le();
-    outvec.reserve(len as usize);
+    outvec.reserve_exact(len as usize);
     d.decompress_vec(buf, &mut outvec, flate2::FlushDecompress::Finish)?;
 
-    output.put(outvec.as_slice());
-    let fields = Self::generic_from_bytes(&mut output)?;
-    Ok(fields)
+    // Fields share the decompressed buffer
+    Self::generic_from_bytes(&mut Bytes::from(outvec))
+  }
+  /// Compresses the serialized fields the way [`RecordData::Compressed`] stores them: the
+  /// decompressed size followed by a zlib stream
+  fn to_zlib_bytes(&self) -> Result<Bytes> {
+    let mut writer = BytesMut::with_capacity(self.serialized_size()).writer();
+    self.to_writer(&mut writer)?;
+    let data = writer.into_inner().freeze();
+    let mut out = BytesMut::with_capacity(4 + data.len() / 2);
+    out.put_u32_le(data.len() as u32);
+    let mut encoder = ZlibEncoder::new(out.writer(), Compression::default());
+    encoder.write_all(&data)?;
+    Ok(encoder.finish()?.into_inner().freeze())
   }
 }
 /// Getters
@@ -60,19 +120,32 @@ impl RecordData {
   pub fn get_fields(&self) -> Vec<&Field> {
     match self {
       RecordData::Empty | RecordData::Raw(_) | RecordData::Compressed(_) => vec![],
-      RecordData::Generic(f) => f.iter().collect(),
+      RecordData::Generic(f) | RecordData::Partial(f, _) => f.iter().collect(),
     }
   }
+  /// The bytes after the last readable field, if any
+  pub fn get_remainder(&self) -> Option<&Bytes> {
+    match self {
+      RecordData::Partial(_, remainder) => Some(remainder),
+      _ => None,
+    }
+  }
+  /// Whether the data has been read into fields
+  pub fn is_decoded(&self) -> bool {
+    matches!(self, RecordData::Generic(_) | RecordData::Partial(..))
+  }
 }
 /// Process
 impl RecordData {
-  pub fn process(&self) -> Result<Self> {
+  /// Reads the fields in place, sharing the record's buffer. Data that can't be read is left
+  /// unchanged.
+  pub fn process(&mut self) -> Result<()> {
     match self {
-      RecordData::Raw(b) => Self::generic_from_bytes(&mut b.clone()),
-      RecordData::Compressed(b) => Self::generic_from_zlib_bytes(&mut b.clone()),
-      RecordData::Generic(_) => Ok(self.clone()),
-      RecordData::Empty => Ok(RecordData::Empty),
+      RecordData::Raw(b) => *self = Self::generic_from_bytes(&mut b.clone())?,
+      RecordData::Compressed(b) => *self = Self::generic_from_zlib_bytes(&mut b.clone())?,
+      RecordData::Generic(_) | RecordData::Partial(..) | RecordData::Empty => {}
     }
+    Ok(())
   }
 }
 
@@ -86,25 +159,62 @@ pub struct Record {
   form_version: u16,
   _unknown_1: u16,
   data: RecordData,
+  /// The data written for a compressed record whose fields have been read: the compressed bytes
+  /// it was read from, so compressed records round-trip exactly, or its fields compressed when
+  /// first measured or written after they change
+  #[serde(skip)]
+  compressed: CompressedCache,
+}
+
+/// A record's compressed data, compressed at most once per change to its fields. It is derived
+/// from the record's data, so it is ignored when comparing and hashing records.
+#[derive(Debug, Clone, Default)]
+struct CompressedCache(OnceLock<Bytes>);
+impl PartialEq for CompressedCache {
+  fn eq(&self, _: &Self) -> bool {
+    true
+  }
+}
+impl Eq for CompressedCache {}
+impl Hash for CompressedCache {
+  fn hash<H: Hasher>(&self, _: &mut H) {}
 }
 /// Constants
 impl Record {
   pub const HEADER_SIZE: usize = 24;
+  pub const COMPRESSED_FLAG: u32 = 0x00040000;
   pub const MAXIMUM_SIZE: usize = Self::HEADER_SIZE + u32::MAX as usize;
 }
 /// Conversion
 impl Record {
-  pub fn from_bytes(buf: &mut BytesMut) -> Result<Self> {
-    let header: BytesMut = buf.split_to(24);
-    let data_size: u32 = u32::from_le_bytes(header[4..8].try_into()?);
-    let flags: u32 = u32::from_le_bytes(header[8..12].try_into()?);
-
-    if buf.len() < data_size as usize {
+  /// A record holding `fields`, with no flags and a form version of 0
+  pub fn new(signature: Signature, form_id: FormID, fields: Vec<Field>) -> Self {
+    Record {
+      signature,
+      raw_flags: 0,
+      form_id,
+      timestamp: Timestamp::default(),
+      vcs_info: VcsInfo::default(),
+      form_version: 0,
+      _unknown_1: 0,
+      data: RecordData::Generic(fields),
+      compressed: CompressedCache::default(),
+    }
+  }
+  pub fn from_bytes(buf: &mut Bytes) -> Result<Self> {
+    if buf.len() < Self::HEADER_SIZE {
+      return Err(Error::BufferTooShort);
+    }
+    // Validate the size before consuming anything so a failed read leaves the buffer untouched
+    let data_size: u32 = u32::from_le_bytes(buf[4..8].try_into()?);
+    if buf.len() - Self::HEADER_SIZE < data_size as usize {
       return Err(Error::BufferTooShort);
     }
 
-    let data: BytesMut = buf.split_to(data_size as usize);
-    let data = match (flags & 0x00040000) != 0 {
+    let header: Bytes = buf.split_to(Self::HEADER_SIZE);
+    let flags: u32 = u32::from_le_bytes(header[8..12].try_into()?);
+    let data: Bytes = buf.split_to(data_size as usize);
+    let data = match (flags & Self::COMPRESSED_FLAG) != 0 {
       true => RecordData::Compressed(data),
       false => RecordData::Raw(data),
     };
@@ -118,25 +228,83 @@ impl Record {
       form_version: u16::from_le_bytes(header[20..22].try_into()?),
       _unknown_1: u16::from_le_bytes(header[22..24].try_into()?),
       data,
+      compressed: CompressedCache::default(),
     })
   }
 
+  /// # Panics
+  /// If the record or one of its fields is too large for its size field, or its fields can't be
+  /// compressed. [`Record::to_writer`] returns these as errors instead.
   pub fn as_bytes(&self) -> Bytes {
-    let mut bytes: BytesMut = BytesMut::new();
-    let data = self.data.as_bytes();
-    let data_len = data.len();
-
-    bytes.put(self.signature.as_bytes());
-    bytes.put_u32_le(data_len as u32);
-    bytes.put_u32_le(self.raw_flags);
-    bytes.put_u32_le(self.form_id.into());
-    bytes.put_u16_le(self.timestamp.into());
-    bytes.put_u16_le(self.vcs_info.into());
-    bytes.put_u16_le(self.form_version);
-    bytes.put_u16_le(self._unknown_1);
-    bytes.put(data);
+    let capacity = self.serialized_size().unwrap_or_default();
+    let mut writer = BytesMut::with_capacity(capacity).writer();
+    self
+      .to_writer(&mut writer)
+      .expect("record is too large to serialize");
+    writer.into_inner().freeze()
+  }
+  /// Writes the record, failing if it is larger than [`Record::MAXIMUM_SIZE`]. Decompressed
+  /// records are compressed again.
+  pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<()> {
+    let compressed = self.compressed_data()?;
+    let size = Self::HEADER_SIZE
+      + match &compressed {
+        Some(data) => data.len(),
+        None => self.data.serialized_size(),
+      };
+    if size > Self::MAXIMUM_SIZE {
+      return Err(Error::RecordTooLarge(self.signature, self.form_id, size));
+    }
 
-    bytes.freeze()
+    let mut header = [0; Self::HEADER_SIZE];
+    header[0..4].copy_from_slice(self.signature.as_slice());
+    header[4..8].copy_from_slice(&((size - Self::HEADER_SIZE) as u32).to_le_bytes());
+    header[8..12].copy_from_slice(&self.raw_flags.to_le_bytes());
+    header[12..16].copy_from_slice(&u32::from(self.form_id).to_le_bytes());
+    header[16..18].copy_from_slice(&u16::from(self.timestamp).to_le_bytes());
+    header[18..20].copy_from_slice(&u16::from(self.vcs_info).to_le_bytes());
+    header[20..22].copy_from_slice(&self.form_version.to_le_bytes());
+    header[22..24].copy_from_slice(&self._unknown_1.to_le_bytes());
+    writer.write_all(&header)?;
+    match compressed {
+      Some(data) => Ok(writer.write_all(data)?),
+      None => self.data.to_writer(writer),
+    }
+  }
+  /// The number of bytes [`Record::to_writer`] writes, compressing the fields of a compressed
+  /// record if they have changed
+  pub fn serialized_size(&self) -> Result<usize> {
+    let size = match self.compressed_data()? {
+      Some(data) => data.len(),
+      None => self.data.serialized_size(),
+    };
+    Ok(Self::HEADER_SIZE + size)
+  }
+  /// The size [`Record::to_writer`] writes, failing as it would if the record or one of its fields
+  /// is too large for its size field, without writing anything
+  pub fn checked_size(&self) -> Result<usize> {
+    for field in self.data.get_fields() {
+      if field.serialized_size() > Field::MAXIMUM_OVERSIZED_SIZE {
+        return Err(Error::FieldTooLarge(*field.get_signature(), field.len()));
+      }
+    }
+    let size = self.serialized_size()?;
+    if size > Self::MAXIMUM_SIZE {
+      return Err(Error::RecordTooLarge(self.signature, self.form_id, size));
+    }
+    Ok(size)
+  }
+  /// The data to write for a compressed record whose fields have been read: the original
+  /// compressed bytes if the fields are unchanged, otherwise the fields compressed again
+  fn compressed_data(&self) -> Result<Option<&Bytes>> {
+    if !self.is_compressed() || !self.data.is_decoded() {
+      return Ok(None);
+    }
+    if let Some(data) = self.compressed.0.get() {
+      return Ok(Some(data));
+    }
+    let data = self.data.to_zlib_bytes()?;
+    Ok(Some(self.compressed.0.get_or_init(|| data)))
   }
 }
 /// Getters
@@ -147,19 +315,116 @@ impl Record {
   pub fn get_form_id(&self) -> &FormID {
     &self.form_id
   }
+  pub fn get_timestamp(&self) -> &Timestamp {
+    &self.timestamp
+  }
+  pub fn get_vcs_info(&self) -> &VcsInfo {
+    &self.vcs_info
+  }
+  pub fn get_unknown(&self) -> &u16 {
+    &self._unknown_1
+  }
   pub fn get_form_version(&self) -> &u16 {
     &self.form_version
   }
+  /// The record's fields, read from a copy of its data if it hasn't been processed
+  pub fn read_fields(&self) -> Result<Vec<Field>> {
+    let mut data = self.data.clone();
+    data.process()?;
+    Ok(data.get_fields().into_iter().cloned().collect())
+  }
+  /// The first field with this signature, see [`Record::read_fields`]
+  pub fn read_field(&self, signature: Signature) -> Option<Field> {
+    let fields = self.read_fields().ok()?;
+    fields.into_iter().find(|f| *f.get_signature() == signature)
+  }
+  pub fn get_flags(&self) -> &u32 {
+    &self.raw_flags
+  }
   pub fn get_data(&self) -> &RecordData {
     &self.data
   }
+  pub fn is_compressed(&self) -> bool {
+    self.raw_flags & Self::COMPRESSED_FLAG != 0
+  }
+}
+/// Setters
+impl Record {
+  pub fn set_timestamp(&mut self, timestamp: Timestamp) {
+    self.timestamp = timestamp;
+  }
+  pub fn set_vcs_info(&mut self, vcs_info: VcsInfo) {
+    self.vcs_info = vcs_info;
+  }
+  pub fn set_unknown(&mut self, unknown: u16) {
+    self._unknown_1 = unknown;
+  }
+  pub fn set_form_version(&mut self, form_version: u16) {
+    self.form_version = form_version;
+  }
+  /// Set the record flags. Setting or clearing [`Record::COMPRESSED_FLAG`] reads the record's
+  /// fields, so they are compressed or written uncompressed to match; this fails if the fields
+  /// can't be read.
+  pub fn set_flags(&mut self, flags: u32) -> Result<()> {
+    if (self.raw_flags ^ flags) & Self::COMPRESSED_FLAG != 0 {
+      self.data.process()?;
+      if self.data == RecordData::Empty {
+        self.data = RecordData::Generic(vec![]);
+      }
+      self.compressed = CompressedCache::default();
+    }
+    self.raw_flags = flags;
+    Ok(())
+  }
+  /// Replaces the record's data. A compressed record is compressed again when written.
+  pub fn set_data(&mut self, data: RecordData) {
+    self.data = data;
+    self.compressed = CompressedCache::default();
+  }
 }
 /// Process
 impl Record {
-  pub fn process(&mut self) {
+  pub fn process(&mut self) -> Vec<Diagnostic> {
+    let source = match &self.data {
+      RecordData::Compressed(b) => Some(b.clone()),
+      _ => None,
+    };
     match self.data.process() {
-      Ok(data) => self.data = data,
-      Err(e) => eprintln!("Error processing record data: {:?}", e),
+      Ok(()) => {
+        if let (true, Some(source)) = (self.data.is_decoded(), source) {
+          self.compressed = CompressedCache(OnceLock::from(source));
+        }
+        match self.data.get_remainder() {
+          Some(remainder) => {
+            let message = format!(
+              "{} trailing bytes could not be read as a field and are kept as-is",
+              remainder.len()
+            );
+            vec![Diagnostic::warning(message).within(self.path_segment())]
+          }
+          None => vec![],
+        }
+      }
+      Err(e) => {
+        let message = format!("Error processing record data: {:?}", e);
+        vec![Diagnostic::error(message).within(self.path_segment())]
+      }
+    }
+  }
+  /// Process the record if `options` selects its signature. Compressed records stay compressed
+  /// when `options` skips decompression.
+  pub fn process_with_options(&mut self, options: &ProcessOptions) -> Vec<Diagnostic> {
+    let skip_compressed =
+      options.skip_decompression && matches!(self.data, RecordData::Compressed(_));
+    match options.selects_record(&self.signature) && !skip_compressed {
+      true => self.process(),
+      false => vec![],
+    }
+  }
+  pub fn path_segment(&self) -> PathSegment {
+    PathSegment::Record {
+      signature: self.signature,
+      form_id: self.form_id,
     }
   }
 }
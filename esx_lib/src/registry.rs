@@ -0,0 +1,196 @@
+//! Known record and field signatures, with human-readable names per game.
+
+use crate::types::{Game, Signature};
+
+mod fields;
+mod functions;
+mod records;
+mod schema;
+
+pub struct RecordInfo {
+  pub signature: Signature,
+  pub name: &'static str,
+  pub games: &'static [Game],
+}
+
+pub struct FieldInfo {
+  /// The record this name applies to, or `None` for names shared by every record
+  pub record: Option<Signature>,
+  pub signature: Signature,
+  pub name: &'static str,
+}
+
+/// The kind of value a condition function parameter holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+  None,
+  Int,
+  /// A FormID, such as a reference, faction or quest
+  Form,
+  /// 'X', 'Y' or 'Z'
+  Axis,
+  /// An actor value index
+  ActorValue,
+  /// 0 for male, 1 for female
+  Sex,
+  /// A quest alias ID
+  Alias,
+  /// A string held in the CIS1 or CIS2 field after the condition
+  String,
+}
+
+pub struct FunctionInfo {
+  pub index: u16,
+  pub name: &'static str,
+  pub params: [ParamType; 2],
+  pub games: &'static [Game],
+}
+
+/// How a schema field member is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaType {
+  U8,
+  U16,
+  U32,
+  I16,
+  I32,
+  F32,
+  FormID,
+}
+
+/// A value at a fixed offset in a field, such as a weapon's damage in its DATA field
+pub struct SchemaField {
+  pub record: Signature,
+  pub field: Signature,
+  pub name: &'static str,
+  pub offset: usize,
+  pub kind: SchemaType,
+  /// Names of the bits of a flags member
+  pub flags: &'static [(&'static str, u32)],
+  pub games: &'static [Game],
+}
+
+/// Looks up a record signature known to the given game
+pub fn record_info(game: Game, signature: Signature) -> Option<&'static RecordInfo> {
+  records::RECORDS
+    .iter()
+    .find(|info| info.signature == signature && info.games.contains(&game))
+}
+/// Looks up a record signature known to any game
+pub fn record_info_any(signature: Signature) -> Option<&'static RecordInfo> {
+  records::RECORDS
+    .iter()
+    .find(|info| info.signature == signature)
+}
+pub fn record_name(game: Game, signature: Signature) -> Option<&'static str> {
+  record_info(game, signature).map(|info| info.name)
+}
+pub fn is_known_record(game: Game, signature: Signature) -> bool {
+  record_info(game, signature).is_some()
+}
+/// Every record signature known to the given game
+pub fn records_for(game: Game) -> impl Iterator<Item = &'static RecordInfo> {
+  records::RECORDS
+    .iter()
+    .filter(move |info| info.games.contains(&game))
+}
+
+/// Record signatures found in the nested groups below a top group of `top`, such as the placed
+/// references inside cells
+pub fn nested_records(top: Signature) -> &'static [Signature] {
+  records::NESTED
+    .iter()
+    .find(|(parent, _)| *parent == top)
+    .map_or(&[], |(_, nested)| nested)
+}
+/// Top group signatures in the order the game's Creation Kit writes them, or an empty slice for
+/// games without a known order
+pub fn top_group_order(game: Game) -> &'static [Signature] {
+  records::TOP_GROUP_ORDER
+    .iter()
+    .find(|(games, _)| games.contains(&game))
+    .map_or(&[], |(_, order)| order)
+}
+
+/// Looks up a condition function by index. Only common functions are listed.
+pub fn condition_function(game: Game, index: u16) -> Option<&'static FunctionInfo> {
+  functions::FUNCTIONS
+    .iter()
+    .find(|info| info.index == index && info.games.contains(&game))
+}
+/// Looks up a condition function by index in any game
+pub fn condition_function_any(index: u16) -> Option<&'static FunctionInfo> {
+  functions::FUNCTIONS.iter().find(|info| info.index == index)
+}
+
+/// Looks up a member of a record's field by name, in the given game or in any game. Only a few
+/// common Skyrim record layouts are known.
+pub fn schema_field(
+  game: Option<Game>,
+  record: Signature,
+  field: Signature,
+  name: &str,
+) -> Option<&'static SchemaField> {
+  schema::SCHEMA.iter().find(|info| {
+    info.record == record
+      && info.field == field
+      && info.name.eq_ignore_ascii_case(name)
+      && game.is_none_or(|game| info.games.contains(&game))
+  })
+}
+/// Looks up a named flag of a record type: a bit of one of its schema flags members, or failing
+/// that a flag of the record header. Header flags are returned without a schema field.
+pub fn record_flag(
+  game: Option<Game>,
+  record: Signature,
+  name: &str,
+) -> Option<(Option<&'static SchemaField>, u32)> {
+  for info in schema::SCHEMA.iter().filter(|info| info.record == record) {
+    if game.is_some_and(|game| !info.games.contains(&game)) {
+      continue;
+    }
+    if let Some((_, bit)) = info
+      .flags
+      .iter()
+      .find(|(n, _)| n.eq_ignore_ascii_case(name))
+    {
+      return Some((Some(info), *bit));
+    }
+  }
+  schema::RECORD_FLAGS
+    .iter()
+    .find(|(n, _)| n.eq_ignore_ascii_case(name))
+    .map(|(_, bit)| (None, *bit))
+}
+
+/// Looks up a field name, preferring the record's own meaning over the shared one
+pub fn field_name(record: Signature, field: Signature) -> Option<&'static str> {
+  let mut shared = None;
+  for info in fields::FIELDS.iter().filter(|info| info.signature == field) {
+    match info.record {
+      Some(rec) if rec == record => return Some(info.name),
+      None => shared = Some(info.name),
+      _ => {}
+    }
+  }
+  shared
+}
+
+/// Formats a record signature as "WEAP — Weapon", or "ABCD — Unknown" when not in the registry
+pub fn describe_record(game: Option<Game>, signature: Signature) -> String {
+  let info = match game {
+    Some(game) => record_info(game, signature),
+    None => record_info_any(signature),
+  };
+  match info {
+    Some(info) => format!("{} — {}", signature, info.name),
+    None => format!("{} — Unknown", signature),
+  }
+}
+/// Formats a field signature as "EDID — Editor ID", or "ABCD — Unknown" when not in the registry
+pub fn describe_field(record: Signature, field: Signature) -> String {
+  match field_name(record, field) {
+    Some(name) => format!("{} — {}", field, name),
+    None => format!("{} — Unknown", field),
+  }
+}
@@ -0,0 +1,116 @@
+use super::FieldInfo;
+use crate::types::Signature;
+
+const fn shared(sig: &[u8; 4], name: &'static str) -> FieldInfo {
+  FieldInfo {
+    record: None,
+    signature: Signature::new(sig),
+    name,
+  }
+}
+const fn field(record: &[u8; 4], sig: &[u8; 4], name: &'static str) -> FieldInfo {
+  FieldInfo {
+    record: Some(Signature::new(record)),
+    signature: Signature::new(sig),
+    name,
+  }
+}
+
+pub(super) static FIELDS: &[FieldInfo] = &[
+  shared(b"XXXX", "Oversize Field Marker"),
+  shared(b"EDID", "Editor ID"),
+  shared(b"FULL", "Name"),
+  shared(b"DESC", "Description"),
+  shared(b"OBND", "Object Bounds"),
+  shared(b"PTRN", "Preview Transform"),
+  shared(b"MODL", "Model Filename"),
+  shared(b"MODT", "Model Texture Data"),
+  shared(b"MODS", "Model Alternate Textures"),
+  shared(b"ICON", "Large Icon Filename"),
+  shared(b"MICO", "Small Icon Filename"),
+  shared(b"SCRI", "Script"),
+  shared(b"VMAD", "Virtual Machine Adapter"),
+  shared(b"KSIZ", "Keyword Count"),
+  shared(b"KWDA", "Keywords"),
+  shared(b"CTDA", "Condition"),
+  shared(b"CIS1", "Condition Parameter 1"),
+  shared(b"CIS2", "Condition Parameter 2"),
+  shared(b"DEST", "Destructible Header"),
+  shared(b"DSTD", "Destruction Stage Data"),
+  shared(b"DSTF", "Destruction Stage End"),
+  shared(b"YNAM", "Sound - Pick Up"),
+  shared(b"ZNAM", "Sound - Drop"),
+  shared(b"DATA", "Data"),
+  shared(b"COED", "Extra Data"),
+  shared(b"LVLD", "Chance None"),
+  shared(b"LVLF", "Leveled Flags"),
+  shared(b"LVLG", "Use Global"),
+  shared(b"LVLM", "Max Count"),
+  shared(b"LLCT", "Leveled Entry Count"),
+  shared(b"LVLO", "Leveled List Entry"),
+  shared(b"NAME", "Base Object"),
+  shared(b"XSCL", "Scale"),
+  shared(b"XESP", "Enable Parent"),
+  shared(b"XOWN", "Owner"),
+  shared(b"XLKR", "Linked Reference"),
+  shared(b"XLCN", "Persistent Location"),
+  shared(b"XEZN", "Encounter Zone"),
+  field(b"TES4", b"HEDR", "Header"),
+  field(b"TES4", b"CNAM", "Author"),
+  field(b"TES4", b"SNAM", "Description"),
+  field(b"TES4", b"MAST", "Master File"),
+  field(b"TES4", b"ONAM", "Overridden Forms"),
+  field(b"TES4", b"INTV", "Internal Version"),
+  field(b"TES4", b"INCC", "Interior Cell Count"),
+  field(b"CELL", b"XCLC", "Grid"),
+  field(b"CELL", b"XCLL", "Lighting"),
+  field(b"CELL", b"XCLW", "Water Height"),
+  field(b"CELL", b"XCLR", "Regions"),
+  field(b"CELL", b"LTMP", "Lighting Template"),
+  field(b"WRLD", b"WNAM", "Parent Worldspace"),
+  field(b"WRLD", b"NAM0", "Object Bounds Min"),
+  field(b"WRLD", b"NAM9", "Object Bounds Max"),
+  field(b"REFR", b"DATA", "Position/Rotation"),
+  field(b"ACHR", b"DATA", "Position/Rotation"),
+  field(b"DIAL", b"QNAM", "Quest"),
+  field(b"DIAL", b"BNAM", "Branch"),
+  field(b"DIAL", b"SNAM", "Subtype"),
+  field(b"DIAL", b"TIFC", "Info Count"),
+  field(b"INFO", b"TRDT", "Response Data"),
+  field(b"INFO", b"NAM1", "Response Text"),
+  field(b"INFO", b"NAM2", "Script Notes"),
+  field(b"INFO", b"NAM3", "Edits"),
+  field(b"INFO", b"PNAM", "Previous Info"),
+  field(b"INFO", b"TCLT", "Choice"),
+  field(b"INFO", b"RNAM", "Prompt"),
+  field(b"QUST", b"DNAM", "General"),
+  field(b"QUST", b"INDX", "Stage Index"),
+  field(b"QUST", b"QSDT", "Log Entry Flags"),
+  field(b"QUST", b"CNAM", "Log Entry"),
+  field(b"QUST", b"QOBJ", "Objective Index"),
+  field(b"QUST", b"NNAM", "Objective Display Text"),
+  field(b"QUST", b"QSTA", "Objective Target"),
+  field(b"QUST", b"ALST", "Reference Alias"),
+  field(b"QUST", b"ALLS", "Location Alias"),
+  field(b"QUST", b"ALID", "Alias Name"),
+  field(b"QUST", b"ALED", "Alias End"),
+  field(b"WEAP", b"DNAM", "Weapon Data"),
+  field(b"WEAP", b"CRDT", "Critical Data"),
+  field(b"WEAP", b"ETYP", "Equipment Type"),
+  field(b"WEAP", b"INAM", "Impact Data Set"),
+  field(b"NPC_", b"ACBS", "Configuration"),
+  field(b"NPC_", b"SNAM", "Faction"),
+  field(b"NPC_", b"RNAM", "Race"),
+  field(b"NPC_", b"CNTO", "Item"),
+  field(b"NPC_", b"PKID", "Package"),
+  field(b"LAND", b"VHGT", "Vertex Height Map"),
+  field(b"LAND", b"VNML", "Vertex Normals"),
+  field(b"LAND", b"VCLR", "Vertex Colors"),
+  field(b"LAND", b"BTXT", "Base Layer Header"),
+  field(b"LAND", b"ATXT", "Alpha Layer Header"),
+  field(b"LAND", b"VTXT", "Alpha Layer Data"),
+  field(b"NAVM", b"NVNM", "Navmesh Geometry"),
+  field(b"TXST", b"TX00", "Diffuse"),
+  field(b"TXST", b"TX01", "Normal/Gloss"),
+  field(b"TXST", b"DODT", "Decal Data"),
+];
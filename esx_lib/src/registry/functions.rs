@@ -0,0 +1,161 @@
+use super::{FunctionInfo, ParamType};
+use crate::types::Game;
+
+use Game::*;
+use ParamType::*;
+
+/// Condition functions are numbered the same way from Oblivion on, though later games add more
+const FROM_OBLIVION: &[Game] = &Game::ALL;
+const SKYRIM_ON: &[Game] = &[Skyrim, SkyrimSE, Fallout4, Fallout76, Starfield];
+
+const fn function(
+  index: u16,
+  name: &'static str,
+  params: [ParamType; 2],
+  games: &'static [Game],
+) -> FunctionInfo {
+  FunctionInfo {
+    index,
+    name,
+    params,
+    games,
+  }
+}
+
+pub(super) static FUNCTIONS: &[FunctionInfo] = &[
+  function(1, "GetDistance", [Form, None], FROM_OBLIVION),
+  function(5, "GetLocked", [None, None], FROM_OBLIVION),
+  function(6, "GetPos", [Axis, None], FROM_OBLIVION),
+  function(8, "GetAngle", [Axis, None], FROM_OBLIVION),
+  function(10, "GetStartingPos", [Axis, None], FROM_OBLIVION),
+  function(11, "GetStartingAngle", [Axis, None], FROM_OBLIVION),
+  function(12, "GetSecondsPassed", [None, None], FROM_OBLIVION),
+  function(14, "GetActorValue", [ActorValue, None], FROM_OBLIVION),
+  function(18, "GetCurrentTime", [None, None], FROM_OBLIVION),
+  function(24, "GetScale", [None, None], FROM_OBLIVION),
+  function(25, "IsMoving", [None, None], FROM_OBLIVION),
+  function(26, "IsTurning", [None, None], FROM_OBLIVION),
+  function(27, "GetLineOfSight", [Form, None], FROM_OBLIVION),
+  function(32, "GetInSameCell", [Form, None], FROM_OBLIVION),
+  function(35, "GetDisabled", [None, None], FROM_OBLIVION),
+  function(36, "MenuMode", [Int, None], FROM_OBLIVION),
+  function(39, "GetDisease", [None, None], FROM_OBLIVION),
+  function(41, "GetClothingValue", [None, None], FROM_OBLIVION),
+  function(42, "SameFaction", [Form, None], FROM_OBLIVION),
+  function(43, "SameRace", [Form, None], FROM_OBLIVION),
+  function(44, "SameSex", [Form, None], FROM_OBLIVION),
+  function(45, "GetDetected", [Form, None], FROM_OBLIVION),
+  function(46, "GetDead", [None, None], FROM_OBLIVION),
+  function(47, "GetItemCount", [Form, None], FROM_OBLIVION),
+  function(48, "GetGold", [None, None], FROM_OBLIVION),
+  function(49, "GetSleeping", [None, None], FROM_OBLIVION),
+  function(50, "GetTalkedToPC", [None, None], FROM_OBLIVION),
+  function(53, "GetScriptVariable", [Form, String], FROM_OBLIVION),
+  function(56, "GetQuestRunning", [Form, None], FROM_OBLIVION),
+  function(58, "GetStage", [Form, None], FROM_OBLIVION),
+  function(59, "GetStageDone", [Form, Int], FROM_OBLIVION),
+  function(60, "GetFactionRankDifference", [Form, Form], FROM_OBLIVION),
+  function(61, "GetAlarmed", [None, None], FROM_OBLIVION),
+  function(62, "IsRaining", [None, None], FROM_OBLIVION),
+  function(63, "GetAttacked", [None, None], FROM_OBLIVION),
+  function(64, "GetIsCreature", [None, None], FROM_OBLIVION),
+  function(65, "GetLockLevel", [None, None], FROM_OBLIVION),
+  function(66, "GetShouldAttack", [Form, None], FROM_OBLIVION),
+  function(67, "GetInCell", [Form, None], FROM_OBLIVION),
+  function(68, "GetIsClass", [Form, None], FROM_OBLIVION),
+  function(69, "GetIsRace", [Form, None], FROM_OBLIVION),
+  function(70, "GetIsSex", [Sex, None], FROM_OBLIVION),
+  function(71, "GetInFaction", [Form, None], FROM_OBLIVION),
+  function(72, "GetIsID", [Form, None], FROM_OBLIVION),
+  function(73, "GetFactionRank", [Form, None], FROM_OBLIVION),
+  function(74, "GetGlobalValue", [Form, None], FROM_OBLIVION),
+  function(75, "IsSnowing", [None, None], FROM_OBLIVION),
+  function(77, "GetRandomPercent", [None, None], FROM_OBLIVION),
+  function(79, "GetQuestVariable", [Form, String], FROM_OBLIVION),
+  function(80, "GetLevel", [None, None], FROM_OBLIVION),
+  function(81, "IsRotating", [None, None], FROM_OBLIVION),
+  function(84, "GetDeadCount", [Form, None], FROM_OBLIVION),
+  function(91, "GetIsAlerted", [None, None], FROM_OBLIVION),
+  function(98, "GetPlayerControlsDisabled", [None, None], FROM_OBLIVION),
+  function(99, "GetHeadingAngle", [Form, None], FROM_OBLIVION),
+  function(101, "IsWeaponMagicOut", [None, None], FROM_OBLIVION),
+  function(102, "IsTorchOut", [None, None], FROM_OBLIVION),
+  function(103, "IsShieldOut", [None, None], FROM_OBLIVION),
+  function(106, "IsFacingUp", [None, None], FROM_OBLIVION),
+  function(107, "GetKnockedState", [None, None], FROM_OBLIVION),
+  function(108, "GetWeaponAnimType", [None, None], FROM_OBLIVION),
+  function(109, "IsWeaponSkillType", [ActorValue, None], FROM_OBLIVION),
+  function(110, "GetCurrentAIPackage", [None, None], FROM_OBLIVION),
+  function(111, "IsWaiting", [None, None], FROM_OBLIVION),
+  function(112, "IsIdlePlaying", [None, None], FROM_OBLIVION),
+  function(117, "IsPlayerInRegion", [Form, None], FROM_OBLIVION),
+  function(122, "GetCrime", [Form, Int], FROM_OBLIVION),
+  function(123, "IsGreetingPlayer", [None, None], FROM_OBLIVION),
+  function(125, "IsGuard", [None, None], FROM_OBLIVION),
+  function(128, "GetStaminaPercentage", [None, None], FROM_OBLIVION),
+  function(130, "GetDying", [None, None], FROM_OBLIVION),
+  function(133, "SameFactionAsPC", [None, None], FROM_OBLIVION),
+  function(134, "SameRaceAsPC", [None, None], FROM_OBLIVION),
+  function(135, "SameSexAsPC", [None, None], FROM_OBLIVION),
+  function(136, "GetIsReference", [Form, None], FROM_OBLIVION),
+  function(141, "IsTalking", [None, None], FROM_OBLIVION),
+  function(142, "GetWalkSpeed", [None, None], FROM_OBLIVION),
+  function(143, "GetCurrentAIProcedure", [None, None], FROM_OBLIVION),
+  function(144, "GetTrespassWarningLevel", [None, None], FROM_OBLIVION),
+  function(145, "IsTrespassing", [None, None], FROM_OBLIVION),
+  function(146, "IsInMyOwnedCell", [None, None], FROM_OBLIVION),
+  function(147, "GetWindSpeed", [None, None], FROM_OBLIVION),
+  function(148, "GetCurrentWeatherPercent", [None, None], FROM_OBLIVION),
+  function(149, "GetIsCurrentWeather", [Form, None], FROM_OBLIVION),
+  function(
+    150,
+    "IsContinuingPackagePCNear",
+    [None, None],
+    FROM_OBLIVION,
+  ),
+  function(152, "GetIsCrimeFaction", [Form, None], FROM_OBLIVION),
+  function(153, "CanHaveFlames", [None, None], FROM_OBLIVION),
+  function(154, "HasFlames", [None, None], FROM_OBLIVION),
+  function(157, "GetOpenState", [None, None], FROM_OBLIVION),
+  function(159, "GetSitting", [None, None], FROM_OBLIVION),
+  function(161, "GetIsCurrentPackage", [Form, None], FROM_OBLIVION),
+  function(162, "IsCurrentFurnitureRef", [Form, None], FROM_OBLIVION),
+  function(163, "IsCurrentFurnitureObj", [Form, None], FROM_OBLIVION),
+  function(170, "GetDayOfWeek", [None, None], FROM_OBLIVION),
+  function(172, "GetTalkedToPCParam", [Form, None], FROM_OBLIVION),
+  function(175, "IsPCSleeping", [None, None], FROM_OBLIVION),
+  function(176, "IsPCAMurderer", [None, None], FROM_OBLIVION),
+  function(182, "GetEquipped", [Form, None], FROM_OBLIVION),
+  function(185, "IsSwimming", [None, None], FROM_OBLIVION),
+  function(190, "GetAmountSoldStolen", [None, None], FROM_OBLIVION),
+  function(192, "GetIgnoreCrime", [None, None], FROM_OBLIVION),
+  function(193, "GetPCExpelled", [Form, None], FROM_OBLIVION),
+  function(195, "GetPCFactionMurder", [Form, None], FROM_OBLIVION),
+  function(197, "GetPCEnemyofFaction", [Form, None], FROM_OBLIVION),
+  function(199, "GetPCFactionAttack", [Form, None], FROM_OBLIVION),
+  function(203, "GetDestroyed", [None, None], FROM_OBLIVION),
+  function(214, "HasMagicEffect", [Form, None], FROM_OBLIVION),
+  function(215, "GetDefaultOpen", [None, None], FROM_OBLIVION),
+  function(219, "GetAnimAction", [None, None], FROM_OBLIVION),
+  function(223, "IsSpellTarget", [Form, None], FROM_OBLIVION),
+  function(224, "GetVATSMode", [None, None], FROM_OBLIVION),
+  function(228, "GetIsClassDefault", [Form, None], FROM_OBLIVION),
+  function(230, "GetInCellParam", [Form, Form], FROM_OBLIVION),
+  function(237, "GetIsGhost", [None, None], FROM_OBLIVION),
+  function(242, "GetUnconscious", [None, None], FROM_OBLIVION),
+  function(244, "GetRestrained", [None, None], FROM_OBLIVION),
+  function(246, "GetIsUsedItem", [Form, None], FROM_OBLIVION),
+  function(248, "IsScenePlaying", [Form, None], SKYRIM_ON),
+  function(249, "IsInDialogueWithPlayer", [None, None], SKYRIM_ON),
+  function(250, "GetLocationCleared", [Form, None], SKYRIM_ON),
+  function(254, "GetIsPlayableRace", [None, None], SKYRIM_ON),
+  function(255, "GetOffersServicesNow", [None, None], SKYRIM_ON),
+  function(300, "IsInInterior", [None, None], SKYRIM_ON),
+  function(310, "GetInWorldspace", [Form, None], SKYRIM_ON),
+  function(448, "HasPerk", [Form, Int], SKYRIM_ON),
+  function(560, "HasKeyword", [Form, None], SKYRIM_ON),
+  function(566, "GetIsAliasRef", [Alias, None], SKYRIM_ON),
+  function(629, "GetVMQuestVariable", [Form, String], SKYRIM_ON),
+  function(630, "GetVMScriptVariable", [Form, String], SKYRIM_ON),
+  function(682, "WornHasKeyword", [Form, None], SKYRIM_ON),
+];
@@ -0,0 +1,549 @@
+use super::RecordInfo;
+use crate::types::{Game, Signature};
+
+use Game::*;
+
+const ALL: &[Game] = &Game::ALL;
+const OBLIVION: &[Game] = &[Oblivion];
+const GAMEBRYO: &[Game] = &[Oblivion, Fallout3, FalloutNV];
+const OBLIVION_SKYRIM: &[Game] = &[Oblivion, Skyrim, SkyrimSE];
+const FALLOUT_GAMEBRYO: &[Game] = &[Fallout3, FalloutNV];
+const NEW_VEGAS: &[Game] = &[FalloutNV];
+const FALLOUT: &[Game] = &[Fallout3, FalloutNV, Fallout4, Fallout76];
+const FO3_TO_SKYRIM: &[Game] = &[Fallout3, FalloutNV, Skyrim, SkyrimSE];
+const FO3_ON: &[Game] = &[
+  Fallout3, FalloutNV, Skyrim, SkyrimSE, Fallout4, Fallout76, Starfield,
+];
+const NOT_STARFIELD: &[Game] = &[
+  Oblivion, Fallout3, FalloutNV, Skyrim, SkyrimSE, Fallout4, Fallout76,
+];
+const SKYRIM: &[Game] = &[Skyrim, SkyrimSE];
+const SKYRIM_ON: &[Game] = &[Skyrim, SkyrimSE, Fallout4, Fallout76, Starfield];
+const SKYRIM_TO_FO4: &[Game] = &[Skyrim, SkyrimSE, Fallout4, Fallout76];
+const SSE_ON: &[Game] = &[SkyrimSE, Fallout4, Fallout76, Starfield];
+const FO4_ON: &[Game] = &[Fallout4, Fallout76, Starfield];
+const STARFIELD: &[Game] = &[Starfield];
+
+const fn rec(sig: &[u8; 4], name: &'static str, games: &'static [Game]) -> RecordInfo {
+  RecordInfo {
+    signature: Signature::new(sig),
+    name,
+    games,
+  }
+}
+
+pub(super) static RECORDS: &[RecordInfo] = &[
+  rec(b"TES4", "File Header", ALL),
+  rec(b"GMST", "Game Setting", ALL),
+  rec(b"GLOB", "Global Variable", ALL),
+  rec(b"CLAS", "Class", ALL),
+  rec(b"FACT", "Faction", ALL),
+  rec(b"HAIR", "Hair", &[Oblivion, Fallout3, FalloutNV]),
+  rec(b"EYES", "Eyes", NOT_STARFIELD),
+  rec(b"RACE", "Race", ALL),
+  rec(b"SOUN", "Sound Marker", NOT_STARFIELD),
+  rec(b"MGEF", "Magic Effect", ALL),
+  rec(b"SCPT", "Script", GAMEBRYO),
+  rec(b"LTEX", "Land Texture", ALL),
+  rec(b"ENCH", "Enchantment", OBLIVION_SKYRIM),
+  rec(
+    b"ENCH",
+    "Object Effect",
+    &[Fallout3, FalloutNV, Fallout4, Fallout76, Starfield],
+  ),
+  rec(b"SPEL", "Spell", ALL),
+  rec(b"BSGN", "Birthsign", OBLIVION),
+  rec(b"SKIL", "Skill", OBLIVION),
+  rec(b"ACTI", "Activator", ALL),
+  rec(b"APPA", "Alchemical Apparatus", OBLIVION_SKYRIM),
+  rec(b"ARMO", "Armor", ALL),
+  rec(b"BOOK", "Book", ALL),
+  rec(b"CLOT", "Clothing", OBLIVION),
+  rec(b"CONT", "Container", ALL),
+  rec(b"DOOR", "Door", ALL),
+  rec(
+    b"INGR",
+    "Ingredient",
+    &[Oblivion, FalloutNV, Skyrim, SkyrimSE, Fallout4, Fallout76],
+  ),
+  rec(b"LIGH", "Light", ALL),
+  rec(b"MISC", "Misc. Item", ALL),
+  rec(b"STAT", "Static", ALL),
+  rec(b"GRAS", "Grass", ALL),
+  rec(b"TREE", "Tree", NOT_STARFIELD),
+  rec(b"FLOR", "Flora", ALL),
+  rec(b"FURN", "Furniture", ALL),
+  rec(b"WEAP", "Weapon", ALL),
+  rec(b"AMMO", "Ammunition", ALL),
+  rec(b"NPC_", "Non-Player Character", ALL),
+  rec(b"CREA", "Creature", GAMEBRYO),
+  rec(b"LVLC", "Leveled Creature", GAMEBRYO),
+  rec(b"SLGM", "Soul Gem", OBLIVION_SKYRIM),
+  rec(b"KEYM", "Key", ALL),
+  rec(b"ALCH", "Ingestible", ALL),
+  rec(b"SBSP", "Subspace", OBLIVION),
+  rec(b"SGST", "Sigil Stone", OBLIVION),
+  rec(b"LVLI", "Leveled Item", ALL),
+  rec(b"LVSP", "Leveled Spell", OBLIVION_SKYRIM),
+  rec(b"WTHR", "Weather", ALL),
+  rec(b"CLMT", "Climate", ALL),
+  rec(b"REGN", "Region", NOT_STARFIELD),
+  rec(b"CELL", "Cell", ALL),
+  rec(b"WRLD", "Worldspace", ALL),
+  rec(b"DIAL", "Dialog Topic", ALL),
+  rec(b"INFO", "Dialog Response", ALL),
+  rec(b"QUST", "Quest", ALL),
+  rec(b"IDLE", "Idle Animation", ALL),
+  rec(b"PACK", "Package", ALL),
+  rec(b"CSTY", "Combat Style", ALL),
+  rec(b"LSCR", "Load Screen", ALL),
+  rec(b"ANIO", "Animated Object", NOT_STARFIELD),
+  rec(b"WATR", "Water", ALL),
+  rec(b"EFSH", "Effect Shader", ALL),
+  rec(b"REFR", "Placed Object", ALL),
+  rec(b"ACHR", "Placed NPC", ALL),
+  rec(b"ACRE", "Placed Creature", GAMEBRYO),
+  rec(b"PGRD", "Path Grid", OBLIVION),
+  rec(b"LAND", "Landscape", ALL),
+  rec(b"ROAD", "Road", OBLIVION),
+  rec(b"TXST", "Texture Set", FO3_ON),
+  rec(b"MICN", "Menu Icon", FALLOUT_GAMEBRYO),
+  rec(b"HDPT", "Head Part", FO3_ON),
+  rec(b"ASPC", "Acoustic Space", FO3_ON),
+  rec(b"TACT", "Talking Activator", FO3_ON),
+  rec(b"SCRL", "Scroll", SKYRIM),
+  rec(b"MSTT", "Movable Static", FO3_ON),
+  rec(b"PWAT", "Placeable Water", FO3_TO_SKYRIM),
+  rec(b"SCOL", "Static Collection", FO3_ON),
+  rec(b"NOTE", "Note", FALLOUT),
+  rec(
+    b"TERM",
+    "Terminal",
+    &[Fallout3, FalloutNV, Fallout4, Fallout76, Starfield],
+  ),
+  rec(b"LVLN", "Leveled NPC", FO3_ON),
+  rec(b"IDLM", "Idle Marker", FO3_ON),
+  rec(b"COBJ", "Constructible Object", FO3_ON),
+  rec(b"PROJ", "Projectile", FO3_ON),
+  rec(b"HAZD", "Hazard", SKYRIM_ON),
+  rec(b"EXPL", "Explosion", FO3_ON),
+  rec(b"DEBR", "Debris", FO3_ON),
+  rec(b"IMGS", "Image Space", FO3_ON),
+  rec(b"IMAD", "Image Space Adapter", FO3_ON),
+  rec(b"FLST", "FormID List", FO3_ON),
+  rec(b"PERK", "Perk", FO3_ON),
+  rec(b"BPTD", "Body Part Data", FO3_ON),
+  rec(b"ADDN", "Addon Node", FO3_ON),
+  rec(b"AVIF", "Actor Value Information", FO3_ON),
+  rec(b"RADS", "Radiation Stage", FALLOUT_GAMEBRYO),
+  rec(b"CAMS", "Camera Shot", FO3_ON),
+  rec(b"CPTH", "Camera Path", FO3_ON),
+  rec(b"VTYP", "Voice Type", FO3_ON),
+  rec(b"IPCT", "Impact", FO3_ON),
+  rec(b"IPDS", "Impact Data Set", FO3_ON),
+  rec(b"ARMA", "Armor Addon", FO3_ON),
+  rec(b"ECZN", "Encounter Zone", FO3_TO_SKYRIM),
+  rec(b"MESG", "Message", FO3_ON),
+  rec(b"RGDL", "Ragdoll", FO3_TO_SKYRIM),
+  rec(b"DOBJ", "Default Object Manager", FO3_ON),
+  rec(b"LGTM", "Lighting Template", FO3_ON),
+  rec(b"MUSC", "Music Type", FO3_ON),
+  rec(b"PGRE", "Placed Grenade", FO3_ON),
+  rec(b"PMIS", "Placed Missile", FO3_ON),
+  rec(b"NAVM", "Navigation Mesh", FO3_ON),
+  rec(b"NAVI", "Navigation Mesh Info Map", FO3_ON),
+  rec(b"CHAL", "Challenge", NEW_VEGAS),
+  rec(b"IMOD", "Item Mod", NEW_VEGAS),
+  rec(b"REPU", "Reputation", NEW_VEGAS),
+  rec(b"RCPE", "Recipe", NEW_VEGAS),
+  rec(b"RCCT", "Recipe Category", NEW_VEGAS),
+  rec(b"CHIP", "Casino Chip", NEW_VEGAS),
+  rec(b"CSNO", "Casino", NEW_VEGAS),
+  rec(b"LSCT", "Load Screen Type", NEW_VEGAS),
+  rec(b"MSET", "Media Set", NEW_VEGAS),
+  rec(b"ALOC", "Media Location Controller", NEW_VEGAS),
+  rec(b"AMEF", "Ammo Effect", NEW_VEGAS),
+  rec(b"CCRD", "Caravan Card", NEW_VEGAS),
+  rec(b"CMNY", "Caravan Money", NEW_VEGAS),
+  rec(b"CDCK", "Caravan Deck", NEW_VEGAS),
+  rec(b"DEHY", "Dehydration Stage", NEW_VEGAS),
+  rec(b"HUNG", "Hunger Stage", NEW_VEGAS),
+  rec(b"SLPD", "Sleep Deprivation Stage", NEW_VEGAS),
+  rec(b"KYWD", "Keyword", SKYRIM_ON),
+  rec(b"LCRT", "Location Reference Type", SKYRIM_ON),
+  rec(b"AACT", "Action", SKYRIM_ON),
+  rec(b"SPGD", "Shader Particle Geometry", SKYRIM_TO_FO4),
+  rec(b"RFCT", "Visual Effect", SKYRIM_ON),
+  rec(b"MATT", "Material Type", SKYRIM_ON),
+  rec(b"LCTN", "Location", SKYRIM_ON),
+  rec(b"FSTP", "Footstep", SKYRIM_ON),
+  rec(b"FSTS", "Footstep Set", SKYRIM_ON),
+  rec(b"SMBN", "Story Manager Branch Node", SKYRIM_ON),
+  rec(b"SMQN", "Story Manager Quest Node", SKYRIM_ON),
+  rec(b"SMEN", "Story Manager Event Node", SKYRIM_ON),
+  rec(b"DLBR", "Dialog Branch", SKYRIM_ON),
+  rec(b"MUST", "Music Track", SKYRIM_ON),
+  rec(b"DLVW", "Dialog View", SKYRIM_ON),
+  rec(b"WOOP", "Word of Power", SKYRIM),
+  rec(b"SHOU", "Shout", SKYRIM),
+  rec(b"EQUP", "Equip Type", SKYRIM_ON),
+  rec(b"RELA", "Relationship", SKYRIM_ON),
+  rec(b"SCEN", "Scene", SKYRIM_ON),
+  rec(b"ASTP", "Association Type", SKYRIM_ON),
+  rec(b"OTFT", "Outfit", SKYRIM_ON),
+  rec(b"ARTO", "Art Object", SKYRIM_ON),
+  rec(b"MATO", "Material Object", SKYRIM_ON),
+  rec(b"MOVT", "Movement Type", SKYRIM_ON),
+  rec(b"SNDR", "Sound Descriptor", SKYRIM_ON),
+  rec(b"DUAL", "Dual Cast Data", SKYRIM),
+  rec(b"SNCT", "Sound Category", SKYRIM_ON),
+  rec(b"SOPM", "Sound Output Model", SKYRIM_ON),
+  rec(b"COLL", "Collision Layer", SKYRIM_ON),
+  rec(b"CLFM", "Color", SKYRIM_ON),
+  rec(b"REVB", "Reverb Parameters", SKYRIM_ON),
+  rec(b"PARW", "Placed Arrow", SKYRIM),
+  rec(b"PBAR", "Placed Barrier", SKYRIM_ON),
+  rec(b"PBEA", "Placed Beam", SKYRIM_ON),
+  rec(b"PCON", "Placed Cone/Voice", SKYRIM_ON),
+  rec(b"PFLA", "Placed Flame", SKYRIM_ON),
+  rec(b"PHZD", "Placed Hazard", SKYRIM_ON),
+  rec(b"LENS", "Lens Flare", SSE_ON),
+  rec(b"VOLI", "Volumetric Lighting", &[SkyrimSE]),
+  rec(b"TRNS", "Transform", FO4_ON),
+  rec(b"CMPO", "Component", FO4_ON),
+  rec(b"DMGT", "Damage Type", FO4_ON),
+  rec(b"PLYR", "Player Reference", FO4_ON),
+  rec(b"BNDS", "Bendable Spline", FO4_ON),
+  rec(b"PKIN", "Pack-In", FO4_ON),
+  rec(b"RFGP", "Reference Group", FO4_ON),
+  rec(b"AMDL", "Aim Model", FO4_ON),
+  rec(b"LAYR", "Layer", FO4_ON),
+  rec(b"OMOD", "Object Modification", FO4_ON),
+  rec(b"MSWP", "Material Swap", FO4_ON),
+  rec(b"ZOOM", "Zoom", FO4_ON),
+  rec(b"INNR", "Instance Naming Rules", FO4_ON),
+  rec(b"KSSM", "Sound Keyword Mapping", FO4_ON),
+  rec(b"AECH", "Audio Effect Chain", FO4_ON),
+  rec(b"SCCO", "Scene Collection", FO4_ON),
+  rec(b"AORU", "Attraction Rule", FO4_ON),
+  rec(b"SCSN", "Sound Category Snapshot", FO4_ON),
+  rec(b"STAG", "Animation Sound Tag Set", FO4_ON),
+  rec(b"NOCM", "Navigation Mesh Obstacle Manager", FO4_ON),
+  rec(b"GDRY", "God Rays", FO4_ON),
+  rec(b"OVIS", "Object Visibility Manager", FO4_ON),
+  rec(b"DFOB", "Default Object", FO4_ON),
+  rec(b"BIOM", "Biome", STARFIELD),
+  rec(b"PNDT", "Planet", STARFIELD),
+  rec(b"STDT", "Star", STARFIELD),
+  rec(b"GBFM", "Generic Base Form", STARFIELD),
+  rec(b"LVLP", "Leveled Pack-In", STARFIELD),
+  rec(b"SFBK", "Surface Block", STARFIELD),
+  rec(b"SFPT", "Surface Pattern", STARFIELD),
+  rec(b"SFTR", "Surface Tree", STARFIELD),
+];
+
+const fn sig(sig: &[u8; 4]) -> Signature {
+  Signature::new(sig)
+}
+
+const CELL_CHILDREN: &[Signature] = &[
+  sig(b"REFR"),
+  sig(b"ACHR"),
+  sig(b"ACRE"),
+  sig(b"PGRE"),
+  sig(b"PMIS"),
+  sig(b"PARW"),
+  sig(b"PBAR"),
+  sig(b"PBEA"),
+  sig(b"PCON"),
+  sig(b"PFLA"),
+  sig(b"PHZD"),
+  sig(b"LAND"),
+  sig(b"NAVM"),
+  sig(b"PGRD"),
+];
+const WORLD_CHILDREN: &[Signature] = &[
+  sig(b"CELL"),
+  sig(b"ROAD"),
+  sig(b"REFR"),
+  sig(b"ACHR"),
+  sig(b"ACRE"),
+  sig(b"PGRE"),
+  sig(b"PMIS"),
+  sig(b"PARW"),
+  sig(b"PBAR"),
+  sig(b"PBEA"),
+  sig(b"PCON"),
+  sig(b"PFLA"),
+  sig(b"PHZD"),
+  sig(b"LAND"),
+  sig(b"NAVM"),
+  sig(b"PGRD"),
+];
+
+pub(super) static NESTED: &[(Signature, &[Signature])] = &[
+  (sig(b"CELL"), CELL_CHILDREN),
+  (sig(b"WRLD"), WORLD_CHILDREN),
+  (sig(b"DIAL"), &[sig(b"INFO")]),
+  (
+    sig(b"QUST"),
+    &[sig(b"DIAL"), sig(b"INFO"), sig(b"SCEN"), sig(b"DLBR")],
+  ),
+];
+
+/// Top groups in the order the Skyrim Creation Kit writes them
+const SKYRIM_TOP_GROUPS: &[Signature] = &[
+  sig(b"GMST"),
+  sig(b"KYWD"),
+  sig(b"LCRT"),
+  sig(b"AACT"),
+  sig(b"TXST"),
+  sig(b"GLOB"),
+  sig(b"CLAS"),
+  sig(b"FACT"),
+  sig(b"HDPT"),
+  sig(b"HAIR"),
+  sig(b"EYES"),
+  sig(b"RACE"),
+  sig(b"SOUN"),
+  sig(b"ASPC"),
+  sig(b"MGEF"),
+  sig(b"SCPT"),
+  sig(b"LTEX"),
+  sig(b"ENCH"),
+  sig(b"SPEL"),
+  sig(b"SCRL"),
+  sig(b"ACTI"),
+  sig(b"TACT"),
+  sig(b"ARMO"),
+  sig(b"BOOK"),
+  sig(b"CONT"),
+  sig(b"DOOR"),
+  sig(b"INGR"),
+  sig(b"LIGH"),
+  sig(b"MISC"),
+  sig(b"APPA"),
+  sig(b"STAT"),
+  sig(b"SCOL"),
+  sig(b"MSTT"),
+  sig(b"PWAT"),
+  sig(b"GRAS"),
+  sig(b"TREE"),
+  sig(b"CLDC"),
+  sig(b"FLOR"),
+  sig(b"FURN"),
+  sig(b"WEAP"),
+  sig(b"AMMO"),
+  sig(b"NPC_"),
+  sig(b"LVLN"),
+  sig(b"KEYM"),
+  sig(b"ALCH"),
+  sig(b"IDLM"),
+  sig(b"COBJ"),
+  sig(b"PROJ"),
+  sig(b"HAZD"),
+  sig(b"SLGM"),
+  sig(b"LVLI"),
+  sig(b"WTHR"),
+  sig(b"CLMT"),
+  sig(b"SPGD"),
+  sig(b"RFCT"),
+  sig(b"REGN"),
+  sig(b"NAVI"),
+  sig(b"CELL"),
+  sig(b"WRLD"),
+  sig(b"DIAL"),
+  sig(b"QUST"),
+  sig(b"IDLE"),
+  sig(b"PACK"),
+  sig(b"CSTY"),
+  sig(b"LSCR"),
+  sig(b"LVSP"),
+  sig(b"ANIO"),
+  sig(b"WATR"),
+  sig(b"EFSH"),
+  sig(b"EXPL"),
+  sig(b"DEBR"),
+  sig(b"IMGS"),
+  sig(b"IMAD"),
+  sig(b"FLST"),
+  sig(b"PERK"),
+  sig(b"BPTD"),
+  sig(b"ADDN"),
+  sig(b"AVIF"),
+  sig(b"CAMS"),
+  sig(b"CPTH"),
+  sig(b"VTYP"),
+  sig(b"MATT"),
+  sig(b"IPCT"),
+  sig(b"IPDS"),
+  sig(b"ARMA"),
+  sig(b"ECZN"),
+  sig(b"LCTN"),
+  sig(b"MESG"),
+  sig(b"RGDL"),
+  sig(b"DOBJ"),
+  sig(b"LGTM"),
+  sig(b"MUSC"),
+  sig(b"FSTP"),
+  sig(b"FSTS"),
+  sig(b"SMBN"),
+  sig(b"SMQN"),
+  sig(b"SMEN"),
+  sig(b"DLBR"),
+  sig(b"MUST"),
+  sig(b"DLVW"),
+  sig(b"WOOP"),
+  sig(b"SHOU"),
+  sig(b"EQUP"),
+  sig(b"RELA"),
+  sig(b"SCEN"),
+  sig(b"ASTP"),
+  sig(b"OTFT"),
+  sig(b"ARTO"),
+  sig(b"MATO"),
+  sig(b"MOVT"),
+  sig(b"SNDR"),
+  sig(b"DUAL"),
+  sig(b"SNCT"),
+  sig(b"SOPM"),
+  sig(b"COLL"),
+  sig(b"CLFM"),
+  sig(b"REVB"),
+];
+/// Top groups in the order the Fallout 4 Creation Kit writes them
+const FALLOUT4_TOP_GROUPS: &[Signature] = &[
+  sig(b"GMST"),
+  sig(b"KYWD"),
+  sig(b"LCRT"),
+  sig(b"AACT"),
+  sig(b"TRNS"),
+  sig(b"CMPO"),
+  sig(b"TXST"),
+  sig(b"GLOB"),
+  sig(b"DMGT"),
+  sig(b"CLAS"),
+  sig(b"FACT"),
+  sig(b"HDPT"),
+  sig(b"EYES"),
+  sig(b"RACE"),
+  sig(b"SOUN"),
+  sig(b"ASPC"),
+  sig(b"MGEF"),
+  sig(b"LTEX"),
+  sig(b"ENCH"),
+  sig(b"SPEL"),
+  sig(b"ACTI"),
+  sig(b"TACT"),
+  sig(b"ARMO"),
+  sig(b"BOOK"),
+  sig(b"CONT"),
+  sig(b"DOOR"),
+  sig(b"INGR"),
+  sig(b"LIGH"),
+  sig(b"MISC"),
+  sig(b"STAT"),
+  sig(b"SCOL"),
+  sig(b"MSTT"),
+  sig(b"GRAS"),
+  sig(b"TREE"),
+  sig(b"FLOR"),
+  sig(b"FURN"),
+  sig(b"WEAP"),
+  sig(b"AMMO"),
+  sig(b"NPC_"),
+  sig(b"PLYR"),
+  sig(b"LVLN"),
+  sig(b"KEYM"),
+  sig(b"ALCH"),
+  sig(b"IDLM"),
+  sig(b"NOTE"),
+  sig(b"PROJ"),
+  sig(b"HAZD"),
+  sig(b"BNDS"),
+  sig(b"TERM"),
+  sig(b"LVLI"),
+  sig(b"WTHR"),
+  sig(b"CLMT"),
+  sig(b"SPGD"),
+  sig(b"RFCT"),
+  sig(b"REGN"),
+  sig(b"NAVI"),
+  sig(b"CELL"),
+  sig(b"WRLD"),
+  sig(b"QUST"),
+  sig(b"IDLE"),
+  sig(b"PACK"),
+  sig(b"CSTY"),
+  sig(b"LSCR"),
+  sig(b"ANIO"),
+  sig(b"WATR"),
+  sig(b"EFSH"),
+  sig(b"EXPL"),
+  sig(b"DEBR"),
+  sig(b"IMGS"),
+  sig(b"IMAD"),
+  sig(b"FLST"),
+  sig(b"PERK"),
+  sig(b"BPTD"),
+  sig(b"ADDN"),
+  sig(b"AVIF"),
+  sig(b"CAMS"),
+  sig(b"CPTH"),
+  sig(b"VTYP"),
+  sig(b"MATT"),
+  sig(b"IPCT"),
+  sig(b"IPDS"),
+  sig(b"ARMA"),
+  sig(b"ECZN"),
+  sig(b"LCTN"),
+  sig(b"MESG"),
+  sig(b"DOBJ"),
+  sig(b"DFOB"),
+  sig(b"LGTM"),
+  sig(b"MUSC"),
+  sig(b"FSTP"),
+  sig(b"FSTS"),
+  sig(b"SMBN"),
+  sig(b"SMQN"),
+  sig(b"SMEN"),
+  sig(b"MUST"),
+  sig(b"DLVW"),
+  sig(b"EQUP"),
+  sig(b"RELA"),
+  sig(b"ASTP"),
+  sig(b"OTFT"),
+  sig(b"ARTO"),
+  sig(b"MATO"),
+  sig(b"MOVT"),
+  sig(b"SNDR"),
+  sig(b"SNCT"),
+  sig(b"SOPM"),
+  sig(b"COLL"),
+  sig(b"CLFM"),
+  sig(b"REVB"),
+  sig(b"PKIN"),
+  sig(b"RFGP"),
+  sig(b"AMDL"),
+  sig(b"LAYR"),
+  sig(b"COBJ"),
+  sig(b"OMOD"),
+  sig(b"MSWP"),
+  sig(b"ZOOM"),
+  sig(b"INNR"),
+  sig(b"KSSM"),
+  sig(b"AECH"),
+  sig(b"SCCO"),
+  sig(b"AORU"),
+  sig(b"SCSN"),
+  sig(b"STAG"),
+  sig(b"NOCM"),
+  sig(b"LENS"),
+  sig(b"GDRY"),
+  sig(b"OVIS"),
+];
+
+pub(super) static TOP_GROUP_ORDER: &[(&[Game], &[Signature])] = &[
+  (SKYRIM, SKYRIM_TOP_GROUPS),
+  (&[Fallout4], FALLOUT4_TOP_GROUPS),
+];
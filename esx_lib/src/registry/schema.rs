@@ -0,0 +1,107 @@
+use super::{SchemaField, SchemaType};
+use crate::types::{Game, Signature};
+
+use SchemaType::*;
+
+const SKYRIM: &[Game] = &[Game::Skyrim, Game::SkyrimSE];
+
+const fn member(
+  record: &[u8; 4],
+  field: &[u8; 4],
+  name: &'static str,
+  offset: usize,
+  kind: SchemaType,
+) -> SchemaField {
+  SchemaField {
+    record: Signature::new(record),
+    field: Signature::new(field),
+    name,
+    offset,
+    kind,
+    flags: &[],
+    games: SKYRIM,
+  }
+}
+const fn flags(
+  record: &[u8; 4],
+  field: &[u8; 4],
+  offset: usize,
+  kind: SchemaType,
+  flags: &'static [(&'static str, u32)],
+) -> SchemaField {
+  SchemaField {
+    record: Signature::new(record),
+    field: Signature::new(field),
+    name: "flags",
+    offset,
+    kind,
+    flags,
+    games: SKYRIM,
+  }
+}
+
+pub(super) static SCHEMA: &[SchemaField] = &[
+  member(b"WEAP", b"DATA", "value", 0, U32),
+  member(b"WEAP", b"DATA", "weight", 4, F32),
+  member(b"WEAP", b"DATA", "damage", 8, U16),
+  member(b"WEAP", b"DNAM", "speed", 4, F32),
+  member(b"WEAP", b"DNAM", "reach", 8, F32),
+  member(b"ARMO", b"DATA", "value", 0, I32),
+  member(b"ARMO", b"DATA", "weight", 4, F32),
+  member(b"ARMO", b"DNAM", "rating", 0, I32),
+  member(b"AMMO", b"DATA", "projectile", 0, FormID),
+  member(b"AMMO", b"DATA", "damage", 8, F32),
+  member(b"AMMO", b"DATA", "value", 12, U32),
+  member(b"MISC", b"DATA", "value", 0, I32),
+  member(b"MISC", b"DATA", "weight", 4, F32),
+  member(b"INGR", b"DATA", "value", 0, I32),
+  member(b"INGR", b"DATA", "weight", 4, F32),
+  member(b"ALCH", b"DATA", "weight", 0, F32),
+  member(b"ALCH", b"ENIT", "value", 0, I32),
+  member(b"BOOK", b"DATA", "value", 8, U32),
+  member(b"BOOK", b"DATA", "weight", 12, F32),
+  member(b"KEYM", b"DATA", "value", 0, I32),
+  member(b"KEYM", b"DATA", "weight", 4, F32),
+  member(b"GLOB", b"FNAM", "type", 0, U8),
+  member(b"GLOB", b"FLTV", "value", 0, F32),
+  member(b"NPC_", b"ACBS", "level", 8, I16),
+  member(b"NPC_", b"RNAM", "race", 0, FormID),
+  flags(
+    b"NPC_",
+    b"ACBS",
+    0,
+    U32,
+    &[
+      ("Female", 0x01),
+      ("Essential", 0x02),
+      ("IsCharGenFacePreset", 0x04),
+      ("Respawn", 0x08),
+      ("AutoCalcStats", 0x10),
+      ("Unique", 0x20),
+      ("DoesntAffectStealthMeter", 0x40),
+      ("PCLevelMult", 0x80),
+      ("Protected", 0x800),
+      ("Summonable", 0x4000),
+      ("DoesntBleed", 0x10000),
+      ("BleedoutOverride", 0x40000),
+      ("OppositeGenderAnims", 0x80000),
+      ("SimpleActor", 0x100000),
+      ("Invulnerable", 0x80000000),
+    ],
+  ),
+];
+
+/// Flags of the record header, named as most record types use them
+pub(super) static RECORD_FLAGS: &[(&str, u32)] = &[
+  ("Master", 0x01),
+  ("Deleted", 0x20),
+  ("Localized", 0x80),
+  ("Light", 0x200),
+  ("Persistent", 0x400),
+  ("InitiallyDisabled", 0x800),
+  ("Ignored", 0x1000),
+  ("VisibleWhenDistant", 0x8000),
+  ("Dangerous", 0x20000),
+  ("Compressed", 0x40000),
+  ("CantWait", 0x80000),
+];
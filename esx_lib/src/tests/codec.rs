@@ -0,0 +1,193 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use flate2::{write::ZlibEncoder, Compression};
+use std::io::Write;
+
+use crate::{
+  codec::*,
+  record::RecordData,
+  types::{FormID, Signature},
+  Field, Record, Result,
+};
+
+const EDID: Signature = Signature::new(b"EDID");
+const CNAM: Signature = Signature::new(b"CNAM");
+const DNAM: Signature = Signature::new(b"DNAM");
+
+/// A keyword with its editor ID and color
+#[derive(Debug, Clone, PartialEq)]
+struct Keyword {
+  editor_id: Option<Lossless<String>>,
+  color: Option<Lossless<u32>>,
+}
+impl RecordCodec for Keyword {
+  const SIGNATURE: Signature = Signature::new(b"KYWD");
+  const FIELDS: &'static [Signature] = &[EDID, CNAM];
+
+  fn supports_form_version(form_version: u16) -> bool {
+    form_version <= 131
+  }
+  fn decode(reader: &mut FieldReader) -> Result<Self> {
+    Ok(Keyword {
+      editor_id: reader.decode_if(EDID)?,
+      color: reader.decode_if(CNAM)?,
+    })
+  }
+  fn encode(&self, fields: &mut Vec<Field>) {
+    if let Some(editor_id) = &self.editor_id {
+      fields.push(Field::from_lossless(EDID, editor_id));
+    }
+    if let Some(color) = &self.color {
+      fields.push(Field::from_lossless(CNAM, color));
+    }
+  }
+}
+
+fn record_bytes(flags: u32, form_version: u16, data: &[u8]) -> Bytes {
+  let mut buf = BytesMut::new();
+  buf.put_slice(b"KYWD");
+  buf.put_u32_le(data.len() as u32);
+  buf.put_u32_le(flags);
+  buf.put_u32_le(0x0001_0F00);
+  buf.put_u32_le(0);
+  buf.put_u16_le(form_version);
+  buf.put_u16_le(0);
+  buf.put_slice(data);
+  buf.freeze()
+}
+fn fields_bytes(fields: &[Field]) -> Vec<u8> {
+  fields.iter().flat_map(|f| f.as_bytes()).collect()
+}
+fn keyword_fields() -> Vec<Field> {
+  vec![
+    Field::from_zstring(EDID, "WeapTypeSword"),
+    Field::new(DNAM, vec![1, 2, 3]),
+    // A color with a trailing byte the codec doesn't know
+    Field::new(CNAM, vec![0x10, 0x20, 0x30, 0x00, 0xFF]),
+  ]
+}
+
+#[test]
+fn lossless_field_values() {
+  let field = Field::new(CNAM, vec![1, 0, 0, 0, 9, 9]);
+  let value = field.decode::<u32>().unwrap();
+  assert_eq!(*value.get_value(), 1);
+  assert_eq!(value.get_remainder().as_ref(), &[9, 9]);
+  assert_eq!(Field::from_lossless(CNAM, &value), field);
+
+  // NaN payloads survive a round trip
+  let field = Field::new(CNAM, vec![0x01, 0x00, 0xC0, 0x7F]);
+  let value = field.decode::<f32>().unwrap();
+  assert_eq!(Field::from_lossless(CNAM, &value), field);
+
+  let field = Field::from_form_id(CNAM, FormID::from(0x0100_0800));
+  assert_eq!(
+    *field.decode::<FormID>().unwrap().get_value(),
+    0x0100_0800.into()
+  );
+
+  // Unterminated strings are not decoded
+  assert!(Field::new(EDID, b"Sword".to_vec())
+    .decode::<String>()
+    .is_err());
+  assert!(Field::new(CNAM, vec![1, 2]).decode::<u32>().is_err());
+}
+
+#[test]
+fn lossless_unknown_fields() {
+  let buf = record_bytes(0, 131, &fields_bytes(&keyword_fields()));
+  let mut record = Record::from_bytes(&mut buf.clone()).unwrap();
+
+  let mut keyword = record.decode::<Keyword>().unwrap().unwrap();
+  assert_eq!(keyword.get_unknown_fields().len(), 1);
+  assert_eq!(keyword.get_unknown_fields()[0].0, 1);
+  assert_eq!(keyword.encode(), keyword_fields());
+  record.encode(&keyword);
+  assert_eq!(record.as_bytes(), buf);
+
+  // Edits keep unknown fields and remainders in place
+  *keyword
+    .get_value_mut()
+    .color
+    .as_mut()
+    .unwrap()
+    .get_value_mut() = 0x00FF_FFFF;
+  record.encode(&keyword);
+  let fields = record.get_data().get_fields();
+  assert_eq!(fields[1].get_signature(), &DNAM);
+  assert_eq!(
+    fields[2].as_bytes().as_ref()[6..],
+    [0xFF, 0xFF, 0xFF, 0x00, 0xFF]
+  );
+}
+
+#[test]
+fn lossless_trailing_bytes() {
+  let mut data = fields_bytes(&keyword_fields());
+  data.extend_from_slice(&[b'X', b'Y', 0x01]);
+  let buf = record_bytes(0, 131, &data);
+
+  let mut record = Record::from_bytes(&mut buf.clone()).unwrap();
+  let diagnostics = record.process();
+  assert_eq!(diagnostics.len(), 1);
+  assert!(matches!(record.get_data(), RecordData::Partial(..)));
+  assert_eq!(record.get_data().get_fields().len(), 3);
+  assert_eq!(record.as_bytes(), buf);
+
+  let keyword = record.decode::<Keyword>().unwrap().unwrap();
+  assert_eq!(
+    keyword.get_remainder().unwrap().as_ref(),
+    &[b'X', b'Y', 0x01]
+  );
+  record.encode(&keyword);
+  assert_eq!(record.as_bytes(), buf);
+}
+
+#[test]
+fn lossless_unknown_form_version() {
+  let buf = record_bytes(0, 200, &fields_bytes(&keyword_fields()));
+  let mut record = Record::from_bytes(&mut buf.clone()).unwrap();
+  assert!(record.decode::<Keyword>().unwrap().is_none());
+  record.process();
+  assert_eq!(record.as_bytes(), buf);
+}
+
+#[test]
+fn lossless_compressed_record() {
+  let fields = fields_bytes(&keyword_fields());
+  let mut data = (fields.len() as u32).to_le_bytes().to_vec();
+  let mut encoder = ZlibEncoder::new(&mut data, Compression::best());
+  encoder.write_all(&fields).unwrap();
+  encoder.finish().unwrap();
+  let buf = record_bytes(Record::COMPRESSED_FLAG, 131, &data);
+
+  // Unchanged fields write back the original compressed data
+  let mut record = Record::from_bytes(&mut buf.clone()).unwrap();
+  assert!(record.process().is_empty());
+  assert_eq!(record.get_data().get_fields().len(), 3);
+  assert_eq!(record.as_bytes(), buf);
+
+  // Changed fields are compressed again
+  let keyword = record.decode::<Keyword>().unwrap().unwrap();
+  record.encode(&keyword);
+  let bytes = record.as_bytes();
+  assert_eq!(record.serialized_size().unwrap(), bytes.len());
+  let mut reread = Record::from_bytes(&mut bytes.clone()).unwrap();
+  assert!(matches!(reread.get_data(), RecordData::Compressed(_)));
+  reread.process();
+  assert_eq!(
+    reread.get_data().get_fields(),
+    record.get_data().get_fields()
+  );
+
+  // Setting the flag on unread data reads the fields so they are compressed
+  let mut record = Record::from_bytes(&mut record_bytes(0, 131, &fields)).unwrap();
+  record.set_flags(Record::COMPRESSED_FLAG).unwrap();
+  let mut reread = Record::from_bytes(&mut record.as_bytes()).unwrap();
+  assert!(matches!(reread.get_data(), RecordData::Compressed(_)));
+  assert!(reread.process().is_empty());
+  assert_eq!(reread.get_data().get_fields().len(), 3);
+
+  // Clearing it writes them uncompressed
+  reread.set_flags(0).unwrap();
+  assert_eq!(reread.as_bytes(), record_bytes(0, 131, &fields));
+}
@@ -0,0 +1,96 @@
+use bytes::BytesMut;
+
+use super::{ctda, sig};
+use crate::{
+  condition::{Comparison, Condition, Operator, Param, RunOn},
+  types::{FormID, Game},
+  Field, Record,
+};
+
+#[test]
+fn condition_decode() {
+  // GetIsID(00013BBF) == 1, joined to the next with OR
+  let data = ctda(0x01, 1f32.to_bits(), 72, [0x13BBF, 0], 0);
+  let condition = Condition::decode(&data).unwrap();
+  assert_eq!(condition.operator, Operator::Equal);
+  assert!(condition.is_or());
+  assert_eq!(condition.comparison, Comparison::Value(1.0));
+  assert_eq!(condition.run_on, RunOn::Subject);
+  assert_eq!(condition.param3, Some(-1));
+  assert_eq!(
+    condition.get_params(Some(Game::Skyrim)),
+    vec![Param::Form(0x13BBF.into())]
+  );
+  let mut buf = BytesMut::new();
+  condition.encode(&mut buf);
+  assert_eq!(&buf[..], &data[..]);
+
+  // GetGlobalValue(...) >= global, in the short form older games write
+  let mut data = ctda(3 << 5 | Condition::USE_GLOBAL, 0x800, 74, [0x801, 0], 1);
+  data[1] = 0xAB;
+  data.truncate(24);
+  let condition = Condition::decode(&data).unwrap();
+  assert_eq!(condition.operator, Operator::GreaterOrEqual);
+  assert_eq!(condition.comparison, Comparison::Global(0x800.into()));
+  assert_eq!(condition.param3, None);
+  let mut buf = BytesMut::new();
+  condition.encode(&mut buf);
+  assert_eq!(&buf[..], &data[..]);
+
+  // GetIsID(...) == 1 run on a reference, in the 28-byte form of Fallout 3 and New Vegas
+  let mut data = ctda(0, 1f32.to_bits(), 72, [0x13BBF, 0], 2);
+  data[24..28].copy_from_slice(&0x14u32.to_le_bytes());
+  data.truncate(28);
+  let condition = Condition::decode(&data).unwrap();
+  assert_eq!(condition.run_on, RunOn::Reference);
+  assert_eq!(condition.reference, 0x14.into());
+  assert_eq!(condition.param3, None);
+  let mut buf = BytesMut::new();
+  condition.encode(&mut buf);
+  assert_eq!(&buf[..], &data[..]);
+  assert!(condition
+    .render(Some(Game::FalloutNV), &|_| None)
+    .starts_with("00000014.GetIsID("));
+
+  assert!(Condition::decode(&data[..20]).is_err());
+  assert!(Condition::decode(&ctda(7 << 5, 0, 72, [0, 0], 0)).is_err());
+}
+
+#[test]
+fn condition_render() {
+  let fields = vec![
+    Field::from_zstring(sig(b"EDID"), "Greeting"),
+    Field::new(
+      sig(b"CTDA"),
+      ctda(0x01, 1f32.to_bits(), 72, [0x13BBF, 0], 0),
+    ),
+    Field::new(sig(b"CTDA"), ctda(0, 1f32.to_bits(), 70, [1, 0], 0)),
+    Field::new(
+      sig(b"CTDA"),
+      ctda(4 << 5, 5f32.to_bits(), 58, [0xF00, 0], 1),
+    ),
+    Field::new(sig(b"CTDA"), ctda(1 << 5, 0, 630, [0xF00, 0], 0)),
+    Field::from_zstring(sig(b"CIS2"), "::Done_var"),
+    Field::new(sig(b"CTDA"), ctda(0, 0, 4000, [2, 3], 5)),
+  ];
+  let record = Record::new(sig(b"INFO"), 0xE01.into(), fields);
+  let conditions = Condition::read_all(&record).unwrap();
+  assert_eq!(conditions.len(), 5);
+  assert_eq!(conditions[3].strings[1].as_deref(), Some("::Done_var"));
+
+  let lookup = |id: FormID| match id.raw() {
+    0x13BBF => Some(sig(b"NPC_")),
+    _ => None,
+  };
+  let rendered = Condition::render_all(&conditions, Some(Game::Skyrim), &lookup);
+  assert_eq!(
+    rendered,
+    "GetIsID(NPC_:00013BBF) == 1 OR GetIsSex(Female) == 1 AND \
+     Target.GetStage(00000F00) < 5 AND GetVMScriptVariable(00000F00, \"::Done_var\") != 0 AND \
+     Alias[-1].Function4000(2, 3) == 0"
+  );
+  assert_eq!(conditions[1].to_string(), "GetIsSex(Female) == 1");
+
+  let fields: Vec<Field> = conditions.iter().flat_map(Condition::to_fields).collect();
+  assert_eq!(fields, record.read_fields().unwrap()[1..]);
+}
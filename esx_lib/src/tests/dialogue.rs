@@ -0,0 +1,66 @@
+use super::{ctda, sig, skyrim_se_plugin};
+use crate::{group::Placement, types::FormID, ESx, Field, Record};
+
+const TOPIC: u32 = 0xE00;
+const QUEST: u32 = 0xF00;
+const SPEAKER: u32 = 0x7;
+
+/// A GetIsID condition on `subject`
+fn get_is_id(subject: u32) -> Field {
+  Field::new(sig(b"CTDA"), ctda(0, 1f32.to_bits(), 72, [subject, 0], 0))
+}
+fn info(form_id: u32, previous: u32, text: &str) -> Record {
+  let fields = vec![
+    Field::from_form_id(sig(b"PNAM"), previous.into()),
+    Field::from_zstring(sig(b"NAM1"), text),
+    get_is_id(SPEAKER),
+  ];
+  Record::new(sig(b"INFO"), form_id.into(), fields)
+}
+
+#[test]
+fn dialogue_topic_responses() {
+  let mut esx = skyrim_se_plugin();
+  let placement = Placement::TopicChild {
+    topic: TOPIC.into(),
+  };
+  // Stored out of order, the chain is E01 -> E03 -> E02
+  for info in [info(0xE02, 0xE03, "third"), info(0xE01, 0, "first")] {
+    esx.insert_record(info, placement).unwrap();
+  }
+  let fields = vec![
+    Field::from_form_id(sig(b"PNAM"), 0xE01.into()),
+    Field::from_zstring(sig(b"RNAM"), "Hello?"),
+    Field::from_zstring(sig(b"NAM1"), "second"),
+    Field::from_zstring(sig(b"NAM1"), "and more"),
+  ];
+  let last = Record::new(sig(b"INFO"), 0xE03.into(), fields);
+  esx.insert_record(last, placement).unwrap();
+  let dial = Record::new(
+    sig(b"DIAL"),
+    TOPIC.into(),
+    vec![
+      Field::from_zstring(sig(b"EDID"), "GreetingTopic"),
+      Field::from_form_id(sig(b"QNAM"), QUEST.into()),
+    ],
+  );
+  esx.insert_record(dial, Placement::Top).unwrap();
+
+  let mut esx = ESx::from_bytes(&mut esx.as_bytes()).unwrap();
+  assert!(esx.process().is_empty());
+  let topics = esx.get_topics();
+  assert_eq!(topics.len(), 1);
+  let topic = esx.get_topic(TOPIC.into()).unwrap();
+  assert_eq!(topic.get_editor_id().as_deref(), Some("GreetingTopic"));
+  assert_eq!(topic.get_quest(), Some(QUEST.into()));
+
+  let responses = topic.get_responses();
+  let ids: Vec<FormID> = responses.iter().map(|r| r.get_form_id()).collect();
+  assert_eq!(ids, [0xE01.into(), 0xE03.into(), 0xE02.into()]);
+  assert_eq!(responses[0].get_previous(), None);
+  assert_eq!(responses[1].get_texts(), ["second", "and more"]);
+  assert_eq!(responses[1].get_prompt().as_deref(), Some("Hello?"));
+  assert_eq!(responses[0].get_speakers(), [SPEAKER.into()]);
+  assert!(responses[1].get_speakers().is_empty());
+  assert_eq!(responses[2].get_conditions().len(), 1);
+}
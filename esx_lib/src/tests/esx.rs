@@ -1,6 +1,16 @@
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 
-use crate::esx::*;
+use super::skyrim_se_plugin;
+
+use crate::{
+  diagnostics::{PathSegment, Severity},
+  esx::*,
+  group::{GroupData, GroupDataComponent, GroupLabel, Placement},
+  options::{ParseMode, ProcessOptions},
+  record::RecordData,
+  types::{FormID, Signature},
+  Group, Record,
+};
 
 const SAMPLE: [u8; 0x28B] = [
   0x54, 0x45, 0x53, 0x34, // 'TES4' as bytes
@@ -67,11 +77,546 @@ const SAMPLE: [u8; 0x28B] = [
 
 #[test]
 fn esx_from_buffer() {
-  let buf: BytesMut = BytesMut::from(SAMPLE.as_slice());
+  let buf = Bytes::from_static(&SAMPLE);
 
-  let esx = ESx::from_bytes(&mut buf.clone()).unwrap();
+  let mut esx = ESx::from_bytes(&mut buf.clone()).unwrap();
+  assert!(esx.process().is_empty());
   println!("ESx Test: {:#?}", esx);
   let bytes = esx.as_bytes();
   println!("Bytes: {:?}", bytes);
   assert_eq!(bytes, buf);
 }
+
+#[test]
+fn esx_lenient_top_group() {
+  // Claim the first top group is far larger than the file
+  let mut buf: BytesMut = BytesMut::from(SAMPLE.as_slice());
+  buf[0x68] = 0xFF;
+  let buf = buf.freeze();
+
+  assert!(ESx::from_bytes(&mut buf.clone()).is_err());
+
+  let esx = ESx::from_bytes_with_mode(&mut buf.clone(), ParseMode::Lenient).unwrap();
+  assert_eq!(esx.get_top_groups().len(), 1);
+  assert_eq!(esx.get_raw_data().len(), 1);
+  assert_eq!(esx.get_raw_data()[0].0, 0);
+  assert_eq!(esx.as_bytes(), buf);
+}
+
+#[test]
+fn esx_lenient_false_group_header() {
+  // Corrupt the first top group and write `GRUP` into two of its texture paths, one with a size
+  // larger than the file and one with a valid size but an unknown label type
+  let mut buf: BytesMut = BytesMut::from(SAMPLE.as_slice());
+  buf[0x68] = 0xFF;
+  buf[0xC2..0xC6].copy_from_slice(b"GRUP");
+  buf[0xFA..0x102].copy_from_slice(&[0x47, 0x52, 0x55, 0x50, 0x18, 0x00, 0x00, 0x00]);
+  let buf = buf.freeze();
+
+  let esx = ESx::from_bytes_with_mode(&mut buf.clone(), ParseMode::Lenient).unwrap();
+  assert_eq!(esx.get_raw_data().len(), 1);
+  assert_eq!(esx.get_raw_data()[0].1.len(), 0x109);
+  assert_eq!(esx.get_top_groups().len(), 1);
+  assert_eq!(esx.as_bytes(), buf);
+}
+
+#[test]
+fn esx_lenient_record() {
+  // Claim the TXST record is larger than its group
+  let mut buf: BytesMut = BytesMut::from(SAMPLE.as_slice());
+  buf[0x7F] = 0xFF;
+  let buf = buf.freeze();
+
+  let mut esx = ESx::from_bytes_with_mode(&mut buf.clone(), ParseMode::Lenient).unwrap();
+  let diagnostics = esx.process_with_mode(ParseMode::Lenient);
+  assert_eq!(diagnostics.len(), 1);
+  assert_eq!(*diagnostics[0].get_severity(), Severity::Error);
+  assert_eq!(diagnostics[0].get_path().len(), 1);
+
+  let data: &GroupData = esx.get_top_groups()[0].get_data();
+  assert_eq!(data.get_raw_data().len(), 1);
+  assert!(data.get_records().is_empty());
+  assert_eq!(esx.get_top_groups()[1].get_data().get_records().len(), 1);
+  assert_eq!(esx.as_bytes(), buf);
+}
+
+#[test]
+fn esx_clear_timestamps() {
+  let mut esx = ESx::from_bytes(&mut Bytes::from_static(&SAMPLE)).unwrap();
+  esx.process();
+  esx.clear_timestamps();
+  for group in esx.get_top_groups() {
+    assert_eq!(u16::from(*group.get_timestamp()), 0);
+  }
+  for record in esx.get_all_records() {
+    assert_eq!(u16::from(*record.get_timestamp()), 0);
+  }
+}
+
+#[test]
+fn esx_clear_timestamps_unprocessed() {
+  let mut buf: BytesMut = BytesMut::from(SAMPLE.as_slice());
+  assert_ne!(&buf[0x8B..0x8D], &[0, 0]); // TXST record timestamp
+  let unreadable = group_bytes(*b"ZZZZ", 0, &[vec![1, 2, 3]]);
+  buf.extend_from_slice(&unreadable);
+
+  // Cleared without processing first
+  let mut esx = ESx::from_bytes(&mut buf.freeze()).unwrap();
+  let diagnostics = esx.clear_timestamps();
+  assert_eq!(diagnostics.len(), 1);
+  assert_eq!(
+    diagnostics[0].get_path(),
+    &[PathSegment::Group(GroupLabel::Top(Signature::new(b"ZZZZ")))]
+  );
+  let bytes = esx.as_bytes();
+  assert_eq!(&bytes[0x8B..0x8D], &[0, 0]);
+  assert!(bytes.ends_with(&unreadable));
+}
+
+#[test]
+fn esx_vcs_info() {
+  let mut buf: BytesMut = BytesMut::from(SAMPLE.as_slice());
+  buf[0x75] = 0x12; // TXST group last user
+  buf[0x76] = 0x34; // TXST group current user
+  buf[0x91] = 0x05; // TXST record unknown word
+  let buf = buf.freeze();
+
+  let mut esx = ESx::from_bytes(&mut buf.clone()).unwrap();
+  esx.process();
+  assert_eq!(esx.as_bytes(), buf);
+
+  let entries = esx.get_vcs_entries();
+  assert_eq!(entries.len(), 5);
+  assert_eq!(entries.iter().filter(|e| e.has_data()).count(), 2);
+  assert_eq!(entries[1].vcs_info.last_user(), 0x12);
+  assert_eq!(entries[1].vcs_info.current_user(), 0x34);
+  assert_eq!(entries[2].unknown, 0x05);
+
+  esx.clear_vcs_info();
+  assert!(esx.get_vcs_entries().iter().all(|e| !e.has_data()));
+  assert_eq!(esx.as_bytes(), BytesMut::from(SAMPLE.as_slice()));
+}
+
+#[test]
+fn esx_vcs_info_unprocessed() {
+  let mut buf: BytesMut = BytesMut::from(SAMPLE.as_slice());
+  buf[0x8D] = 0x12; // TXST record last user
+  buf[0x91] = 0x05; // TXST record unknown word
+  let buf = buf.freeze();
+
+  // Read and cleared without processing first
+  let mut esx = ESx::from_bytes(&mut buf.clone()).unwrap();
+  let entries = esx.get_vcs_entries();
+  assert_eq!(entries.len(), 5);
+  assert_eq!(entries[2].vcs_info.last_user(), 0x12);
+  assert_eq!(entries[2].unknown, 0x05);
+
+  assert!(esx.clear_vcs_info().is_empty());
+  let bytes = esx.as_bytes();
+  assert_eq!(&bytes[0x7B..0x7F], b"TXST");
+  assert_eq!(&bytes[0x8D..0x8F], &[0, 0]);
+  assert_eq!(&bytes[0x91..0x93], &[0, 0]);
+  assert_eq!(bytes, BytesMut::from(SAMPLE.as_slice()));
+}
+
+#[test]
+fn esx_masters() {
+  let buf = Bytes::from_static(&SAMPLE);
+
+  let esx = ESx::from_bytes(&mut buf.clone()).unwrap();
+  assert_eq!(esx.get_masters(), vec!["Fallout4.esm".to_string()]);
+  assert_eq!(esx.detect_game(), Some(crate::types::Game::Fallout4));
+}
+
+#[test]
+fn esx_to_writer() {
+  let buf = Bytes::from_static(&SAMPLE);
+
+  let mut esx = ESx::from_bytes(&mut buf.clone()).unwrap();
+  for processed in [false, true] {
+    if processed {
+      assert!(esx.process().is_empty());
+    }
+    let mut out: Vec<u8> = vec![];
+    esx.to_writer(&mut out).unwrap();
+    assert_eq!(out, buf);
+    assert_eq!(esx.serialized_size().unwrap(), buf.len());
+    assert!(esx.check_sizes().is_ok());
+  }
+
+  let path = std::env::temp_dir().join(format!("esx_to_writer_{}.esp", std::process::id()));
+  esx
+    .try_write(&std::fs::File::create(&path).unwrap())
+    .unwrap();
+  let written = std::fs::read(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(written, buf);
+}
+
+#[test]
+fn esx_reprocess_structured_group() {
+  let buf = Bytes::from_static(&SAMPLE);
+
+  let mut esx = ESx::from_bytes(&mut buf.clone()).unwrap();
+  assert!(esx.process().is_empty());
+
+  // Swap a processed record for an unprocessed copy inside an already structured group
+  let data = esx.get_top_groups_mut()[0].get_data_mut();
+  let components = data.get_components_mut().unwrap();
+  let Some(GroupDataComponent::Record(record)) = components.first_mut() else {
+    panic!("first component should be a record");
+  };
+  *record = Record::from_bytes(&mut record.as_bytes()).unwrap();
+  assert!(matches!(record.get_data(), RecordData::Raw(_)));
+
+  assert!(esx.process().is_empty());
+  let record = esx.get_top_groups()[0].get_data().get_records_recurse()[0];
+  assert!(matches!(record.get_data(), RecordData::Generic(_)));
+  assert_eq!(esx.as_bytes(), buf);
+}
+
+#[test]
+fn esx_process_selected_signatures() {
+  let buf = Bytes::from_static(&SAMPLE);
+
+  let mut esx = ESx::from_bytes(&mut buf.clone()).unwrap();
+  let options = ProcessOptions::new().with_signatures([Signature::new(b"HDPT")]);
+  assert!(esx.process_with_options(&options).is_empty());
+  let [txst, hdpt] = esx.get_top_groups().as_slice() else {
+    panic!("sample has two top groups");
+  };
+  assert!(matches!(txst.get_data(), GroupData::Raw(_)));
+  let records = hdpt.get_data().get_records_recurse();
+  assert!(!records.is_empty());
+  assert!(records
+    .iter()
+    .all(|r| matches!(r.get_data(), RecordData::Generic(_))));
+  assert_eq!(esx.as_bytes(), buf);
+
+  let mut esx = ESx::from_bytes(&mut buf.clone()).unwrap();
+  let options = ProcessOptions::new().with_top_groups_only(true);
+  assert!(esx.process_with_options(&options).is_empty());
+  for group in esx.get_top_groups() {
+    assert!(matches!(group.get_label(), GroupLabel::Top(_)));
+    assert!(matches!(group.get_data(), GroupData::Raw(_)));
+  }
+  assert_eq!(esx.as_bytes(), buf);
+}
+
+#[test]
+fn process_options_nested_groups() {
+  let options = ProcessOptions::new().with_signatures([Signature::new(b"REFR")]);
+  assert!(options.selects_group(&GroupLabel::Top(Signature::new(b"CELL"))));
+  assert!(options.selects_group(&GroupLabel::Top(Signature::new(b"WRLD"))));
+  assert!(!options.selects_group(&GroupLabel::Top(Signature::new(b"WEAP"))));
+  assert!(!options.selects_record(&Signature::new(b"CELL")));
+
+  let options = ProcessOptions::new().with_max_depth(1);
+  assert!(options.structures_depth(1));
+  assert!(!options.structures_depth(2));
+}
+
+fn record_bytes(signature: &[u8; 4], form_id: u32) -> Vec<u8> {
+  let mut buf = signature.to_vec();
+  buf.extend_from_slice(&[0; 4]);
+  buf.extend_from_slice(&[0; 4]);
+  buf.extend_from_slice(&form_id.to_le_bytes());
+  buf.extend_from_slice(&[0; 8]);
+  buf
+}
+fn group_bytes(label: [u8; 4], label_type: u32, contents: &[Vec<u8>]) -> Vec<u8> {
+  let contents = contents.concat();
+  let mut buf = b"GRUP".to_vec();
+  buf.extend_from_slice(&(24 + contents.len() as u32).to_le_bytes());
+  buf.extend_from_slice(&label);
+  buf.extend_from_slice(&label_type.to_le_bytes());
+  buf.extend_from_slice(&[0; 8]);
+  buf.extend_from_slice(&contents);
+  buf
+}
+
+#[test]
+fn esx_normalise() {
+  let mut esx = ESx::from_bytes(&mut Bytes::from_static(&SAMPLE)).unwrap();
+  let cell = group_bytes(
+    *b"CELL",
+    0,
+    &[
+      record_bytes(b"CELL", 0x20),
+      record_bytes(b"CELL", 0x10),
+      group_bytes(
+        0x10u32.to_le_bytes(),
+        6,
+        &[
+          group_bytes(
+            0x10u32.to_le_bytes(),
+            9,
+            &[record_bytes(b"REFR", 0x31), record_bytes(b"REFR", 0x30)],
+          ),
+          group_bytes(0x10u32.to_le_bytes(), 8, &[]),
+        ],
+      ),
+    ],
+  );
+  let unknown = group_bytes(*b"ZZZZ", 0, &[]);
+  for buf in [unknown, cell] {
+    let group = Group::from_bytes(&mut Bytes::from(buf)).unwrap();
+    esx.get_top_groups_mut().insert(0, group);
+  }
+  esx.get_top_groups_mut().swap(2, 3);
+  assert!(esx.process().is_empty());
+  esx.normalise();
+
+  let labels: Vec<GroupLabel> = esx
+    .get_top_groups()
+    .iter()
+    .map(|g| *g.get_label())
+    .collect();
+  let top = |s: &[u8; 4]| GroupLabel::Top(Signature::new(s));
+  assert_eq!(
+    labels,
+    [top(b"TXST"), top(b"HDPT"), top(b"CELL"), top(b"ZZZZ")]
+  );
+
+  let cells = esx.get_top_groups()[2].get_data().get_components().unwrap();
+  let GroupDataComponent::Record(first) = &cells[0] else {
+    panic!("expected a record first");
+  };
+  assert_eq!(*first.get_form_id(), 0x10.into());
+  let GroupDataComponent::Group(children) = &cells[1] else {
+    panic!("expected the children of the first cell");
+  };
+  assert_eq!(*children.get_label(), GroupLabel::CellChildren(0x10.into()));
+  let subgroups = children.get_data().get_subgroups();
+  assert_eq!(
+    *subgroups[0].get_label(),
+    GroupLabel::CellPersistentChildren(0x10.into())
+  );
+  let refs: Vec<FormID> = subgroups[1]
+    .get_data()
+    .get_records()
+    .iter()
+    .map(|r| *r.get_form_id())
+    .collect();
+  assert_eq!(refs, [0x30.into(), 0x31.into()]);
+  assert!(matches!(cells[2], GroupDataComponent::Record(_)));
+
+  // Normalising twice changes nothing
+  let bytes = esx.as_bytes();
+  esx.normalise();
+  assert_eq!(esx.as_bytes(), bytes);
+}
+
+#[test]
+fn cell_block_numbers() {
+  let (block, sub_block) = GroupLabel::interior_cell_blocks(0x0101_2345.into());
+  assert_eq!(block, GroupLabel::InteriorCellBlock(5));
+  assert_eq!(sub_block, GroupLabel::InteriorCellSubBlock(6));
+
+  let (block, sub_block) = GroupLabel::exterior_cell_blocks(33, -1);
+  assert_eq!(block, GroupLabel::ExteriorCellBlock { x: 1, y: -1 });
+  assert_eq!(sub_block, GroupLabel::ExteriorCellSubBlock { x: 4, y: -1 });
+  let (block, sub_block) = GroupLabel::exterior_cell_blocks(-33, 8);
+  assert_eq!(block, GroupLabel::ExteriorCellBlock { x: -2, y: 0 });
+  assert_eq!(sub_block, GroupLabel::ExteriorCellSubBlock { x: -5, y: 1 });
+}
+
+#[test]
+fn esx_insert_record() {
+  let mut esx = ESx::from_bytes(&mut Bytes::from_static(&SAMPLE)).unwrap();
+  let world: FormID = 0x0100_0D00.into();
+  let cell: FormID = 0x0100_0D01.into();
+  let refr = Record::new(Signature::new(b"REFR"), 0x0100_0D02.into(), vec![]);
+  let placement = Placement::ExteriorCellChild {
+    world,
+    cell,
+    x: -3,
+    y: 40,
+    persistent: false,
+  };
+  esx.insert_record(refr, placement).unwrap();
+  // The cell goes before the children group made for its reference
+  let cell_record = Record::new(Signature::new(b"CELL"), cell, vec![]);
+  let placement = Placement::ExteriorCell {
+    world,
+    x: -3,
+    y: 40,
+  };
+  esx.insert_record(cell_record, placement).unwrap();
+  // Persistent references go in the world's persistent cell, outside the grid blocks
+  let persistent_cell: FormID = 0x0100_0D03.into();
+  let refr = Record::new(Signature::new(b"REFR"), 0x0100_0D04.into(), vec![]);
+  let placement = Placement::WorldPersistentChild {
+    world,
+    cell: persistent_cell,
+  };
+  esx.insert_record(refr, placement).unwrap();
+  let cell_record = Record::new(Signature::new(b"CELL"), persistent_cell, vec![]);
+  let placement = Placement::PersistentCell { world };
+  esx.insert_record(cell_record, placement).unwrap();
+  let world_record = Record::new(Signature::new(b"WRLD"), world, vec![]);
+  esx.insert_record(world_record, Placement::Top).unwrap();
+
+  let info = Record::new(Signature::new(b"INFO"), 0x0100_0E01.into(), vec![]);
+  let placement = Placement::TopicChild {
+    topic: 0x0100_0E00.into(),
+  };
+  esx.insert_record(info, placement).unwrap();
+
+  // Everything survives being written and read back
+  let mut esx = ESx::from_bytes(&mut esx.as_bytes()).unwrap();
+  assert!(esx.process().is_empty());
+  let labels: Vec<GroupLabel> = esx
+    .get_top_groups()
+    .iter()
+    .map(|g| *g.get_label())
+    .collect();
+  let top = |s: &[u8; 4]| GroupLabel::Top(Signature::new(s));
+  assert_eq!(
+    labels,
+    [top(b"TXST"), top(b"HDPT"), top(b"WRLD"), top(b"DIAL")]
+  );
+
+  let worlds = esx.get_top_groups()[2].get_data().get_components().unwrap();
+  assert!(matches!(&worlds[0], GroupDataComponent::Record(r) if *r.get_form_id() == world));
+  let GroupDataComponent::Group(children) = &worlds[1] else {
+    panic!("expected the world's children");
+  };
+  let components = children.get_data().get_components().unwrap();
+  assert!(
+    matches!(&components[0], GroupDataComponent::Record(r) if *r.get_form_id() == persistent_cell)
+  );
+  let GroupDataComponent::Group(persistent_children) = &components[1] else {
+    panic!("expected the persistent cell's children");
+  };
+  assert_eq!(
+    *persistent_children.get_label(),
+    GroupLabel::CellChildren(persistent_cell)
+  );
+  let persistent = &persistent_children.get_data().get_subgroups()[0];
+  assert_eq!(
+    *persistent.get_label(),
+    GroupLabel::CellPersistentChildren(persistent_cell)
+  );
+  assert_eq!(persistent.get_data().get_records().len(), 1);
+  assert_eq!(children.get_data().get_subgroups().len(), 2);
+
+  let block = &children.get_data().get_subgroups()[1];
+  assert_eq!(
+    *block.get_label(),
+    GroupLabel::ExteriorCellBlock { x: -1, y: 1 }
+  );
+  let sub_block = &block.get_data().get_subgroups()[0];
+  assert_eq!(
+    *sub_block.get_label(),
+    GroupLabel::ExteriorCellSubBlock { x: -1, y: 5 }
+  );
+  let cells = sub_block.get_data().get_components().unwrap();
+  assert!(matches!(&cells[0], GroupDataComponent::Record(r) if *r.get_form_id() == cell));
+  let GroupDataComponent::Group(cell_children) = &cells[1] else {
+    panic!("expected the cell's children");
+  };
+  let temporary = &cell_children.get_data().get_subgroups()[0];
+  assert_eq!(
+    *temporary.get_label(),
+    GroupLabel::CellTemporaryChildren(cell)
+  );
+  assert_eq!(temporary.get_data().get_records().len(), 1);
+
+  let topic = &esx.get_top_groups()[3].get_data().get_subgroups()[0];
+  assert_eq!(
+    *topic.get_label(),
+    GroupLabel::TopicChildren(0x0100_0E00.into())
+  );
+}
+
+/// The FormIDs of the records in a group and the labels of its subgroups, in order
+fn component_ids(group: &Group) -> Vec<Result<FormID, GroupLabel>> {
+  let components = group.get_data().get_components().unwrap();
+  components
+    .iter()
+    .map(|c| match c {
+      GroupDataComponent::Record(r) => Ok(*r.get_form_id()),
+      GroupDataComponent::Group(g) => Err(*g.get_label()),
+      _ => panic!("expected records and groups"),
+    })
+    .collect()
+}
+
+#[test]
+fn esx_normalise_inserted_cells() {
+  let mut esx = skyrim_se_plugin();
+  let world: FormID = 0xD00.into();
+  // The cell with the higher FormID comes first
+  for (cell, refr) in [(0xD11, 0xD21), (0xD10, 0xD20)] {
+    let cell_record = Record::new(Signature::new(b"CELL"), cell.into(), vec![]);
+    let placement = Placement::ExteriorCell { world, x: 0, y: 0 };
+    esx.insert_record(cell_record, placement).unwrap();
+    let refr = Record::new(Signature::new(b"REFR"), refr.into(), vec![]);
+    let placement = Placement::ExteriorCellChild {
+      world,
+      cell: cell.into(),
+      x: 0,
+      y: 0,
+      persistent: false,
+    };
+    esx.insert_record(refr, placement).unwrap();
+  }
+  // Read back without processing, and add to the first cell only, leaving the labels after it
+  // unread
+  let mut esx = ESx::from_bytes(&mut esx.as_bytes()).unwrap();
+  let refr = Record::new(Signature::new(b"REFR"), 0xD22.into(), vec![]);
+  let placement = Placement::ExteriorCellChild {
+    world,
+    cell: 0xD11.into(),
+    x: 0,
+    y: 0,
+    persistent: false,
+  };
+  esx.insert_record(refr, placement).unwrap();
+  esx.normalise();
+
+  let worlds = esx.get_top_groups()[0].get_data().get_subgroups();
+  let block = &worlds[0].get_data().get_subgroups()[0];
+  let sub_block = &block.get_data().get_subgroups()[0];
+  assert_eq!(
+    component_ids(sub_block),
+    [
+      Ok(0xD10.into()),
+      Err(GroupLabel::CellChildren(0xD10.into())),
+      Ok(0xD11.into()),
+      Err(GroupLabel::CellChildren(0xD11.into())),
+    ]
+  );
+}
+
+#[test]
+fn esx_normalise_inserted_topics() {
+  let mut esx = skyrim_se_plugin();
+  for (topic, info) in [(0xE01, 0xE11), (0xE00, 0xE10)] {
+    let dial = Record::new(Signature::new(b"DIAL"), topic.into(), vec![]);
+    esx.insert_record(dial, Placement::Top).unwrap();
+    let info = Record::new(Signature::new(b"INFO"), info.into(), vec![]);
+    let placement = Placement::TopicChild {
+      topic: topic.into(),
+    };
+    esx.insert_record(info, placement).unwrap();
+  }
+  let mut esx = ESx::from_bytes(&mut esx.as_bytes()).unwrap();
+  let info = Record::new(Signature::new(b"INFO"), 0xE12.into(), vec![]);
+  let placement = Placement::TopicChild {
+    topic: 0xE01.into(),
+  };
+  esx.insert_record(info, placement).unwrap();
+  esx.normalise();
+
+  assert_eq!(
+    component_ids(&esx.get_top_groups()[0]),
+    [
+      Ok(0xE00.into()),
+      Err(GroupLabel::TopicChildren(0xE00.into())),
+      Ok(0xE01.into()),
+      Err(GroupLabel::TopicChildren(0xE01.into())),
+    ]
+  );
+}
@@ -0,0 +1,97 @@
+use bytes::{BufMut, BytesMut};
+
+use crate::{
+  field::*,
+  record::RecordData,
+  types::{FormID, Signature},
+  Error,
+};
+
+fn round_trip(field: &Field) -> Field {
+  let mut buf = field.as_bytes();
+  let read = Field::from_bytes(&mut buf).unwrap();
+  assert!(buf.is_empty());
+  read
+}
+
+#[test]
+fn field_size_boundary() {
+  let edid = Signature::new(b"EDID");
+
+  let largest = Field::new(edid, vec![0xAB; u16::MAX as usize]);
+  assert!(!largest.is_oversized());
+  let bytes = largest.as_bytes();
+  assert_eq!(&bytes[0..4], b"EDID");
+  assert_eq!(bytes.len(), Field::MAXIMUM_SIZE);
+  assert_eq!(round_trip(&largest), largest);
+
+  let smallest = Field::new(edid, vec![0xAB; u16::MAX as usize + 1]);
+  assert!(smallest.is_oversized());
+  let bytes = smallest.as_bytes();
+  assert_eq!(&bytes[0..4], b"XXXX");
+  assert_eq!(&bytes[10..14], b"EDID");
+  assert_eq!(
+    bytes.len(),
+    Field::OVERSIZE_HEADER_SIZE + u16::MAX as usize + 1
+  );
+  assert_eq!(round_trip(&smallest), smallest);
+}
+
+#[test]
+fn field_oversized_navm_land() {
+  let nvnm = Field::new(
+    Signature::new(b"NVNM"),
+    (0..200_000u32).map(|i| i as u8).collect::<Vec<u8>>(),
+  );
+  let vhgt = Field::new(Signature::new(b"VHGT"), vec![0x7F; 0x1_0A00]);
+  let edid = Field::from_zstring(Signature::new(b"EDID"), "TestNavmesh");
+
+  let mut buf = BytesMut::new();
+  for field in [&edid, &nvnm, &vhgt] {
+    buf.put(field.as_bytes());
+  }
+  let expected = buf.freeze();
+
+  let mut data = RecordData::Raw(expected.clone());
+  data.process().unwrap();
+  let fields = data.get_fields();
+  assert_eq!(fields, vec![&edid, &nvnm, &vhgt]);
+  assert_eq!(data.as_bytes(), expected);
+}
+
+#[test]
+fn field_oversized_truncated() {
+  let field = Field::new(Signature::new(b"NVNM"), vec![0; 0x2_0000]);
+  let bytes = field.as_bytes();
+
+  // Cut inside the XXXX field, inside the following header, and inside the data
+  for len in [8, 12, bytes.len() - 1] {
+    let mut buf = bytes.slice(..len);
+    assert!(matches!(
+      Field::from_bytes(&mut buf),
+      Err(Error::BufferTooShort)
+    ));
+    assert_eq!(buf.len(), len);
+  }
+}
+
+#[test]
+fn field_typed_values() {
+  let sig = Signature::new(b"DATA");
+  assert_eq!(Field::from_u8(sig, 7).get_u8(), Some(7));
+  assert_eq!(Field::from_u16(sig, 0x1234).get_u16(), Some(0x1234));
+  assert_eq!(Field::from_u32(sig, 0xDEADBEEF).get_u32(), Some(0xDEADBEEF));
+  assert_eq!(Field::from_i32(sig, -5).get_i32(), Some(-5));
+  assert_eq!(Field::from_f32(sig, 1.5).get_f32(), Some(1.5));
+  let id = FormID::from(0x0100_0F99);
+  assert_eq!(Field::from_form_id(sig, id).get_form_id(), Some(id));
+  assert_eq!(Field::from_u16(sig, 1).get_u32(), None);
+
+  let name = Field::from_zstring(Signature::new(b"FULL"), "Laser Musket");
+  assert_eq!(name.len(), 13);
+  assert_eq!(name.get_zstring(), "Laser Musket");
+
+  let empty = Field::empty(sig);
+  assert!(empty.is_empty());
+  assert_eq!(round_trip(&empty).get_bytes(), empty.get_bytes());
+}
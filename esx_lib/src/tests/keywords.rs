@@ -0,0 +1,140 @@
+use super::{plugin, sig, skyrim_se_plugin};
+use crate::{
+  group::Placement,
+  types::{FormID, Signature},
+  Field, Record,
+};
+
+const WEAPON_TYPE: u32 = 0x1E711;
+const MAGIC: u32 = 0x800;
+
+fn weapon(form_id: u32, keywords: &[u32]) -> Record {
+  let kwda: Vec<u8> = keywords.iter().flat_map(|k| k.to_le_bytes()).collect();
+  let fields = vec![
+    Field::from_zstring(sig(b"EDID"), "Weapon"),
+    Field::from_u32(sig(b"KSIZ"), keywords.len() as u32),
+    Field::new(sig(b"KWDA"), kwda),
+    Field::new(sig(b"DATA"), vec![0; 10]),
+  ];
+  Record::new(sig(b"WEAP"), form_id.into(), fields)
+}
+fn signatures(record: &Record) -> Vec<Signature> {
+  let fields = record.read_fields().unwrap();
+  fields.iter().map(|f| *f.get_signature()).collect()
+}
+
+#[test]
+fn keyword_edit() {
+  let mut record = weapon(0x801, &[WEAPON_TYPE]);
+  assert_eq!(record.get_keywords(), [FormID::from(WEAPON_TYPE)]);
+
+  assert!(record.add_keyword(MAGIC.into()).unwrap());
+  assert!(!record.add_keyword(MAGIC.into()).unwrap());
+  assert_eq!(record.get_keywords(), [WEAPON_TYPE.into(), MAGIC.into()]);
+  assert_eq!(record.read_field(sig(b"KSIZ")).unwrap().get_u32(), Some(2));
+  assert_eq!(
+    signatures(&record),
+    [sig(b"EDID"), sig(b"KSIZ"), sig(b"KWDA"), sig(b"DATA")]
+  );
+
+  assert!(record.remove_keyword(WEAPON_TYPE.into()).unwrap());
+  assert!(!record.remove_keyword(WEAPON_TYPE.into()).unwrap());
+  assert_eq!(record.read_field(sig(b"KSIZ")).unwrap().get_u32(), Some(1));
+  assert!(record.remove_keyword(MAGIC.into()).unwrap());
+  assert_eq!(signatures(&record), [sig(b"EDID"), sig(b"DATA")]);
+
+  assert!(record.add_keyword(MAGIC.into()).unwrap());
+  assert_eq!(
+    signatures(&record),
+    [sig(b"EDID"), sig(b"DATA"), sig(b"KSIZ"), sig(b"KWDA")]
+  );
+}
+
+#[test]
+fn keyword_query() {
+  let mut esx = skyrim_se_plugin();
+  let keyword = Record::new(
+    sig(b"KYWD"),
+    MAGIC.into(),
+    vec![Field::from_zstring(sig(b"EDID"), "WeapTypeMagic")],
+  );
+  let records = [
+    keyword,
+    weapon(0x801, &[WEAPON_TYPE, MAGIC]),
+    weapon(0x802, &[WEAPON_TYPE]),
+  ];
+  for record in records {
+    esx.insert_record(record, Placement::Top).unwrap();
+  }
+
+  let ids =
+    |records: Vec<&Record>| -> Vec<FormID> { records.iter().map(|r| *r.get_form_id()).collect() };
+  assert_eq!(
+    ids(esx.get_records_with_keyword(WEAPON_TYPE.into())),
+    [0x801.into(), 0x802.into()]
+  );
+  assert_eq!(
+    ids(esx.get_records_with_keyword_named(&[], "weaptypemagic")),
+    [FormID::from(0x801)]
+  );
+  assert!(esx
+    .get_records_with_keyword_named(&[], "Missing")
+    .is_empty());
+  let index = esx.get_keyword_index();
+  assert_eq!(ids(index[&MAGIC.into()].clone()), [FormID::from(0x801)]);
+  assert_eq!(index[&WEAPON_TYPE.into()].len(), 2);
+
+  for record in esx.get_all_records_mut() {
+    if *record.get_signature() == sig(b"WEAP") {
+      record.add_keyword(MAGIC.into()).unwrap();
+    }
+  }
+  assert_eq!(esx.get_records_with_keyword(MAGIC.into()).len(), 2);
+}
+
+#[test]
+fn keyword_from_masters() {
+  let keyword = |form_id: u32, editor_id: &str| {
+    let edid = Field::from_zstring(sig(b"EDID"), editor_id);
+    Record::new(sig(b"KYWD"), form_id.into(), vec![edid])
+  };
+  let base = plugin(&[], vec![keyword(WEAPON_TYPE, "WeapTypeSword")]);
+  // Defines its own keyword, and overrides the base one under another editor ID
+  let update = plugin(
+    &["Base.esm"],
+    vec![
+      keyword(0x0100_0800, "WeapTypeMagic"),
+      keyword(WEAPON_TYPE, "WeapTypeBlade"),
+    ],
+  );
+  let mut esx = plugin(
+    &["Base.esm", "Update.esm"],
+    vec![
+      weapon(0x0200_0801, &[WEAPON_TYPE, 0x0100_0800]),
+      weapon(0x0200_0802, &[WEAPON_TYPE]),
+    ],
+  );
+  let masters = [("Base.esm", &base), ("Update.esm", &update)];
+
+  assert_eq!(esx.get_keyword_id("WeapTypeMagic"), None);
+  assert_eq!(
+    esx.get_keyword_id_in(&masters, "weaptypemagic"),
+    Some(0x0100_0800.into())
+  );
+  assert_eq!(
+    esx.get_keyword_id_in(&masters, "WeapTypeBlade"),
+    Some(WEAPON_TYPE.into())
+  );
+  assert_eq!(esx.get_keyword_id_in(&masters[..1], "WeapTypeMagic"), None);
+  // Update.esm isn't a master of its master
+  assert_eq!(base.get_keyword_id_in(&masters, "WeapTypeMagic"), None);
+  assert_eq!(
+    esx
+      .get_records_with_keyword_named(&masters, "WeapTypeMagic")
+      .len(),
+    1
+  );
+
+  esx.process();
+  assert!(esx.get_all_records()[1].has_keyword(WEAPON_TYPE.into()));
+}
@@ -0,0 +1,161 @@
+use super::{plugin, sig};
+use crate::{
+  diagnostics::PathSegment,
+  leveled::{merge_leveled_lists, LeveledEntry, LeveledList},
+  types::FormID,
+  ESx, Field, Record,
+};
+
+fn lvlo(level: u16, reference: u32, count: u16) -> Field {
+  let mut data = vec![0; 12];
+  data[0..2].copy_from_slice(&level.to_le_bytes());
+  data[4..8].copy_from_slice(&reference.to_le_bytes());
+  data[8..10].copy_from_slice(&count.to_le_bytes());
+  Field::new(sig(b"LVLO"), data)
+}
+/// A leveled item list with a chance none and the given entries
+fn list(form_id: u32, chance_none: u8, entries: &[(u16, u32)]) -> Record {
+  let mut fields = vec![
+    Field::from_zstring(sig(b"EDID"), "LItemTest"),
+    Field::from_u8(sig(b"LVLD"), chance_none),
+    Field::from_u8(sig(b"LVLF"), 1),
+    Field::from_u8(sig(b"LLCT"), entries.len() as u8),
+  ];
+  fields.extend(entries.iter().map(|(level, id)| lvlo(*level, *id, 1)));
+  Record::new(sig(b"LVLI"), form_id.into(), fields)
+}
+#[test]
+fn leveled_list_codec() {
+  // A Fallout 4 list: two byte flags, an entry chance none, ownership and a trailing keyword chance
+  let mut entry = lvlo(2, 0x1234, 3).get_bytes().to_vec();
+  entry[10] = 25;
+  let fields = vec![
+    Field::from_zstring(sig(b"EDID"), "LItemFO4"),
+    Field::from_u8(sig(b"LVLD"), 5),
+    Field::from_u8(sig(b"LVLM"), 2),
+    Field::from_u16(sig(b"LVLF"), 0x0101),
+    Field::from_u8(sig(b"LLCT"), 1),
+    Field::new(sig(b"LVLO"), entry),
+    Field::new(
+      sig(b"COED"),
+      vec![0x14, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0x80, 0x3F],
+    ),
+    Field::new(sig(b"LLKC"), vec![0; 8]),
+  ];
+  let mut record = Record::new(sig(b"LVLI"), 0x800.into(), fields.clone());
+  let mut list = record.decode::<LeveledList>().unwrap().unwrap();
+  assert_eq!(list.encode(), fields);
+
+  let value = list.get_value();
+  assert_eq!(*value.max_count.as_ref().unwrap().get_value(), 2);
+  assert!(value.flags.as_ref().unwrap().has_remainder());
+  let entry = &value.entries[0];
+  assert_eq!((entry.level, entry.count, entry.chance_none), (2, 3, 25));
+  assert_eq!(entry.reference, FormID::from(0x1234));
+  let owner = entry.owner.as_ref().unwrap().get_value();
+  assert_eq!(
+    (owner.owner, owner.value, owner.condition),
+    (0x14.into(), 1, 1.0)
+  );
+
+  // New entries go before the trailing field and update the count
+  let entries = &mut list.get_value_mut().entries;
+  entries.push(LeveledEntry::new(4, 0x5678.into(), 1));
+  record.encode(&list);
+  let fields = record.read_fields().unwrap();
+  let signatures: Vec<&[u8; 4]> = fields
+    .iter()
+    .map(|f| f.get_signature().as_array())
+    .collect();
+  assert_eq!(
+    signatures[4..],
+    [b"LLCT", b"LVLO", b"COED", b"LVLO", b"LLKC"]
+  );
+  assert_eq!(fields[4].get_u8(), Some(2));
+
+  let spell = Record::new(sig(b"LVSP"), 0x801.into(), vec![]);
+  assert!(spell.decode::<LeveledList>().unwrap().is_some());
+  let other = Record::new(sig(b"WEAP"), 0x802.into(), vec![]);
+  assert!(other.decode::<LeveledList>().unwrap().is_none());
+}
+
+#[test]
+fn leveled_list_merge() {
+  let base = plugin(
+    &[],
+    vec![
+      list(0x800, 0, &[(1, 0x801), (5, 0x802)]),
+      list(0xA00, 0, &[(1, 0x801)]),
+    ],
+  );
+  // Adds its own item
+  let first = plugin(
+    &["Base.esm"],
+    vec![
+      list(0x800, 0, &[(1, 0x801), (5, 0x802), (10, 0x0100_0900)]),
+      list(0xA00, 0, &[(1, 0x801), (2, 0x802)]),
+    ],
+  );
+  // Removes an item, adds another and changes the chance none. Its other list has ownership data
+  // without an entry, so that list isn't merged.
+  let malformed = Record::new(
+    sig(b"LVLI"),
+    0xA00.into(),
+    vec![
+      Field::from_u8(sig(b"LLCT"), 0),
+      Field::new(sig(b"COED"), vec![0; 12]),
+    ],
+  );
+  let second = plugin(
+    &["Base.esm"],
+    vec![list(0x800, 10, &[(3, 0x803), (1, 0x801)]), malformed],
+  );
+  // Only overridden once, so not merged
+  let unrelated = plugin(&[], vec![list(0x900, 0, &[(1, 0x901)])]);
+  let load_order = [
+    ("Base.esm", &base),
+    ("First.esp", &first),
+    ("Second.esp", &second),
+    ("Other.esp", &unrelated),
+  ];
+
+  let (patch, diagnostics) = merge_leveled_lists(&load_order).unwrap();
+  assert_eq!(diagnostics.len(), 1);
+  assert!(diagnostics[0].get_message().contains("Second.esp"));
+  assert_eq!(
+    diagnostics[0].get_path(),
+    &[PathSegment::Record {
+      signature: sig(b"LVLI"),
+      form_id: 0xA00.into(),
+    }]
+  );
+  assert_eq!(patch.get_masters(), ["Base.esm", "First.esp", "Second.esp"]);
+  let mut reread = ESx::from_bytes(&mut patch.as_bytes()).unwrap();
+  reread.process();
+  let records = reread.get_all_records();
+  assert_eq!(records.len(), 2);
+  let hedr = records[0].read_field(sig(b"HEDR")).unwrap();
+  assert_eq!(&hedr.get_bytes()[4..8], &2u32.to_le_bytes());
+
+  let merged = records[1].decode::<LeveledList>().unwrap().unwrap();
+  assert_eq!(*records[1].get_form_id(), FormID::from(0x800));
+  let merged = merged.get_value();
+  let entries: Vec<(u16, FormID)> = merged
+    .entries
+    .iter()
+    .map(|e| (e.level, e.reference))
+    .collect();
+  assert_eq!(
+    entries,
+    [
+      (1, 0x801.into()),
+      (3, 0x803.into()),
+      (10, 0x0100_0900.into()),
+    ]
+  );
+  assert_eq!(*merged.chance_none.as_ref().unwrap().get_value(), 10);
+  assert_eq!(
+    records[1].read_field(sig(b"EDID")).unwrap().get_zstring(),
+    "LItemTest"
+  );
+}
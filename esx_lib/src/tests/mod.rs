@@ -1,3 +1,64 @@
+mod codec;
+mod condition;
+mod dialogue;
 mod esx;
 mod field;
+mod keywords;
+mod leveled;
+mod query;
+mod quest;
 mod record;
+mod types;
+mod vmad;
+mod world;
+
+use bytes::Bytes;
+
+use crate::{group::Placement, types::Signature, ESx, Field, Record};
+
+/// A TES4 header without fields
+const SKYRIM_SE_HEADER: &[u8] = &[
+  0x54, 0x45, 0x53, 0x34, // 'TES4' as bytes
+  0x00, 0x00, 0x00, 0x00, // Data field size in bytes (0)
+  0x00, 0x00, 0x00, 0x00, // Flags (None)
+  0x00, 0x00, 0x00, 0x00, // Form ID (0)
+  0x00, 0x00, // Timestamp (unset)
+  0x00, 0x00, // VCS (unset)
+  0x2C, 0x00, // Internal version 44
+  0x00, 0x00, // Unknown u16
+];
+
+fn sig(s: &[u8; 4]) -> Signature {
+  Signature::new(s)
+}
+/// A plugin with only a Skyrim SE header, to insert records into
+fn skyrim_se_plugin() -> ESx {
+  ESx::from_bytes(&mut Bytes::from_static(SKYRIM_SE_HEADER)).unwrap()
+}
+/// A Skyrim SE plugin with these masters and top-level records
+fn plugin(masters: &[&str], records: Vec<Record>) -> ESx {
+  let mut fields = vec![Field::from_f32(sig(b"HEDR"), 1.71)];
+  for master in masters {
+    fields.push(Field::from_zstring(sig(b"MAST"), master));
+    fields.push(Field::new(sig(b"DATA"), vec![0; 8]));
+  }
+  let mut header = Record::new(sig(b"TES4"), 0.into(), fields);
+  header.set_form_version(44);
+  let mut esx = ESx::new(header);
+  for record in records {
+    esx.insert_record(record, Placement::Top).unwrap();
+  }
+  esx
+}
+/// A 32-byte CTDA field
+fn ctda(first: u8, comparison: u32, function: u16, params: [u32; 2], run_on: u32) -> Vec<u8> {
+  let mut data = vec![0; 32];
+  data[0] = first;
+  data[4..8].copy_from_slice(&comparison.to_le_bytes());
+  data[8..10].copy_from_slice(&function.to_le_bytes());
+  data[12..16].copy_from_slice(&params[0].to_le_bytes());
+  data[16..20].copy_from_slice(&params[1].to_le_bytes());
+  data[20..24].copy_from_slice(&run_on.to_le_bytes());
+  data[28..32].copy_from_slice(&(-1i32).to_le_bytes());
+  data
+}
@@ -0,0 +1,97 @@
+use super::sig;
+use crate::{query::Query, types::Game, Field, Record};
+
+fn weapon(editor_id: &str, damage: u16) -> Record {
+  let mut data = vec![0; 10];
+  data[0..4].copy_from_slice(&25u32.to_le_bytes());
+  data[8..10].copy_from_slice(&damage.to_le_bytes());
+  let fields = vec![
+    Field::from_zstring(sig(b"EDID"), editor_id),
+    Field::from_form_id(sig(b"ETYP"), 0x00012EB7.into()),
+    Field::new(sig(b"DATA"), data),
+  ];
+  Record::new(sig(b"WEAP"), 0x800.into(), fields)
+}
+fn npc(acbs_flags: u32, record_flags: u32) -> Record {
+  let mut acbs = vec![0; 24];
+  acbs[0..4].copy_from_slice(&acbs_flags.to_le_bytes());
+  acbs[8..10].copy_from_slice(&12i16.to_le_bytes());
+  let fields = vec![Field::new(sig(b"ACBS"), acbs)];
+  let mut record = Record::new(sig(b"NPC_"), 0x801.into(), fields);
+  record.set_flags(record_flags).unwrap();
+  record.set_form_version(44);
+  record
+}
+fn matches(query: &str, record: &Record) -> bool {
+  Query::parse(query)
+    .unwrap()
+    .matches(record, Some(Game::SkyrimSE))
+}
+
+#[test]
+fn query_matches() {
+  let iron = weapon("IronSword", 12);
+  let steel = weapon("SteelSword", 8);
+  let query = r#"WEAP where EDID ~ "Iron.*" and DATA.damage > 10"#;
+  assert!(matches(query, &iron));
+  assert!(!matches(query, &steel));
+  assert!(!matches("ARMO", &iron));
+  assert!(matches("ARMO, WEAP", &iron));
+
+  assert!(matches("* referencing 00012EB7", &iron));
+  assert!(!matches("* referencing 00012EB8", &iron));
+  // FormIDs of light plugins start with a letter
+  let light = Record::new(
+    sig(b"WEAP"),
+    0xFE00_0D62.into(),
+    vec![Field::from_form_id(sig(b"ETYP"), 0xFE00_0D63.into())],
+  );
+  assert!(matches("* referencing FE000D63", &light));
+  assert!(matches("* referencing fe000d63", &light));
+  assert!(!matches("* referencing FE000D64", &light));
+  assert!(matches("WEAP form_id = FE000D62", &light));
+  assert!(matches("WEAP ETYP = FE000D63 and ETYP != 00012EB7", &light));
+  assert!(matches(
+    r#"* EDID = "SteelSword" or DATA.damage >= 12"#,
+    &iron
+  ));
+  assert!(matches("WEAP not (FULL or EDID !~ \"Sword$\")", &iron));
+  assert!(matches(
+    "WEAP DATA[8] = 12 and DATA[0..4] = x\"19 00 00 00\"",
+    &iron
+  ));
+  assert!(matches("WEAP DATA.value = 25 and ETYP = 00012EB7", &iron));
+  assert!(matches(
+    "WEAP signature = \"WEAP\" and form_id = 0x800",
+    &iron
+  ));
+
+  let essential = npc(0x02, 0);
+  let deleted = npc(0, 0x20);
+  assert!(matches("NPC_ flags.Essential", &essential));
+  assert!(!matches("NPC_ flags.Essential", &deleted));
+  assert!(matches("NPC_ flags.deleted and flags.0x20", &deleted));
+  assert!(matches(
+    "NPC_ ACBS.level = 12 and form_version >= 44",
+    &deleted
+  ));
+  assert!(!matches("NPC_ flags.NoSuchFlag", &essential));
+}
+
+#[test]
+fn query_errors() {
+  for query in [
+    "",
+    "WEAPON",
+    "WEAP where",
+    "WEAP EDID ~",
+    "WEAP EDID ~ \"(\"",
+    "WEAP DATA[4..2]",
+    "WEAP (EDID",
+    "WEAP EDID = \"unterminated",
+    "WEAP editor = 1",
+    "WEAP EDID = 1 1",
+  ] {
+    assert!(Query::parse(query).is_err(), "{}", query);
+  }
+}
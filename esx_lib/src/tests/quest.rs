@@ -0,0 +1,78 @@
+use super::{sig, skyrim_se_plugin};
+use crate::{group::Placement, quest::*, ESx, Field, Record};
+
+const QUEST: u32 = 0xF00;
+
+#[test]
+fn quest_structure() {
+  let fields = vec![
+    Field::from_zstring(sig(b"EDID"), "MQ101"),
+    Field::from_zstring(sig(b"FULL"), "Unbound"),
+    Field::new(sig(b"INDX"), vec![10, 0, 2, 0]),
+    Field::from_u8(sig(b"QSDT"), 1),
+    Field::new(sig(b"CTDA"), vec![0; 32]),
+    Field::from_zstring(sig(b"CNAM"), "I escaped."),
+    Field::new(sig(b"INDX"), vec![200, 0, 0, 0]),
+    Field::from_u8(sig(b"QSDT"), 0),
+    Field::from_form_id(sig(b"NAM0"), 0xF01.into()),
+    Field::from_u16(sig(b"QOBJ"), 10),
+    Field::from_u32(sig(b"FNAM"), 1),
+    Field::from_zstring(sig(b"NNAM"), "Escape Helgen"),
+    Field::new(sig(b"QSTA"), vec![2, 0, 0, 0, 0, 0, 0, 0]),
+    Field::from_u32(sig(b"ANAM"), 3),
+    Field::from_u32(sig(b"ALST"), 2),
+    Field::from_zstring(sig(b"ALID"), "Hadvar"),
+    Field::from_u32(sig(b"FNAM"), 0x40),
+    Field::empty(sig(b"ALED")),
+    Field::from_u32(sig(b"ALLS"), 3),
+    Field::from_zstring(sig(b"ALID"), "Helgen"),
+    Field::empty(sig(b"ALED")),
+  ];
+  let mut esx = skyrim_se_plugin();
+  let quest = Record::new(sig(b"QUST"), QUEST.into(), fields);
+  esx.insert_record(quest, Placement::Top).unwrap();
+  let scene = Record::new(
+    sig(b"SCEN"),
+    0xF10.into(),
+    vec![Field::from_zstring(sig(b"EDID"), "MQ101Intro")],
+  );
+  let placement = Placement::QuestChild {
+    quest: QUEST.into(),
+  };
+  esx.insert_record(scene, placement).unwrap();
+
+  let mut esx = ESx::from_bytes(&mut esx.as_bytes()).unwrap();
+  assert!(esx.process().is_empty());
+  let quests = esx.get_quests().unwrap();
+  assert_eq!(quests.len(), 1);
+  let quest = &quests[0];
+  assert_eq!(quest.editor_id.as_deref(), Some("MQ101"));
+  assert_eq!(quest.name.as_deref(), Some("Unbound"));
+
+  assert_eq!(quest.stages.len(), 2);
+  assert_eq!(quest.stages[0].index, 10);
+  assert_eq!(quest.stages[0].flags, 2);
+  let entry = &quest.stages[0].log_entries[0];
+  assert_eq!(entry.text.as_deref(), Some("I escaped."));
+  assert_eq!(entry.condition_count, 1);
+  assert_eq!(
+    quest.stages[1].log_entries[0].next_quest,
+    Some(0xF01.into())
+  );
+
+  let objective = &quest.objectives[0];
+  assert_eq!(objective.index, 10);
+  assert_eq!(objective.flags, 1);
+  assert_eq!(objective.text.as_deref(), Some("Escape Helgen"));
+  assert_eq!(objective.target_aliases, [2]);
+
+  assert_eq!(quest.aliases.len(), 2);
+  assert_eq!(quest.aliases[0].kind, AliasKind::Reference);
+  assert_eq!(quest.aliases[0].name.as_deref(), Some("Hadvar"));
+  assert_eq!(quest.aliases[0].flags, 0x40);
+  assert_eq!(quest.aliases[1].kind, AliasKind::Location);
+  assert_eq!(quest.aliases[1].flags, 0);
+
+  assert_eq!(quest.scenes.len(), 1);
+  assert_eq!(quest.scenes[0].editor_id.as_deref(), Some("MQ101Intro"));
+}
@@ -1,4 +1,4 @@
-use bytes::BytesMut;
+use bytes::Bytes;
 
 use crate::record::*;
 
@@ -15,7 +15,7 @@ const SAMPLE: [u8; 24] = [
 
 #[test]
 fn record_from_buffer() {
-  let buf: BytesMut = BytesMut::from(SAMPLE.as_slice());
+  let buf = Bytes::from_static(&SAMPLE);
 
   let record = Record::from_bytes(&mut buf.clone()).unwrap();
   println!("Record Test: {:#?}", record);
@@ -0,0 +1,191 @@
+use crate::types::*;
+
+#[test]
+fn timestamp_bit_packed() {
+  // Group stamp from a Fallout 4 plugin
+  let timestamp = Timestamp::from(0x20A2);
+  assert_eq!(timestamp.to_ymd(Game::Fallout4), Some((2016, 5, 2)));
+  assert_eq!(
+    Timestamp::from_ymd(2016, 5, 2, Game::Fallout4),
+    Some(timestamp)
+  );
+  assert_eq!(Timestamp::from_ymd(2130, 1, 1, Game::Fallout4), None);
+}
+
+#[test]
+fn timestamp_month_count() {
+  let timestamp = Timestamp::from_ymd(2011, 11, 11, Game::Skyrim).unwrap();
+  assert_eq!(u16::from(timestamp), (107 << 8) | 11);
+  assert_eq!(timestamp.to_ymd(Game::Skyrim), Some((2011, 11, 11)));
+  assert_eq!(Timestamp::from(0x0100).to_ymd(Game::Skyrim), None);
+  assert_eq!(Timestamp::from_ymd(2002, 12, 1, Game::Skyrim), None);
+}
+
+#[test]
+fn timestamp_game_families() {
+  // The same 11 November 2011 in each format
+  let month_count = Timestamp::from((107 << 8) | 11);
+  let bit_packed = Timestamp::from((11 << 9) | (11 << 5) | 11);
+  for game in Game::ALL {
+    let expected = match game {
+      Game::Oblivion => None,
+      Game::Fallout3 | Game::FalloutNV | Game::Skyrim => {
+        Some(TimestampFormat::MonthCount { epoch_year: 2003 })
+      }
+      _ => Some(TimestampFormat::BitPacked { epoch_year: 2000 }),
+    };
+    assert_eq!(TimestampFormat::for_game(game), expected);
+    let Some(expected) = expected else {
+      // Oblivion's stamps are never dates
+      assert_eq!(month_count.to_ymd(game), None);
+      assert_eq!(Timestamp::from_ymd(2011, 11, 11, game), None);
+      continue;
+    };
+    let timestamp = match expected {
+      TimestampFormat::MonthCount { .. } => month_count,
+      TimestampFormat::BitPacked { .. } => bit_packed,
+    };
+    assert_eq!(timestamp.to_ymd(game), Some((2011, 11, 11)), "{}", game);
+    assert_eq!(Timestamp::from_ymd(2011, 11, 11, game), Some(timestamp));
+  }
+}
+
+#[test]
+fn timestamp_unset() {
+  assert_eq!(Timestamp::default().to_ymd(Game::Skyrim), None);
+  assert_eq!(Timestamp::default().to_ymd(Game::Fallout4), None);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn timestamp_chrono() {
+  let date = chrono::NaiveDate::from_ymd_opt(2015, 11, 10).unwrap();
+  let timestamp = Timestamp::from_date(date, Game::Fallout4).unwrap();
+  assert_eq!(timestamp.to_date(Game::Fallout4), Some(date));
+  // 30 February
+  assert_eq!(Timestamp::from(0x205E).to_date(Game::Fallout4), None);
+}
+
+#[test]
+fn vcs_info_round_trip() {
+  let vcs_info = VcsInfo::from(0x3412);
+  assert_eq!(vcs_info.last_user(), 0x12);
+  assert_eq!(vcs_info.current_user(), 0x34);
+  assert_eq!(u16::from(vcs_info), 0x3412);
+  assert_eq!(vcs_info.as_bytes().as_ref(), &[0x12, 0x34]);
+}
+
+#[test]
+fn signature_from_str() {
+  assert_eq!(
+    "WEAP".parse::<Signature>().unwrap(),
+    Signature::new(b"WEAP")
+  );
+  let imad = Signature::new(&[0x12, b'I', b'A', b'D']);
+  assert_eq!(imad.as_string().parse::<Signature>().unwrap(), imad);
+  assert!(Signature::try_from("WEAPON").is_err());
+  assert!(Signature::try_from("WÉP").is_err());
+  assert!(Signature::new(b"ARMO") < Signature::new(b"WEAP"));
+}
+
+#[test]
+fn game_from_header() {
+  for (form_version, hedr, game) in [
+    (0, Some(1.0), Some(Game::Oblivion)),
+    (0, Some(0.8), Some(Game::Oblivion)),
+    (15, Some(0.94), Some(Game::Fallout3)),
+    (15, Some(1.34), Some(Game::FalloutNV)),
+    (43, Some(0.94), Some(Game::Skyrim)),
+    (44, Some(1.71), Some(Game::SkyrimSE)),
+    (131, Some(1.0), Some(Game::Fallout4)),
+    (208, Some(68.0), Some(Game::Fallout76)),
+    (555, Some(0.96), Some(Game::Starfield)),
+    (15, None, None),
+    (15, Some(2.5), None),
+  ] {
+    assert_eq!(Game::from_header(form_version, hedr), game);
+  }
+}
+
+#[test]
+fn signature_registry() {
+  use crate::registry;
+  let weap = Signature::new(b"WEAP");
+  assert_eq!(
+    registry::describe_record(Some(Game::Fallout4), weap),
+    "WEAP — Weapon"
+  );
+  assert!(!registry::is_known_record(
+    Game::Fallout4,
+    Signature::new(b"CLOT")
+  ));
+  assert!(registry::is_known_record(
+    Game::Oblivion,
+    Signature::new(b"CLOT")
+  ));
+  assert_eq!(
+    registry::describe_record(None, Signature::new(b"ZZZZ")),
+    "ZZZZ — Unknown"
+  );
+  let edid = Signature::new(b"EDID");
+  assert_eq!(registry::field_name(weap, edid), Some("Editor ID"));
+  assert_eq!(
+    registry::field_name(weap, Signature::new(b"DNAM")),
+    Some("Weapon Data")
+  );
+  assert_eq!(registry::field_name(weap, Signature::new(b"HEDR")), None);
+}
+
+#[test]
+fn form_id_layouts() {
+  let id = FormID::from(0x0100_0F99);
+  assert_eq!(id.master_index(), 0x01);
+  assert_eq!(id.object_id(), 0x000F99);
+  assert_eq!(id.layout(), FormIDLayout::Full);
+
+  let light = FormID::from(0xFE01_2801);
+  assert_eq!(light.layout(), FormIDLayout::Light);
+  assert_eq!(light.slot(), 0x012);
+  assert_eq!(light.slot_object_id(), 0x801);
+
+  let medium = FormID::from(0xFD03_1234);
+  assert_eq!(medium.layout(), FormIDLayout::Medium);
+  assert_eq!(medium.slot(), 0x03);
+  assert_eq!(medium.slot_object_id(), 0x1234);
+
+  assert!(FormID::NULL.is_null());
+  assert!(FormID::from(0x14).is_hardcoded());
+  assert!(!FormID::from(0x800).is_hardcoded());
+}
+
+#[test]
+fn form_id_parse_and_display() {
+  assert_eq!(
+    "0x0001F4A5".parse::<FormID>().unwrap(),
+    FormID::from(0x0001_F4A5)
+  );
+  assert_eq!(
+    "1f4a5".parse::<FormID>().unwrap(),
+    FormID::from(0x0001_F4A5)
+  );
+  assert!("0x".parse::<FormID>().is_err());
+  assert!("+1".parse::<FormID>().is_err());
+  assert!("100000000".parse::<FormID>().is_err());
+
+  let masters = ["Fallout4.esm", "DLCRobot.esm"];
+  let id = FormID::from(0x0100_0F99);
+  assert_eq!(
+    id.display_with_masters(&masters, "Mod.esp"),
+    "[DLCRobot.esm]:000F99"
+  );
+  let own = FormID::from(0x0200_0800);
+  assert_eq!(
+    own.display_with_masters(&masters, "Mod.esp"),
+    "[Mod.esp]:000800"
+  );
+  let bad = FormID::from(0x0500_0800);
+  assert_eq!(
+    bad.display_with_masters(&masters, "Mod.esp"),
+    "[05?]:000800"
+  );
+}
@@ -0,0 +1,223 @@
+use bytes::{Bytes, BytesMut};
+
+use super::sig;
+use crate::{vmad::*, Field, Record};
+
+/// Skyrim VMAD: version 5, object format 2, one script with an object and an int property
+const SKYRIM_VMAD: &[u8] = &[
+  0x05, 0x00, 0x02, 0x00, // Version, object format
+  0x01, 0x00, // Script count
+  0x06, 0x00, b'M', b'y', b'Q', b'u', b'e', b's', // Script name
+  0x00, // Status
+  0x02, 0x00, // Property count
+  0x04, 0x00, b'D', b'o', b'o', b'r', // Property name
+  0x01, 0x01, // Object, edited
+  0x00, 0x00, 0xFF, 0xFF, 0xBF, 0x3B, 0x01, 0x00, // Unused, no alias, FormID 00013BBF
+  0x05, 0x00, b'C', b'o', b'u', b'n', b't', // Property name
+  0x03, 0x01, // Int, edited
+  0x2A, 0x00, 0x00, 0x00, // 42
+];
+
+fn encode(vmad: &Vmad) -> Bytes {
+  let mut buf = BytesMut::new();
+  vmad.encode(&mut buf);
+  buf.freeze()
+}
+fn object(form_id: u32, alias: i16) -> ObjectRef {
+  ObjectRef {
+    form_id: form_id.into(),
+    alias,
+    unused: 0,
+  }
+}
+fn property(name: &str, value: PropertyValue) -> Property {
+  Property {
+    name: name.to_string(),
+    status: 1,
+    value,
+  }
+}
+fn script(name: &str, properties: Vec<Property>) -> Script {
+  Script {
+    name: name.to_string(),
+    status: 0,
+    properties,
+  }
+}
+
+#[test]
+fn vmad_skyrim_layout() {
+  let vmad = Vmad::decode(sig(b"WEAP"), SKYRIM_VMAD).unwrap();
+  assert_eq!(vmad.version, 5);
+  assert_eq!(vmad.scripts[0].name, "MyQues");
+  let properties = &vmad.scripts[0].properties;
+  assert_eq!(
+    properties[0].value,
+    PropertyValue::Object(object(0x13BBF, -1))
+  );
+  assert_eq!(properties[1].value, PropertyValue::Int(42));
+  assert!(vmad.fragments.is_none());
+  assert_eq!(encode(&vmad), SKYRIM_VMAD);
+
+  // Edit a value and read it back from a record
+  let mut edited = vmad.clone();
+  edited.scripts[0].properties[1].value = PropertyValue::Int(7);
+  let record = Record::new(sig(b"WEAP"), 0x800.into(), vec![edited.to_field()]);
+  let decoded = Vmad::from_record(&record).unwrap().unwrap();
+  assert_eq!(decoded, edited);
+}
+
+#[test]
+fn vmad_property_types_round_trip() {
+  let properties = vec![
+    property("None", PropertyValue::None),
+    property("Name", PropertyValue::String("Lydia".to_string())),
+    property(
+      "Var",
+      PropertyValue::Variable(Box::new(PropertyValue::Int(5))),
+    ),
+    property(
+      "Vars",
+      PropertyValue::VariableArray(vec![
+        PropertyValue::String("b".into()),
+        PropertyValue::Object(object(0x14, -1)),
+        PropertyValue::None,
+      ]),
+    ),
+    property("Speed", PropertyValue::Float(1.5)),
+    property("Enabled", PropertyValue::Bool(true)),
+    property(
+      "Refs",
+      PropertyValue::ObjectArray(vec![object(0x14, -1), object(0, 3)]),
+    ),
+    property(
+      "Names",
+      PropertyValue::StringArray(vec!["a".into(), "".into()]),
+    ),
+    property("Ints", PropertyValue::IntArray(vec![-1, 0, 1])),
+    property("Floats", PropertyValue::FloatArray(vec![0.25])),
+    property("Flags", PropertyValue::BoolArray(vec![false, true])),
+    property(
+      "Entry",
+      PropertyValue::Struct(vec![property("Count", PropertyValue::Int(3))]),
+    ),
+    property(
+      "Entries",
+      PropertyValue::StructArray(vec![
+        vec![property("Item", PropertyValue::Object(object(0xF, -1)))],
+        vec![],
+      ]),
+    ),
+  ];
+  for (version, object_format) in [(6, 2), (5, 1), (3, 1)] {
+    let vmad = Vmad {
+      version,
+      object_format,
+      scripts: vec![script("Everything", properties.clone())],
+      fragments: None,
+    };
+    let bytes = encode(&vmad);
+    let decoded = Vmad::decode(sig(b"ARMO"), &bytes).unwrap();
+    assert_eq!(encode(&decoded), bytes);
+    if version >= 4 {
+      assert_eq!(decoded, vmad);
+    } else {
+      // Status bytes aren't stored before version 4
+      assert_eq!(decoded.scripts[0].properties[1].status, 0);
+    }
+  }
+
+  let mut unknown = encode(&Vmad {
+    version: 6,
+    object_format: 2,
+    scripts: vec![script("Var", vec![property("X", PropertyValue::Int(0))])],
+    fragments: None,
+  })
+  .to_vec();
+  // Change the property type to one that isn't used
+  unknown[4 + 2 + 2 + 3 + 1 + 2 + 2 + 1] = 8;
+  assert!(Vmad::decode(sig(b"ARMO"), &unknown).is_err());
+  assert!(Field::new(sig(b"VMAD"), unknown).decode::<Vmad>().is_err());
+}
+
+#[test]
+fn vmad_strings_keep_their_bytes() {
+  // "Caf\xE9 \x93x\x94" in Windows-1252, with 0x81 which it leaves undefined
+  let name = [b'C', b'a', b'f', 0xE9, b' ', 0x93, b'x', 0x94, 0x81];
+  let mut bytes = vec![0x05, 0x00, 0x02, 0x00, 0x01, 0x00, name.len() as u8, 0x00];
+  bytes.extend_from_slice(&name);
+  bytes.extend_from_slice(&[0x00, 0x00, 0x00]);
+  let vmad = Vmad::decode(sig(b"WEAP"), &bytes).unwrap();
+  assert_eq!(vmad.scripts[0].name, "Caf\u{E9} \u{201C}x\u{201D}\u{81}");
+  assert_eq!(encode(&vmad), bytes);
+}
+
+#[test]
+fn vmad_fragments_round_trip() {
+  let info = Vmad {
+    version: 5,
+    object_format: 2,
+    scripts: vec![],
+    fragments: Some(Fragments::Info(InfoFragments {
+      unknown: 2,
+      flags: 0b11,
+      script: FragmentScript::FileName("TIF__0001".to_string()),
+      fragments: vec![
+        Fragment {
+          unknown: 1,
+          script_name: "TIF__0001".to_string(),
+          fragment_name: "Fragment_0".to_string(),
+        },
+        Fragment {
+          unknown: 1,
+          script_name: "TIF__0001".to_string(),
+          fragment_name: "Fragment_1".to_string(),
+        },
+      ],
+    })),
+  };
+  let bytes = encode(&info);
+  assert_eq!(Vmad::decode(sig(b"INFO"), &bytes).unwrap(), info);
+
+  let quest = Vmad {
+    version: 6,
+    object_format: 2,
+    scripts: vec![script("QuestScript", vec![])],
+    fragments: Some(Fragments::Quest(QuestFragments {
+      unknown: 3,
+      script: FragmentScript::Script(script(
+        "Fragments:Quests:QF_MQ101",
+        vec![property(
+          "Alias_Player",
+          PropertyValue::Object(object(0, 0)),
+        )],
+      )),
+      stages: vec![StageFragment {
+        stage: 10,
+        unknown_1: 0,
+        log_entry: 0,
+        unknown_2: 1,
+        script_name: "Fragments:Quests:QF_MQ101".to_string(),
+        fragment_name: "Fragment_Stage_0010_Item_00".to_string(),
+      }],
+      aliases: vec![AliasScripts {
+        alias: object(0, 2),
+        version: 6,
+        object_format: 2,
+        scripts: vec![script(
+          "AliasScript",
+          vec![property("Count", PropertyValue::Int(1))],
+        )],
+      }],
+    })),
+  };
+  let bytes = encode(&quest);
+  assert_eq!(Vmad::decode(sig(b"QUST"), &bytes).unwrap(), quest);
+
+  // Fragments of other records, or that don't match their record, are kept as they are
+  for record in [sig(b"PERK"), sig(b"INFO")] {
+    let decoded = Vmad::decode(record, &bytes).unwrap();
+    assert!(matches!(decoded.fragments, Some(Fragments::Raw(_))));
+    assert_eq!(encode(&decoded), bytes);
+  }
+}
@@ -0,0 +1,125 @@
+use bytes::Bytes;
+
+use crate::{
+  group::Placement,
+  types::{FormID, Signature},
+  ESx, Field, Record,
+};
+
+const SAMPLE: &[u8] = &[
+  0x54, 0x45, 0x53, 0x34, // 'TES4' as bytes
+  0x00, 0x00, 0x00, 0x00, // Data field size in bytes (0)
+  0x00, 0x00, 0x00, 0x00, // Flags (None)
+  0x00, 0x00, 0x00, 0x00, // Form ID (0)
+  0x00, 0x00, // Timestamp (unset)
+  0x00, 0x00, // VCS (unset)
+  0x83, 0x00, // Internal version 131
+  0x00, 0x00, // Unknown u16
+];
+const WORLD: u32 = 0x0000_0D00;
+
+fn cell(form_id: u32, x: i32, y: i32) -> Record {
+  let mut grid = x.to_le_bytes().to_vec();
+  grid.extend_from_slice(&y.to_le_bytes());
+  let xclc = Field::new(Signature::new(b"XCLC"), grid);
+  Record::new(Signature::new(b"CELL"), form_id.into(), vec![xclc])
+}
+fn world_plugin() -> ESx {
+  let mut esx = ESx::from_bytes(&mut Bytes::from_static(SAMPLE)).unwrap();
+  let world = Record::new(Signature::new(b"WRLD"), WORLD.into(), vec![]);
+  esx.insert_record(world, Placement::Top).unwrap();
+  let persistent_cell = Record::new(Signature::new(b"CELL"), 0xD01.into(), vec![]);
+  let placement = Placement::PersistentCell {
+    world: WORLD.into(),
+  };
+  esx.insert_record(persistent_cell, placement).unwrap();
+
+  for (form_id, x, y) in [
+    (0xD10, 0, 0),
+    (0xD11, 1, 1),
+    (0xD12, -40, 5),
+    (0xD13, 33, 0),
+  ] {
+    let placement = Placement::ExteriorCell {
+      world: WORLD.into(),
+      x,
+      y,
+    };
+    esx.insert_record(cell(form_id, x, y), placement).unwrap();
+  }
+  // Persistent references belong to the persistent cell, wherever they stand
+  let refr = Record::new(Signature::new(b"REFR"), 0xD20.into(), vec![]);
+  let placement = Placement::WorldPersistentChild {
+    world: WORLD.into(),
+    cell: 0xD01.into(),
+  };
+  esx.insert_record(refr, placement).unwrap();
+  for form_id in [0xD21, 0xD22] {
+    let refr = Record::new(Signature::new(b"REFR"), form_id.into(), vec![]);
+    let placement = Placement::ExteriorCellChild {
+      world: WORLD.into(),
+      cell: 0xD11.into(),
+      x: 1,
+      y: 1,
+      persistent: false,
+    };
+    esx.insert_record(refr, placement).unwrap();
+  }
+  // Read back so everything comes from the file layout
+  let mut esx = ESx::from_bytes(&mut esx.as_bytes()).unwrap();
+  assert!(esx.process().is_empty());
+  esx
+}
+
+#[test]
+fn world_cells_by_grid() {
+  let esx = world_plugin();
+  let worlds = esx.get_worldspaces();
+  assert_eq!(worlds.len(), 1);
+  let world = esx.get_worldspace(WORLD.into()).unwrap();
+  assert_eq!(
+    world.get_persistent_cell().unwrap().get_form_id(),
+    0xD01.into()
+  );
+  assert_eq!(world.get_cells().len(), 4);
+
+  let cell = world.get_cell(-40, 5).unwrap();
+  assert_eq!(cell.get_form_id(), 0xD12.into());
+  assert_eq!(cell.get_grid(), Some((-40, 5)));
+  assert!(world.get_cell(2, 2).is_none());
+
+  let mut ids: Vec<FormID> = world
+    .get_cells_in((0, 0), (33, 1))
+    .iter()
+    .map(|c| c.get_form_id())
+    .collect();
+  ids.sort();
+  assert_eq!(ids, [0xD10.into(), 0xD11.into(), 0xD13.into()]);
+  assert!(world.get_cells_in((2, 2), (30, 30)).is_empty());
+}
+
+#[test]
+fn world_cell_references() {
+  let esx = world_plugin();
+  let world = esx.get_worldspace(WORLD.into()).unwrap();
+  let cell = world.get_cell(1, 1).unwrap();
+  let ids =
+    |records: Vec<&Record>| -> Vec<FormID> { records.iter().map(|r| *r.get_form_id()).collect() };
+  let persistent_cell = world.get_persistent_cell().unwrap();
+  assert_eq!(
+    ids(persistent_cell.get_persistent_references()),
+    [0xD20.into()]
+  );
+  assert!(cell.get_persistent_references().is_empty());
+  assert_eq!(
+    ids(cell.get_temporary_references()),
+    [0xD21.into(), 0xD22.into()]
+  );
+  let empty = esx
+    .get_worldspace(WORLD.into())
+    .unwrap()
+    .get_cell(0, 0)
+    .unwrap();
+  assert!(empty.get_temporary_references().is_empty());
+  assert!(esx.get_interior_cells().is_empty());
+}
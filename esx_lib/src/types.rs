@@ -1,9 +1,11 @@
 pub mod form_id;
+pub mod game;
 pub mod signature;
 pub mod timestamp;
 pub mod vcs_info;
 
-pub use form_id::FormID;
+pub use form_id::{FormID, FormIDLayout};
+pub use game::Game;
 pub use signature::Signature;
-pub use timestamp::Timestamp;
+pub use timestamp::{Timestamp, TimestampFormat};
 pub use vcs_info::VcsInfo;
@@ -1,15 +1,35 @@
-use std::fmt::{Debug, Display};
+use std::{
+  fmt::{Debug, Display},
+  str::FromStr,
+};
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
 
+use crate::Error;
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct FormID(u32);
 
+/// How a load-order FormID splits into a file slot and an object ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FormIDLayout {
+  /// `SSOOOOOO`: a full plugin in slot `SS`
+  Full,
+  /// `FDSSOOOO`: a medium master in slot `SS`
+  Medium,
+  /// `FESSSOOO`: a light (ESL) plugin in slot `SSS`
+  Light,
+}
+
 /// Constants
 impl FormID {
   pub const MAX_IDS: u32 = 0x00FFFFFF;
   pub const MAX_IDS_ESL: u32 = 0x00000FFF;
+  pub const MAX_IDS_MEDIUM: u32 = 0x0000FFFF;
+  pub const NULL: FormID = FormID(0);
+  /// FormIDs below this belong to the engine rather than any plugin
+  pub const HARDCODED_END: u32 = 0x00000800;
 }
 /// Conversion
 impl FormID {
@@ -22,6 +42,61 @@ impl FormID {
     bytes.freeze()
   }
 }
+/// Getters
+impl FormID {
+  pub fn raw(&self) -> u32 {
+    self.0
+  }
+  /// The index into the plugin's master list, or the plugin itself when it equals the master count
+  pub fn master_index(&self) -> u8 {
+    (self.0 >> 24) as u8
+  }
+  /// The ID of the object within the file given by [`FormID::master_index`]
+  pub fn object_id(&self) -> u32 {
+    self.0 & Self::MAX_IDS
+  }
+  pub fn is_null(&self) -> bool {
+    self.0 == 0
+  }
+  pub fn is_hardcoded(&self) -> bool {
+    !self.is_null() && self.0 < Self::HARDCODED_END
+  }
+  /// The layout of this FormID if it is a load-order FormID, decided by its top byte
+  pub fn layout(&self) -> FormIDLayout {
+    match self.master_index() {
+      0xFE => FormIDLayout::Light,
+      0xFD => FormIDLayout::Medium,
+      _ => FormIDLayout::Full,
+    }
+  }
+  /// The load order slot of this FormID under its [`FormID::layout`]
+  pub fn slot(&self) -> u16 {
+    match self.layout() {
+      FormIDLayout::Full => self.master_index() as u16,
+      FormIDLayout::Medium => ((self.0 >> 16) & 0xFF) as u16,
+      FormIDLayout::Light => ((self.0 >> 12) & 0xFFF) as u16,
+    }
+  }
+  /// The object ID within the slot of this FormID under its [`FormID::layout`]
+  pub fn slot_object_id(&self) -> u32 {
+    match self.layout() {
+      FormIDLayout::Full => self.object_id(),
+      FormIDLayout::Medium => self.0 & Self::MAX_IDS_MEDIUM,
+      FormIDLayout::Light => self.0 & Self::MAX_IDS_ESL,
+    }
+  }
+  /// Formats this FormID as `[Plugin.esm]:XXXXXX`, resolving the master index against `masters`,
+  /// with `plugin` standing for the file itself
+  pub fn display_with_masters<S: AsRef<str>>(&self, masters: &[S], plugin: &str) -> String {
+    let index = self.master_index() as usize;
+    let file = match masters.get(index) {
+      Some(master) => master.as_ref(),
+      None if index == masters.len() => plugin,
+      None => return format!("[{:02X}?]:{:06X}", index, self.object_id()),
+    };
+    format!("[{}]:{:06X}", file, self.object_id())
+  }
+}
 
 impl Debug for FormID {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -44,3 +119,20 @@ impl From<FormID> for u32 {
     val.0
   }
 }
+impl FromStr for FormID {
+  type Err = Error;
+
+  /// Parses a hexadecimal FormID, with or without a `0x` prefix
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let hex = s
+      .strip_prefix("0x")
+      .or_else(|| s.strip_prefix("0X"))
+      .unwrap_or(s);
+    match hex.is_empty() || hex.starts_with('+') {
+      true => Err(Error::InvalidFormID(s.into())),
+      false => u32::from_str_radix(hex, 16)
+        .map(Self)
+        .map_err(|_| Error::InvalidFormID(s.into())),
+    }
+  }
+}
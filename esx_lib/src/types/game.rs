@@ -0,0 +1,70 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Game {
+  Oblivion,
+  Fallout3,
+  FalloutNV,
+  Skyrim,
+  SkyrimSE,
+  Fallout4,
+  Fallout76,
+  Starfield,
+}
+/// Constants
+impl Game {
+  pub const ALL: [Game; 8] = [
+    Game::Oblivion,
+    Game::Fallout3,
+    Game::FalloutNV,
+    Game::Skyrim,
+    Game::SkyrimSE,
+    Game::Fallout4,
+    Game::Fallout76,
+    Game::Starfield,
+  ];
+}
+/// Conversion
+impl Game {
+  /// Guesses the game from a header record's form version and the version in its HEDR field.
+  /// Oblivion, Fallout 3 and Fallout: New Vegas headers share form versions below 40, so only the
+  /// HEDR version (0.8 or 1.0, 0.94, and 1.32 to 1.34) tells them apart.
+  pub fn from_header(form_version: u16, hedr_version: Option<f32>) -> Option<Game> {
+    let hundredths = hedr_version.map(|v| (v * 100.0).round() as i32);
+    match (form_version, hundredths) {
+      (0..=39, Some(80 | 100)) => Some(Game::Oblivion),
+      (0..=39, Some(94)) => Some(Game::Fallout3),
+      (0..=39, Some(132..=134)) => Some(Game::FalloutNV),
+      _ => Game::from_form_version(form_version),
+    }
+  }
+  /// Guesses the game from a header record's form version, where it is unambiguous
+  pub fn from_form_version(form_version: u16) -> Option<Game> {
+    match form_version {
+      40..=43 => Some(Game::Skyrim),
+      44 => Some(Game::SkyrimSE),
+      131 => Some(Game::Fallout4),
+      132..=499 => Some(Game::Fallout76),
+      500.. => Some(Game::Starfield),
+      _ => None,
+    }
+  }
+}
+
+impl Display for Game {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let name = match self {
+      Game::Oblivion => "The Elder Scrolls IV: Oblivion",
+      Game::Fallout3 => "Fallout 3",
+      Game::FalloutNV => "Fallout: New Vegas",
+      Game::Skyrim => "The Elder Scrolls V: Skyrim",
+      Game::SkyrimSE => "The Elder Scrolls V: Skyrim Special Edition",
+      Game::Fallout4 => "Fallout 4",
+      Game::Fallout76 => "Fallout 76",
+      Game::Starfield => "Starfield",
+    };
+    write!(f, "{}", name)
+  }
+}
@@ -1,11 +1,20 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
+use crate::Error;
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Signature([u8; 4]);
 
+/// Constants
+impl Signature {
+  pub const TES4: Signature = Signature::new(b"TES4");
+  pub const GRUP: Signature = Signature::new(b"GRUP");
+  pub const XXXX: Signature = Signature::new(b"XXXX");
+}
+
 impl PartialOrd for Signature {
   fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
     Some(self.cmp(other))
@@ -14,15 +23,18 @@ impl PartialOrd for Signature {
 
 impl Ord for Signature {
   fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-    self.as_string().cmp(&other.as_string())
+    self.0.cmp(&other.0)
   }
 }
 
 /// Conversion
 impl Signature {
-  pub fn new(sig: &[u8; 4]) -> Self {
+  pub const fn new(sig: &[u8; 4]) -> Self {
     Signature(*sig)
   }
+  pub const fn from_array(sig: [u8; 4]) -> Self {
+    Signature(sig)
+  }
   pub fn from_bytes(bytes: Bytes) -> Self {
     let mut sig = [0; 4];
     sig.copy_from_slice(&bytes);
@@ -40,6 +52,9 @@ impl Signature {
   pub fn as_slice(&self) -> &[u8] {
     self.0.as_slice()
   }
+  pub const fn as_array(&self) -> &[u8; 4] {
+    &self.0
+  }
 }
 
 impl std::fmt::Debug for Signature {
@@ -68,3 +83,29 @@ impl From<Signature> for String {
     String::from_utf8_lossy(&val.0).into_owned()
   }
 }
+impl FromStr for Signature {
+  type Err = Error;
+
+  /// Parses four ASCII characters, or the `(xx)IAD` form used when displaying `_IAD` signatures
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if let Some(hex) = s.strip_prefix('(').and_then(|s| s.strip_suffix(")IAD")) {
+      let imad_val = u8::from_str_radix(hex, 16).map_err(|_| Error::InvalidSignature(s.into()))?;
+      return Ok(Signature([imad_val, b'I', b'A', b'D']));
+    }
+    let sig: [u8; 4] = s
+      .as_bytes()
+      .try_into()
+      .map_err(|_| Error::InvalidSignature(s.into()))?;
+    match sig.is_ascii() {
+      true => Ok(Signature(sig)),
+      false => Err(Error::InvalidSignature(s.into())),
+    }
+  }
+}
+impl TryFrom<&str> for Signature {
+  type Error = Error;
+
+  fn try_from(s: &str) -> Result<Self, Self::Error> {
+    s.parse()
+  }
+}
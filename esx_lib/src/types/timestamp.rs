@@ -1,32 +1,111 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
-pub struct Timestamp {
-  year: u8,
-  month: u8,
-  day: u8,
+use super::Game;
+
+/// The 16-bit date stamp found in record and group headers. The raw value is kept so it always
+/// round-trips; use [`Timestamp::to_ymd`] to read it as a date for a specific game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash, Default)]
+pub struct Timestamp(u16);
+
+/// How a game packs a date into a [`Timestamp`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimestampFormat {
+  /// Low byte is the day of the month, high byte is the number of months since December of the
+  /// year before `epoch_year` (1 is January of `epoch_year`)
+  MonthCount { epoch_year: i32 },
+  /// Bits 0-4 are the day, bits 5-8 the month and bits 9-15 the years since `epoch_year`
+  BitPacked { epoch_year: i32 },
 }
+
 /// Constants
 impl Timestamp {
   pub const YEAR_MASK: u16 = 0b1111111000000000;
   pub const MONTH_MASK: u16 = 0b0000000111100000;
   pub const DAY_MASK: u16 = 0b0000000000011111;
 }
+impl TimestampFormat {
+  /// The format of a game's stamps. Fallout 3, New Vegas and Skyrim count months from 2003, and
+  /// Skyrim Special Edition onwards pack the date into bits from 2000, as xEdit reads them
+  /// (`wbVCI1ToStrBeforeFO4` and `wbVCI1ToStrAfterFO4`). Oblivion's stamp is version control
+  /// data without a known date layout, so it has none and never reads as a date.
+  pub fn for_game(game: Game) -> Option<Self> {
+    match game {
+      Game::Oblivion => None,
+      Game::Fallout3 | Game::FalloutNV | Game::Skyrim => {
+        Some(TimestampFormat::MonthCount { epoch_year: 2003 })
+      }
+      Game::SkyrimSE | Game::Fallout4 | Game::Fallout76 | Game::Starfield => {
+        Some(TimestampFormat::BitPacked { epoch_year: 2000 })
+      }
+    }
+  }
+}
+/// Conversion
+impl Timestamp {
+  pub fn raw(&self) -> u16 {
+    self.0
+  }
+  /// Decode into `(year, month, day)`, or `None` if the stamp is unset, not a valid date or the
+  /// game's stamps aren't dates
+  pub fn to_ymd(&self, game: Game) -> Option<(i32, u32, u32)> {
+    let (year, month, day) = match TimestampFormat::for_game(game)? {
+      TimestampFormat::MonthCount { epoch_year } => {
+        let months = (self.0 >> 8) as i32;
+        if months == 0 {
+          return None;
+        }
+        let year = epoch_year + (months - 1) / 12;
+        let month = (months - 1) % 12 + 1;
+        (year, month as u32, (self.0 & 0x00FF) as u32)
+      }
+      TimestampFormat::BitPacked { epoch_year } => (
+        epoch_year + ((self.0 & Self::YEAR_MASK) >> 9) as i32,
+        ((self.0 & Self::MONTH_MASK) >> 5) as u32,
+        (self.0 & Self::DAY_MASK) as u32,
+      ),
+    };
+    match (1..=12).contains(&month) && (1..=31).contains(&day) {
+      true => Some((year, month, day)),
+      false => None,
+    }
+  }
+  /// Encode a date, or `None` if the game's format cannot represent it
+  pub fn from_ymd(year: i32, month: u32, day: u32, game: Game) -> Option<Self> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+      return None;
+    }
+    match TimestampFormat::for_game(game)? {
+      TimestampFormat::MonthCount { epoch_year } => {
+        let months = (year - epoch_year) * 12 + month as i32;
+        let months = u8::try_from(months).ok().filter(|m| *m != 0)?;
+        Some(Self(((months as u16) << 8) | day as u16))
+      }
+      TimestampFormat::BitPacked { epoch_year } => {
+        let years = u16::try_from(year - epoch_year).ok().filter(|y| *y <= 0x7F)?;
+        Some(Self((years << 9) | ((month as u16) << 5) | day as u16))
+      }
+    }
+  }
+}
+#[cfg(feature = "chrono")]
+impl Timestamp {
+  pub fn to_date(&self, game: Game) -> Option<chrono::NaiveDate> {
+    let (year, month, day) = self.to_ymd(game)?;
+    chrono::NaiveDate::from_ymd_opt(year, month, day)
+  }
+  pub fn from_date(date: chrono::NaiveDate, game: Game) -> Option<Self> {
+    use chrono::Datelike;
+    Self::from_ymd(date.year(), date.month(), date.day(), game)
+  }
+}
 
 impl From<u16> for Timestamp {
   fn from(val: u16) -> Self {
-    Self {
-      year: ((val & Self::YEAR_MASK) >> 9) as u8,
-      month: ((val & Self::MONTH_MASK) >> 5) as u8,
-      day: (val & Self::DAY_MASK) as u8,
-    }
+    Self(val)
   }
 }
 impl From<Timestamp> for u16 {
   fn from(val: Timestamp) -> u16 {
-    let year = ((val.year as u16) << 9) & Timestamp::YEAR_MASK;
-    let month = ((val.month as u16) << 5) & Timestamp::MONTH_MASK;
-    let day = val.day as u16 & Timestamp::DAY_MASK;
-    year + month + day
+    val.0
   }
 }
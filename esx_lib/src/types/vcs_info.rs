@@ -1,13 +1,20 @@
 use bytes::{Buf, BufMut, BytesMut};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+/// Creation Kit version control user IDs. Stored little-endian, so the last user is the low byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash, Default)]
 pub struct VcsInfo {
   last_user: u8,
   current_user: u8,
 }
 
 impl VcsInfo {
+  pub fn new(last_user: u8, current_user: u8) -> Self {
+    Self {
+      last_user,
+      current_user,
+    }
+  }
   pub fn from_bytes(buf: &mut BytesMut) -> Self {
     Self {
       last_user: buf.get_u8(),
@@ -21,19 +28,31 @@ impl VcsInfo {
     bytes
   }
 }
+/// Getters
+impl VcsInfo {
+  pub fn last_user(&self) -> u8 {
+    self.last_user
+  }
+  pub fn current_user(&self) -> u8 {
+    self.current_user
+  }
+  pub fn is_empty(&self) -> bool {
+    self.last_user == 0 && self.current_user == 0
+  }
+}
 
 impl From<u16> for VcsInfo {
   fn from(val: u16) -> Self {
     Self {
-      last_user: ((val & 0x00FF) >> 8) as u8,
-      current_user: (val & 0xFF00) as u8,
+      last_user: (val & 0x00FF) as u8,
+      current_user: (val >> 8) as u8,
     }
   }
 }
 impl From<VcsInfo> for u16 {
   fn from(val: VcsInfo) -> u16 {
-    let last_user = ((val.last_user as u16) << 8) & 0x00FF;
-    let current_user = val.current_user as u16 & 0xFF00;
-    last_user + current_user
+    let last_user = val.last_user as u16;
+    let current_user = (val.current_user as u16) << 8;
+    last_user | current_user
   }
 }
@@ -0,0 +1,98 @@
+//! Reading and stripping the Creation Kit version control data kept in record and group headers
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  diagnostics::PathSegment,
+  group::GroupDataComponent,
+  types::{Timestamp, VcsInfo},
+  Diagnostic, ESx, Group, Record,
+};
+
+/// Version control data from a single record or group header
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub struct VcsEntry {
+  pub path: Vec<PathSegment>,
+  pub timestamp: Timestamp,
+  pub vcs_info: VcsInfo,
+  /// The header word following the form version for records, or the last header word for groups
+  pub unknown: u32,
+}
+impl VcsEntry {
+  /// Whether any user ID or unknown word is set
+  pub fn has_data(&self) -> bool {
+    !self.vcs_info.is_empty() || self.unknown != 0
+  }
+}
+
+impl ESx {
+  /// Version control data for the header and every group and record, in file order. Groups that
+  /// have not been processed are read from a copy of their data; the contents of groups that
+  /// can't be read are left out.
+  pub fn get_vcs_entries(&self) -> Vec<VcsEntry> {
+    let mut entries: Vec<VcsEntry> = vec![record_entry(self.get_header_record(), &[])];
+    for group in self.get_top_groups() {
+      group_entries(group, &[], &mut entries);
+    }
+    entries
+  }
+  /// Zero the user IDs and unknown header words of the header and every group and record,
+  /// structuring groups that have not been processed. Contents that can't be read are left
+  /// unchanged and reported. Timestamps are left alone; see [`ESx::clear_timestamps`].
+  pub fn clear_vcs_info(&mut self) -> Vec<Diagnostic> {
+    clear_record(self.get_header_record_mut());
+    let mut diagnostics = vec![];
+    for group in self.get_top_groups_mut() {
+      diagnostics.append(&mut group.visit_mut(&mut clear_group, &mut clear_record));
+    }
+    diagnostics
+  }
+}
+
+fn record_entry(record: &Record, parents: &[PathSegment]) -> VcsEntry {
+  let mut path = parents.to_vec();
+  path.push(record.path_segment());
+  VcsEntry {
+    path,
+    timestamp: *record.get_timestamp(),
+    vcs_info: *record.get_vcs_info(),
+    unknown: *record.get_unknown() as u32,
+  }
+}
+fn group_entries(group: &Group, parents: &[PathSegment], entries: &mut Vec<VcsEntry>) {
+  let mut path = parents.to_vec();
+  path.push(group.path_segment());
+  entries.push(VcsEntry {
+    path: path.clone(),
+    timestamp: *group.get_timestamp(),
+    vcs_info: *group.get_vcs_info(),
+    unknown: *group.get_unknown(),
+  });
+  let read;
+  let components = match group.get_data().get_components() {
+    Some(components) => components,
+    None => match group.get_data().read_components() {
+      Ok(components) => {
+        read = components;
+        &read
+      }
+      Err(_) => return,
+    },
+  };
+  for component in components {
+    match component {
+      GroupDataComponent::Group(g) => group_entries(g, &path, entries),
+      GroupDataComponent::Record(r) => entries.push(record_entry(r, &path)),
+      _ => {}
+    }
+  }
+}
+
+fn clear_record(record: &mut Record) {
+  record.set_vcs_info(VcsInfo::default());
+  record.set_unknown(0);
+}
+fn clear_group(group: &mut Group) {
+  group.set_vcs_info(VcsInfo::default());
+  group.set_unknown(0);
+}
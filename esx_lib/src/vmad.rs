@@ -0,0 +1,630 @@
+//! Papyrus script attachments, stored in VMAD fields.
+//!
+//! A VMAD field lists the scripts attached to a record with their property values, followed by
+//! script fragments for some record types. Quest and dialogue response fragments are decoded;
+//! fragments of other records, and any that can't be decoded, are kept as raw bytes so that a
+//! decoded field always encodes back to the same bytes.
+//!
+//! Versions 4 and later store a status byte for every script and property. Version 6 (Fallout 4)
+//! adds structs and variables, and stores a whole script, rather than a file name, for
+//! fragments.
+//!
+//! Strings are Windows-1252, as the games write them, so that any bytes decode and encode back
+//! unchanged.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  codec::FieldCodec,
+  types::{FormID, Signature},
+  Error, Field, Record, Result,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vmad {
+  pub version: i16,
+  pub object_format: i16,
+  pub scripts: Vec<Script>,
+  pub fragments: Option<Fragments>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Script {
+  pub name: String,
+  /// Local, inherited or removed; only stored from version 4
+  pub status: u8,
+  pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Property {
+  pub name: String,
+  /// Edited, removed and similar flags; only stored from version 4
+  pub status: u8,
+  pub value: PropertyValue,
+}
+
+/// A reference to a form, or to an alias of the quest the script is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectRef {
+  pub form_id: FormID,
+  /// The alias ID, or -1 for a plain form
+  pub alias: i16,
+  pub unused: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PropertyValue {
+  None,
+  Object(ObjectRef),
+  String(String),
+  Int(i32),
+  Float(f32),
+  Bool(bool),
+  /// A `Var`, stored as the type of the value it holds followed by the value
+  Variable(Box<PropertyValue>),
+  /// The members of a struct, stored like properties
+  Struct(Vec<Property>),
+  ObjectArray(Vec<ObjectRef>),
+  StringArray(Vec<String>),
+  IntArray(Vec<i32>),
+  FloatArray(Vec<f32>),
+  BoolArray(Vec<bool>),
+  VariableArray(Vec<PropertyValue>),
+  StructArray(Vec<Vec<Property>>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Fragments {
+  Info(InfoFragments),
+  Quest(QuestFragments),
+  /// Fragments that aren't decoded, kept as read
+  Raw(Bytes),
+}
+
+/// The script holding a record's fragments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FragmentScript {
+  /// The script's file name, before version 6
+  FileName(String),
+  Script(Script),
+}
+
+/// The begin and end fragments of a dialogue response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InfoFragments {
+  pub unknown: u8,
+  /// Bit 0 for a begin fragment and bit 1 for an end fragment, one fragment per bit in order
+  pub flags: u8,
+  pub script: FragmentScript,
+  pub fragments: Vec<Fragment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fragment {
+  pub unknown: u8,
+  pub script_name: String,
+  pub fragment_name: String,
+}
+
+/// Quest stage fragments and the scripts attached to quest aliases
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestFragments {
+  pub unknown: u8,
+  pub script: FragmentScript,
+  pub stages: Vec<StageFragment>,
+  pub aliases: Vec<AliasScripts>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageFragment {
+  pub stage: u16,
+  pub unknown_1: i16,
+  pub log_entry: i32,
+  pub unknown_2: u8,
+  pub script_name: String,
+  pub fragment_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AliasScripts {
+  pub alias: ObjectRef,
+  pub version: i16,
+  pub object_format: i16,
+  pub scripts: Vec<Script>,
+}
+
+/// Conversion
+impl Vmad {
+  /// Decodes a VMAD field of a record with this signature, which decides how its fragments are
+  /// read
+  pub fn decode(record: Signature, data: &[u8]) -> Result<Self> {
+    let mut reader = Reader::new(data);
+    let version = reader.i16()?;
+    let object_format = reader.i16()?;
+    let format = Format {
+      version,
+      object_format,
+    };
+    let scripts = format.scripts(&mut reader)?;
+
+    let rest = reader.rest();
+    let fragments = match rest.is_empty() {
+      true => None,
+      false => Some(Fragments::decode(record, format, rest)),
+    };
+    Ok(Vmad {
+      version,
+      object_format,
+      scripts,
+      fragments,
+    })
+  }
+  pub fn encode(&self, buf: &mut BytesMut) {
+    let format = Format {
+      version: self.version,
+      object_format: self.object_format,
+    };
+    buf.put_i16_le(self.version);
+    buf.put_i16_le(self.object_format);
+    format.put_scripts(buf, &self.scripts);
+    if let Some(fragments) = &self.fragments {
+      fragments.encode(format, buf)
+    }
+  }
+  /// Decodes the record's VMAD field, if it has one
+  pub fn from_record(record: &Record) -> Result<Option<Self>> {
+    let signature = Signature::new(b"VMAD");
+    let fields = record.read_fields()?;
+    match fields.iter().find(|f| *f.get_signature() == signature) {
+      Some(field) => Self::decode(*record.get_signature(), field.get_data().as_slice()).map(Some),
+      None => Ok(None),
+    }
+  }
+  pub fn to_field(&self) -> Field {
+    let mut buf = BytesMut::new();
+    self.encode(&mut buf);
+    Field::new(Signature::new(b"VMAD"), buf.freeze())
+  }
+}
+/// Decodes without knowing the record, so any fragments are kept raw
+impl FieldCodec for Vmad {
+  fn decode(data: &[u8]) -> Result<(Self, usize)> {
+    Vmad::decode(Signature::new(b"\0\0\0\0"), data).map(|v| (v, data.len()))
+  }
+  fn encode(&self, buf: &mut BytesMut) {
+    Vmad::encode(self, buf)
+  }
+}
+
+impl Fragments {
+  /// Reads the fragments of quests and dialogue responses, keeping anything else, or anything
+  /// that doesn't read exactly, as raw bytes
+  fn decode(record: Signature, format: Format, data: &[u8]) -> Self {
+    let mut reader = Reader::new(data);
+    let decoded = match record.as_array() {
+      b"INFO" => format.info_fragments(&mut reader).map(Fragments::Info),
+      b"QUST" => format.quest_fragments(&mut reader).map(Fragments::Quest),
+      _ => Err(Error::BufferTooShort),
+    };
+    match decoded {
+      Ok(fragments) if reader.rest().is_empty() => fragments,
+      _ => Fragments::Raw(Bytes::copy_from_slice(data)),
+    }
+  }
+  fn encode(&self, format: Format, buf: &mut BytesMut) {
+    match self {
+      Fragments::Info(info) => {
+        buf.put_u8(info.unknown);
+        buf.put_u8(info.flags);
+        format.put_fragment_script(buf, &info.script);
+        for fragment in &info.fragments {
+          buf.put_u8(fragment.unknown);
+          put_wstring(buf, &fragment.script_name);
+          put_wstring(buf, &fragment.fragment_name);
+        }
+      }
+      Fragments::Quest(quest) => {
+        buf.put_u8(quest.unknown);
+        buf.put_u16_le(quest.stages.len() as u16);
+        format.put_fragment_script(buf, &quest.script);
+        for stage in &quest.stages {
+          buf.put_u16_le(stage.stage);
+          buf.put_i16_le(stage.unknown_1);
+          buf.put_i32_le(stage.log_entry);
+          buf.put_u8(stage.unknown_2);
+          put_wstring(buf, &stage.script_name);
+          put_wstring(buf, &stage.fragment_name);
+        }
+        buf.put_u16_le(quest.aliases.len() as u16);
+        for alias in &quest.aliases {
+          format.put_object(buf, &alias.alias);
+          buf.put_i16_le(alias.version);
+          buf.put_i16_le(alias.object_format);
+          let alias_format = Format {
+            version: alias.version,
+            object_format: alias.object_format,
+          };
+          alias_format.put_scripts(buf, &alias.scripts);
+        }
+      }
+      Fragments::Raw(b) => buf.put_slice(b),
+    }
+  }
+}
+
+/// The version and object format a VMAD field is read and written with
+#[derive(Debug, Clone, Copy)]
+struct Format {
+  version: i16,
+  object_format: i16,
+}
+impl Format {
+  fn has_status(&self) -> bool {
+    self.version >= 4
+  }
+
+  fn scripts(&self, reader: &mut Reader) -> Result<Vec<Script>> {
+    let count = reader.u16()?;
+    (0..count).map(|_| self.script(reader)).collect()
+  }
+  fn script(&self, reader: &mut Reader) -> Result<Script> {
+    let name = reader.wstring()?;
+    let status = self.status(reader)?;
+    let count = reader.u16()?;
+    let properties = (0..count)
+      .map(|_| self.property(reader))
+      .collect::<Result<_>>()?;
+    Ok(Script {
+      name,
+      status,
+      properties,
+    })
+  }
+  fn status(&self, reader: &mut Reader) -> Result<u8> {
+    match self.has_status() {
+      true => reader.u8(),
+      false => Ok(0),
+    }
+  }
+  fn property(&self, reader: &mut Reader) -> Result<Property> {
+    let name = reader.wstring()?;
+    let property_type = reader.u8()?;
+    let status = self.status(reader)?;
+    let value = self.value(reader, property_type)?;
+    Ok(Property {
+      name,
+      status,
+      value,
+    })
+  }
+  fn value(&self, reader: &mut Reader, property_type: u8) -> Result<PropertyValue> {
+    Ok(match property_type {
+      0 => PropertyValue::None,
+      1 => PropertyValue::Object(self.object(reader)?),
+      2 => PropertyValue::String(reader.wstring()?),
+      3 => PropertyValue::Int(reader.i32()?),
+      4 => PropertyValue::Float(reader.f32()?),
+      5 => PropertyValue::Bool(reader.bool()?),
+      6 => PropertyValue::Variable(Box::new(self.variable(reader)?)),
+      7 => PropertyValue::Struct(self.members(reader)?),
+      11 => PropertyValue::ObjectArray(reader.array(|r| self.object(r))?),
+      12 => PropertyValue::StringArray(reader.array(Reader::wstring)?),
+      13 => PropertyValue::IntArray(reader.array(Reader::i32)?),
+      14 => PropertyValue::FloatArray(reader.array(Reader::f32)?),
+      15 => PropertyValue::BoolArray(reader.array(Reader::bool)?),
+      16 => PropertyValue::VariableArray(reader.array(|r| self.variable(r))?),
+      17 => PropertyValue::StructArray(reader.array(|r| self.members(r))?),
+      t => return Err(Error::UnknownPropertyType(t)),
+    })
+  }
+  fn variable(&self, reader: &mut Reader) -> Result<PropertyValue> {
+    let property_type = reader.u8()?;
+    self.value(reader, property_type)
+  }
+  fn members(&self, reader: &mut Reader) -> Result<Vec<Property>> {
+    let count = reader.u32()?;
+    (0..count).map(|_| self.property(reader)).collect()
+  }
+  fn object(&self, reader: &mut Reader) -> Result<ObjectRef> {
+    match self.object_format {
+      1 => {
+        let form_id = reader.u32()?.into();
+        let alias = reader.i16()?;
+        let unused = reader.u16()?;
+        Ok(ObjectRef {
+          form_id,
+          alias,
+          unused,
+        })
+      }
+      _ => {
+        let unused = reader.u16()?;
+        let alias = reader.i16()?;
+        let form_id = reader.u32()?.into();
+        Ok(ObjectRef {
+          form_id,
+          alias,
+          unused,
+        })
+      }
+    }
+  }
+  fn fragment_script(&self, reader: &mut Reader) -> Result<FragmentScript> {
+    match self.version >= 6 {
+      true => self.script(reader).map(FragmentScript::Script),
+      false => reader.wstring().map(FragmentScript::FileName),
+    }
+  }
+  fn info_fragments(&self, reader: &mut Reader) -> Result<InfoFragments> {
+    let unknown = reader.u8()?;
+    let flags = reader.u8()?;
+    let script = self.fragment_script(reader)?;
+    let fragments = (0..(flags & 0b11).count_ones())
+      .map(|_| {
+        Ok(Fragment {
+          unknown: reader.u8()?,
+          script_name: reader.wstring()?,
+          fragment_name: reader.wstring()?,
+        })
+      })
+      .collect::<Result<_>>()?;
+    Ok(InfoFragments {
+      unknown,
+      flags,
+      script,
+      fragments,
+    })
+  }
+  fn quest_fragments(&self, reader: &mut Reader) -> Result<QuestFragments> {
+    let unknown = reader.u8()?;
+    let count = reader.u16()?;
+    let script = self.fragment_script(reader)?;
+    let stages = (0..count)
+      .map(|_| {
+        Ok(StageFragment {
+          stage: reader.u16()?,
+          unknown_1: reader.i16()?,
+          log_entry: reader.i32()?,
+          unknown_2: reader.u8()?,
+          script_name: reader.wstring()?,
+          fragment_name: reader.wstring()?,
+        })
+      })
+      .collect::<Result<_>>()?;
+    let count = reader.u16()?;
+    let aliases = (0..count)
+      .map(|_| {
+        let alias = self.object(reader)?;
+        let version = reader.i16()?;
+        let object_format = reader.i16()?;
+        let format = Format {
+          version,
+          object_format,
+        };
+        Ok(AliasScripts {
+          alias,
+          version,
+          object_format,
+          scripts: format.scripts(reader)?,
+        })
+      })
+      .collect::<Result<_>>()?;
+    Ok(QuestFragments {
+      unknown,
+      script,
+      stages,
+      aliases,
+    })
+  }
+
+  fn put_scripts(&self, buf: &mut BytesMut, scripts: &[Script]) {
+    buf.put_u16_le(scripts.len() as u16);
+    for script in scripts {
+      self.put_script(buf, script);
+    }
+  }
+  fn put_script(&self, buf: &mut BytesMut, script: &Script) {
+    put_wstring(buf, &script.name);
+    if self.has_status() {
+      buf.put_u8(script.status);
+    }
+    buf.put_u16_le(script.properties.len() as u16);
+    for property in &script.properties {
+      self.put_property(buf, property);
+    }
+  }
+  fn put_property(&self, buf: &mut BytesMut, property: &Property) {
+    put_wstring(buf, &property.name);
+    buf.put_u8(property.value.property_type());
+    if self.has_status() {
+      buf.put_u8(property.status);
+    }
+    self.put_value(buf, &property.value);
+  }
+  fn put_value(&self, buf: &mut BytesMut, value: &PropertyValue) {
+    match value {
+      PropertyValue::None => {}
+      PropertyValue::Object(o) => self.put_object(buf, o),
+      PropertyValue::String(s) => put_wstring(buf, s),
+      PropertyValue::Int(v) => buf.put_i32_le(*v),
+      PropertyValue::Float(v) => buf.put_f32_le(*v),
+      PropertyValue::Bool(v) => buf.put_u8(*v as u8),
+      PropertyValue::Variable(v) => self.put_variable(buf, v),
+      PropertyValue::Struct(members) => self.put_members(buf, members),
+      PropertyValue::ObjectArray(v) => {
+        buf.put_u32_le(v.len() as u32);
+        v.iter().for_each(|o| self.put_object(buf, o));
+      }
+      PropertyValue::StringArray(v) => {
+        buf.put_u32_le(v.len() as u32);
+        v.iter().for_each(|s| put_wstring(buf, s));
+      }
+      PropertyValue::IntArray(v) => {
+        buf.put_u32_le(v.len() as u32);
+        v.iter().for_each(|i| buf.put_i32_le(*i));
+      }
+      PropertyValue::FloatArray(v) => {
+        buf.put_u32_le(v.len() as u32);
+        v.iter().for_each(|f| buf.put_f32_le(*f));
+      }
+      PropertyValue::BoolArray(v) => {
+        buf.put_u32_le(v.len() as u32);
+        v.iter().for_each(|b| buf.put_u8(*b as u8));
+      }
+      PropertyValue::VariableArray(v) => {
+        buf.put_u32_le(v.len() as u32);
+        v.iter().for_each(|value| self.put_variable(buf, value));
+      }
+      PropertyValue::StructArray(v) => {
+        buf.put_u32_le(v.len() as u32);
+        v.iter().for_each(|members| self.put_members(buf, members));
+      }
+    }
+  }
+  fn put_variable(&self, buf: &mut BytesMut, value: &PropertyValue) {
+    buf.put_u8(value.property_type());
+    self.put_value(buf, value);
+  }
+  fn put_members(&self, buf: &mut BytesMut, members: &[Property]) {
+    buf.put_u32_le(members.len() as u32);
+    for member in members {
+      self.put_property(buf, member);
+    }
+  }
+  fn put_object(&self, buf: &mut BytesMut, object: &ObjectRef) {
+    match self.object_format {
+      1 => {
+        buf.put_u32_le(object.form_id.into());
+        buf.put_i16_le(object.alias);
+        buf.put_u16_le(object.unused);
+      }
+      _ => {
+        buf.put_u16_le(object.unused);
+        buf.put_i16_le(object.alias);
+        buf.put_u32_le(object.form_id.into());
+      }
+    }
+  }
+  fn put_fragment_script(&self, buf: &mut BytesMut, script: &FragmentScript) {
+    match script {
+      FragmentScript::FileName(name) => put_wstring(buf, name),
+      FragmentScript::Script(script) => self.put_script(buf, script),
+    }
+  }
+}
+
+/// Getters
+impl PropertyValue {
+  /// The type stored before the value
+  pub fn property_type(&self) -> u8 {
+    match self {
+      PropertyValue::None => 0,
+      PropertyValue::Object(_) => 1,
+      PropertyValue::String(_) => 2,
+      PropertyValue::Int(_) => 3,
+      PropertyValue::Float(_) => 4,
+      PropertyValue::Bool(_) => 5,
+      PropertyValue::Variable(_) => 6,
+      PropertyValue::Struct(_) => 7,
+      PropertyValue::ObjectArray(_) => 11,
+      PropertyValue::StringArray(_) => 12,
+      PropertyValue::IntArray(_) => 13,
+      PropertyValue::FloatArray(_) => 14,
+      PropertyValue::BoolArray(_) => 15,
+      PropertyValue::VariableArray(_) => 16,
+      PropertyValue::StructArray(_) => 17,
+    }
+  }
+}
+
+/// Reads little-endian values from the front of a VMAD field
+struct Reader<'a> {
+  data: &'a [u8],
+}
+impl<'a> Reader<'a> {
+  fn new(data: &'a [u8]) -> Self {
+    Reader { data }
+  }
+  fn rest(&self) -> &'a [u8] {
+    self.data
+  }
+  fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+    if self.data.len() < N {
+      return Err(Error::BufferTooShort);
+    }
+    let (bytes, rest) = self.data.split_at(N);
+    self.data = rest;
+    Ok(bytes.try_into()?)
+  }
+  fn u8(&mut self) -> Result<u8> {
+    self.take().map(u8::from_le_bytes)
+  }
+  fn u16(&mut self) -> Result<u16> {
+    self.take().map(u16::from_le_bytes)
+  }
+  fn i16(&mut self) -> Result<i16> {
+    self.take().map(i16::from_le_bytes)
+  }
+  fn u32(&mut self) -> Result<u32> {
+    self.take().map(u32::from_le_bytes)
+  }
+  fn i32(&mut self) -> Result<i32> {
+    self.take().map(i32::from_le_bytes)
+  }
+  fn f32(&mut self) -> Result<f32> {
+    self.take().map(f32::from_le_bytes)
+  }
+  fn bool(&mut self) -> Result<bool> {
+    match self.u8()? {
+      0 => Ok(false),
+      1 => Ok(true),
+      v => Err(Error::InvalidBool(v)),
+    }
+  }
+  /// A Windows-1252 string preceded by its u16 length, without a terminator
+  fn wstring(&mut self) -> Result<String> {
+    let len = self.u16()? as usize;
+    if self.data.len() < len {
+      return Err(Error::BufferTooShort);
+    }
+    let (bytes, rest) = self.data.split_at(len);
+    self.data = rest;
+    Ok(bytes.iter().map(|b| decode_cp1252(*b)).collect())
+  }
+  fn array<T>(&mut self, mut read: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+    let count = self.u32()?;
+    (0..count).map(|_| read(self)).collect()
+  }
+}
+/// Characters outside Windows-1252 are written as `?`
+fn put_wstring(buf: &mut BytesMut, s: &str) {
+  let bytes: Vec<u8> = s.chars().map(encode_cp1252).collect();
+  buf.put_u16_le(bytes.len() as u16);
+  buf.put_slice(&bytes);
+}
+
+/// The characters of Windows-1252 bytes 0x80 to 0x9F. The five bytes it leaves undefined map to
+/// the control characters of the same value, so every byte decodes.
+const CP1252_HIGH: [char; 32] = [
+  '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+  '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}', '\u{90}',
+  '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}', '\u{2DC}',
+  '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+fn decode_cp1252(byte: u8) -> char {
+  match byte {
+    0x80..=0x9F => CP1252_HIGH[(byte - 0x80) as usize],
+    _ => byte as char,
+  }
+}
+fn encode_cp1252(c: char) -> u8 {
+  match CP1252_HIGH.iter().position(|h| *h == c) {
+    Some(i) => 0x80 + i as u8,
+    None => match u8::try_from(c) {
+      Ok(byte) if !(0x80..=0x9F).contains(&byte) => byte,
+      _ => b'?',
+    },
+  }
+}
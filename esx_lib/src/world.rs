@@ -0,0 +1,192 @@
+//! Navigation of worldspaces and cells by location.
+//!
+//! Only groups that have been processed are visible, but cell records are read on demand so
+//! their grid coordinates are available without processing every record.
+
+use crate::{
+  group::GroupLabel,
+  types::{FormID, Signature},
+  ESx, Group, Record,
+};
+
+/// A WRLD record and the group of its children
+#[derive(Debug, Clone, Copy)]
+pub struct Worldspace<'a> {
+  record: &'a Record,
+  children: Option<&'a Group>,
+}
+/// Getters
+impl<'a> Worldspace<'a> {
+  pub fn get_record(&self) -> &'a Record {
+    self.record
+  }
+  pub fn get_form_id(&self) -> FormID {
+    *self.record.get_form_id()
+  }
+  pub fn get_children(&self) -> Option<&'a Group> {
+    self.children
+  }
+  /// The cell holding the worldspace's persistent references, which sits outside the blocks
+  pub fn get_persistent_cell(&self) -> Option<Cell<'a>> {
+    let children = self.children?;
+    cells_in(children).into_iter().next()
+  }
+  /// Every exterior cell, block by block
+  pub fn get_cells(&self) -> Vec<Cell<'a>> {
+    let Some(children) = self.children else {
+      return vec![];
+    };
+    children
+      .get_data()
+      .get_subgroups()
+      .into_iter()
+      .flat_map(|block| block.get_data().get_subgroups())
+      .flat_map(cells_in)
+      .collect()
+  }
+  /// The exterior cell at grid coordinates, found through its block and sub-block
+  pub fn get_cell(&self, x: i32, y: i32) -> Option<Cell<'a>> {
+    let (block, sub_block) = GroupLabel::exterior_cell_blocks(x, y);
+    let block = find_subgroup(self.children?, block)?;
+    let sub_block = find_subgroup(block, sub_block)?;
+    cells_in(sub_block)
+      .into_iter()
+      .find(|cell| cell.get_grid() == Some((x, y)))
+  }
+  /// The exterior cells inside a rectangle of grid coordinates, bounds included. Only the blocks
+  /// overlapping the rectangle are searched.
+  pub fn get_cells_in(&self, min: (i32, i32), max: (i32, i32)) -> Vec<Cell<'a>> {
+    let Some(children) = self.children else {
+      return vec![];
+    };
+    let (min_block, min_sub_block) = GroupLabel::exterior_cell_blocks(min.0, min.1);
+    let (max_block, max_sub_block) = GroupLabel::exterior_cell_blocks(max.0, max.1);
+    let overlaps = |label: &GroupLabel, min: &GroupLabel, max: &GroupLabel| match (
+      label_grid(label),
+      label_grid(min),
+      label_grid(max),
+    ) {
+      (Some((x, y)), Some(min), Some(max)) => {
+        (min.0..=max.0).contains(&x) && (min.1..=max.1).contains(&y)
+      }
+      _ => false,
+    };
+    children
+      .get_data()
+      .get_subgroups()
+      .into_iter()
+      .filter(|block| overlaps(block.get_label(), &min_block, &max_block))
+      .flat_map(|block| block.get_data().get_subgroups())
+      .filter(|sub_block| overlaps(sub_block.get_label(), &min_sub_block, &max_sub_block))
+      .flat_map(cells_in)
+      .filter(|cell| match cell.get_grid() {
+        Some((x, y)) => (min.0..=max.0).contains(&x) && (min.1..=max.1).contains(&y),
+        None => false,
+      })
+      .collect()
+  }
+}
+
+/// A CELL record and the group of its children
+#[derive(Debug, Clone, Copy)]
+pub struct Cell<'a> {
+  record: &'a Record,
+  children: Option<&'a Group>,
+}
+/// Getters
+impl<'a> Cell<'a> {
+  pub fn get_record(&self) -> &'a Record {
+    self.record
+  }
+  pub fn get_form_id(&self) -> FormID {
+    *self.record.get_form_id()
+  }
+  pub fn get_children(&self) -> Option<&'a Group> {
+    self.children
+  }
+  /// The grid coordinates from the cell's XCLC field, which interior cells don't have
+  pub fn get_grid(&self) -> Option<(i32, i32)> {
+    let xclc = self.record.read_field(Signature::new(b"XCLC"))?;
+    let bytes = xclc.get_data().as_slice();
+    Some((
+      i32::from_le_bytes(bytes.get(0..4)?.try_into().ok()?),
+      i32::from_le_bytes(bytes.get(4..8)?.try_into().ok()?),
+    ))
+  }
+  /// References, actors and other records that are always loaded with the cell
+  pub fn get_persistent_references(&self) -> Vec<&'a Record> {
+    self.get_references(GroupLabel::CellPersistentChildren(self.get_form_id()))
+  }
+  /// References, navmeshes, landscape and other records loaded with the cell when it is attached
+  pub fn get_temporary_references(&self) -> Vec<&'a Record> {
+    self.get_references(GroupLabel::CellTemporaryChildren(self.get_form_id()))
+  }
+  fn get_references(&self, label: GroupLabel) -> Vec<&'a Record> {
+    self
+      .children
+      .and_then(|children| find_subgroup(children, label))
+      .map_or(vec![], |group| group.get_data().get_records())
+  }
+}
+
+/// Getters
+impl ESx {
+  /// Every worldspace in the WRLD top group
+  pub fn get_worldspaces(&self) -> Vec<Worldspace<'_>> {
+    self
+      .get_top_group(Signature::new(b"WRLD"))
+      .map_or(vec![], |group| {
+        group
+          .get_data()
+          .get_records_with_children()
+          .into_iter()
+          .map(|(record, children)| Worldspace { record, children })
+          .collect()
+      })
+  }
+  pub fn get_worldspace(&self, form_id: FormID) -> Option<Worldspace<'_>> {
+    self
+      .get_worldspaces()
+      .into_iter()
+      .find(|world| world.get_form_id() == form_id)
+  }
+  /// Every interior cell, block by block
+  pub fn get_interior_cells(&self) -> Vec<Cell<'_>> {
+    let Some(group) = self.get_top_group(Signature::new(b"CELL")) else {
+      return vec![];
+    };
+    group
+      .get_data()
+      .get_subgroups()
+      .into_iter()
+      .flat_map(|block| block.get_data().get_subgroups())
+      .flat_map(cells_in)
+      .collect()
+  }
+}
+
+/// The cells in a group, each with its children
+fn cells_in(group: &Group) -> Vec<Cell<'_>> {
+  group
+    .get_data()
+    .get_records_with_children()
+    .into_iter()
+    .filter(|(record, _)| *record.get_signature() == Signature::new(b"CELL"))
+    .map(|(record, children)| Cell { record, children })
+    .collect()
+}
+fn find_subgroup(group: &Group, label: GroupLabel) -> Option<&Group> {
+  group
+    .get_data()
+    .get_subgroups()
+    .into_iter()
+    .find(|g| *g.get_label() == label)
+}
+fn label_grid(label: &GroupLabel) -> Option<(i32, i32)> {
+  match *label {
+    GroupLabel::ExteriorCellBlock { x, y } | GroupLabel::ExteriorCellSubBlock { x, y } => {
+      Some((x as i32, y as i32))
+    }
+    _ => None,
+  }
+}
@@ -1,7 +1,7 @@
 use eframe::{egui, App, Frame};
 
 use crate::{
-  panels::{FileTreePanel, InfoPanel, Panel, TopPanel},
+  panels::{DiagnosticsPanel, FileTreePanel, InfoPanel, Panel, QueryPanel, TopPanel},
   service::ServiceController,
 };
 
@@ -9,7 +9,9 @@ pub struct ESXView {
   service: ServiceController,
   top_panel: TopPanel,
   file_tree_panel: FileTreePanel,
+  diagnostics_panel: DiagnosticsPanel,
   info_panel: InfoPanel,
+  query_panel: QueryPanel,
 }
 
 impl Default for ESXView {
@@ -19,7 +21,9 @@ impl Default for ESXView {
       service: service.clone(),
       top_panel: TopPanel::new(service.clone()),
       file_tree_panel: FileTreePanel::new(service.clone()),
+      diagnostics_panel: DiagnosticsPanel::new(service.clone()),
       info_panel: InfoPanel::new(service.clone()),
+      query_panel: QueryPanel::new(service.clone()),
     }
   }
 }
@@ -32,6 +36,8 @@ impl App for ESXView {
 
     self.top_panel.draw(ctx);
     self.file_tree_panel.draw(ctx);
+    self.diagnostics_panel.draw(ctx);
+    self.query_panel.draw(ctx);
     self.info_panel.draw(ctx);
 
     let Self {
@@ -1,10 +1,11 @@
-use esx_lib::ESx;
+use esx_lib::{Diagnostic, ESx};
 use std::{ops::Deref, path::PathBuf};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ESxFile {
   file_path: PathBuf,
   esx: Box<ESx>,
+  diagnostics: Vec<Diagnostic>,
 }
 
 impl Deref for ESxFile {
@@ -15,9 +16,13 @@ impl Deref for ESxFile {
 }
 
 impl ESxFile {
-  pub fn new(file_path: PathBuf, esx: ESx) -> Self {
+  pub fn new(file_path: PathBuf, esx: ESx, diagnostics: Vec<Diagnostic>) -> Self {
     let esx = Box::new(esx);
-    ESxFile { esx, file_path }
+    ESxFile {
+      esx,
+      file_path,
+      diagnostics,
+    }
   }
 }
 impl ESxFile {
@@ -27,6 +32,9 @@ impl ESxFile {
   pub fn esx(&self) -> &ESx {
     &self.esx
   }
+  pub fn diagnostics(&self) -> &Vec<Diagnostic> {
+    &self.diagnostics
+  }
 }
 impl ESxFile {
   pub fn file_name(&self) -> String {
@@ -1,10 +1,14 @@
 use eframe::egui;
 
+mod diagnostics_panel;
 mod file_tree_panel;
 mod info_panel;
+mod query_panel;
 mod top_panel;
+pub use diagnostics_panel::DiagnosticsPanel;
 pub use file_tree_panel::FileTreePanel;
 pub use info_panel::InfoPanel;
+pub use query_panel::QueryPanel;
 pub use top_panel::TopPanel;
 
 pub trait Panel {
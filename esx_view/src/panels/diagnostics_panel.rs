@@ -0,0 +1,76 @@
+use eframe::egui::{self, TopBottomPanel};
+use egui_extras::{Column, TableBuilder};
+
+use crate::service::ServiceController;
+
+#[derive(Default)]
+pub struct DiagnosticsPanel {
+  service: ServiceController,
+}
+impl DiagnosticsPanel {
+  pub fn new(service: ServiceController) -> DiagnosticsPanel {
+    DiagnosticsPanel { service }
+  }
+}
+
+impl super::Panel for DiagnosticsPanel {
+  fn draw(&mut self, ctx: &egui::Context) {
+    let file = match self.service.get_active_file() {
+      Some(index) => self.service.get_esx_file(index),
+      None => None,
+    };
+    let Some(file) = file else {
+      return;
+    };
+    let diagnostics = file.diagnostics();
+    if diagnostics.is_empty() {
+      return;
+    }
+
+    TopBottomPanel::bottom("diagnostics_panel")
+      .resizable(true)
+      .show(ctx, |ui| {
+        ui.strong(format!("Problems ({})", diagnostics.len()));
+        let mut table = TableBuilder::new(ui);
+        table = table.resizable(true).auto_shrink(false);
+        table = table
+          .column(Column::initial(60.0).range(20.0..=100.0))
+          .column(Column::initial(300.0).range(20.0..=800.0).clip(true))
+          .column(Column::remainder());
+
+        let header = |mut header: egui_extras::TableRow<'_, '_>| {
+          header.col(|ui| {
+            ui.strong("Severity");
+          });
+          header.col(|ui| {
+            ui.strong("Path");
+          });
+          header.col(|ui| {
+            ui.strong("Message");
+          });
+        };
+        let body = |mut body: egui_extras::TableBody<'_>| {
+          for diagnostic in diagnostics {
+            let path: Vec<String> = diagnostic
+              .get_path()
+              .iter()
+              .map(|segment| segment.to_string())
+              .collect();
+            body.row(20.0, |mut row| {
+              row.col(|ui| {
+                ui.label(diagnostic.get_severity().to_string());
+              });
+              row.col(|ui| {
+                ui.label(path.join(" > "));
+              });
+              row.col(|ui| {
+                ui.label(diagnostic.get_message());
+              });
+            });
+          }
+        };
+
+        table.header(20.0, header).body(body);
+      });
+  }
+}
@@ -1,5 +1,6 @@
 use eframe::egui::{self, CentralPanel, ScrollArea};
 use egui_extras::{Column, TableBuilder};
+use esx_lib::{group::GroupLabel, registry};
 
 use crate::{files::ESxFile, service::ServiceController};
 
@@ -27,14 +28,18 @@ impl super::Panel for InfoPanel {
             let header = file.get_header_record();
             let form_version = header.get_form_version();
             let record_count = file.get_all_records().len();
+            let game = file.detect_game();
             ui.heading(file.file_name());
             ui.label(format!("Form Version: {}", form_version));
             ui.label(format!("Record Count: {}", record_count));
+            if let Some(game) = game {
+              ui.label(format!("Game: {}", game));
+            }
             let mut table = TableBuilder::new(ui);
             table = table.resizable(true).auto_shrink(false);
             table = table.sense(egui::Sense::click());
             table = table
-              .column(Column::initial(80.0).range(20.0..=200.0).clip(true))
+              .column(Column::initial(200.0).range(20.0..=200.0).clip(true))
               .column(Column::remainder());
             let header = |mut header: egui_extras::TableRow<'_, '_>| {
               header.col(|ui| {
@@ -47,8 +52,21 @@ impl super::Panel for InfoPanel {
             let body = |mut body: egui_extras::TableBody<'_>| {
               for group in file.get_top_groups() {
                 body.row(20.0, |mut row| {
-                  row.col(|ui| {
-                    ui.label(group.get_label().to_string());
+                  row.col(|ui| match group.get_label() {
+                    GroupLabel::Top(signature) => {
+                      let known = match game {
+                        Some(game) => registry::is_known_record(game, *signature),
+                        None => registry::record_info_any(*signature).is_some(),
+                      };
+                      let text = registry::describe_record(game, *signature);
+                      match known {
+                        true => ui.label(text),
+                        false => ui.colored_label(ui.visuals().warn_fg_color, text),
+                      };
+                    }
+                    label => {
+                      ui.label(label.to_string());
+                    }
                   });
                   row.col(|ui| {
                     ui.label(group.get_data().get_records_recurse().len().to_string());
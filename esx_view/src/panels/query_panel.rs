@@ -0,0 +1,113 @@
+use eframe::egui::{self, Key, SidePanel};
+use egui_extras::{Column, TableBuilder};
+use esx_lib::{query::Query, types::Signature};
+
+use crate::service::ServiceController;
+
+#[derive(Default)]
+pub struct QueryPanel {
+  service: ServiceController,
+  query: String,
+  results: Vec<[String; 3]>,
+  error: Option<String>,
+}
+impl QueryPanel {
+  pub fn new(service: ServiceController) -> QueryPanel {
+    QueryPanel {
+      service,
+      ..Default::default()
+    }
+  }
+}
+
+impl QueryPanel {
+  fn run(&mut self) {
+    self.results.clear();
+    self.error = None;
+    let file = match self.service.get_active_file() {
+      Some(index) => self.service.get_esx_file(index),
+      None => None,
+    };
+    let Some(file) = file else {
+      self.error = Some("No file selected".to_string());
+      return;
+    };
+    let query = match Query::parse(&self.query) {
+      Ok(query) => query,
+      Err(e) => {
+        self.error = Some(format!("{:?}", e));
+        return;
+      }
+    };
+    self.results = file
+      .query(&query)
+      .into_iter()
+      .map(|record| {
+        let editor_id = record
+          .read_field(Signature::new(b"EDID"))
+          .map(|f| f.get_zstring())
+          .unwrap_or_default();
+        [
+          record.get_signature().to_string(),
+          record.get_form_id().to_string(),
+          editor_id,
+        ]
+      })
+      .collect();
+  }
+}
+
+impl super::Panel for QueryPanel {
+  fn draw(&mut self, ctx: &egui::Context) {
+    SidePanel::right("query_panel")
+      .resizable(true)
+      .show(ctx, |ui| {
+        ui.strong("Query");
+        let mut run = false;
+        ui.horizontal(|ui| {
+          let response = ui.text_edit_singleline(&mut self.query);
+          run |= response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+          run |= ui.button("Run").clicked();
+        });
+        if run {
+          self.run();
+        }
+        if let Some(error) = &self.error {
+          ui.colored_label(ui.visuals().error_fg_color, error);
+          return;
+        }
+        ui.label(format!("{} records", self.results.len()));
+
+        let mut table = TableBuilder::new(ui);
+        table = table.resizable(true).auto_shrink(false);
+        table = table
+          .column(Column::initial(50.0).range(20.0..=100.0))
+          .column(Column::initial(80.0).range(20.0..=150.0))
+          .column(Column::remainder());
+
+        let header = |mut header: egui_extras::TableRow<'_, '_>| {
+          header.col(|ui| {
+            ui.strong("Type");
+          });
+          header.col(|ui| {
+            ui.strong("FormID");
+          });
+          header.col(|ui| {
+            ui.strong("Editor ID");
+          });
+        };
+        let body = |body: egui_extras::TableBody<'_>| {
+          body.rows(20.0, self.results.len(), |mut row| {
+            let result = &self.results[row.index()];
+            for text in result {
+              row.col(|ui| {
+                ui.label(text);
+              });
+            }
+          });
+        };
+
+        table.header(20.0, header).body(body);
+      });
+  }
+}
@@ -18,8 +18,8 @@ impl ESxLoader {
   pub fn load_file(&mut self, path: &PathBuf) -> Result<()> {
     let file = File::open(path)?;
     let mut esx = ESx::from_file(&file)?;
-    esx.process();
-    let file = Rc::new(ESxFile::new(path.clone(), esx));
+    let diagnostics = esx.process();
+    let file = Rc::new(ESxFile::new(path.clone(), esx, diagnostics));
     self.esx_list.push(file);
     Ok(())
   }